@@ -5,12 +5,15 @@
 //! AI types (MCTS, Neural, Random, etc.) in the future.
 
 use super::{NegamaxPlayer, Difficulty};
-use super::search::iterative_deepening_search;
+use super::limits::SearchLimits;
+use super::search_handle::BackgroundSearcher;
+use super::searcher::Searcher;
 use crate::game_repr::{Color, Move, Position};
 use crate::board::Board;
 use crate::agent::player::Player;
 use std::sync::Arc;
 use std::cell::RefCell;
+use std::fmt;
 
 /// Enumeration of available AI algorithms
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -84,25 +87,22 @@ impl AIType {
     /// Generate a move directly without creating a Player instance
     ///
     /// This is useful for AIvAI mode where we don't need persistent Player objects.
-    /// The search is performed on the given position and returns the best move.
+    /// The search is performed on the given position with `searcher`, which
+    /// keeps its tables for the next call, using `threads` search threads,
+    /// and returns the best move.
     pub fn generate_move(
         &self,
         position: &Position,
         color: Color,
         difficulty: Difficulty,
+        threads: usize,
+        searcher: &mut Searcher,
     ) -> Option<Move> {
         match self {
             AIType::Negamax => {
-                let max_depth = difficulty.max_depth();
-                let time_limit_ms = difficulty.time_limit_ms();
-
-                let result = iterative_deepening_search(
-                    position,
-                    color,
-                    max_depth,
-                    time_limit_ms,
-                );
-
+                debug_assert_eq!(color, position.side_to_move);
+                let limits = SearchLimits { threads, ..difficulty.search_limits() };
+                let result = searcher.search(position, limits, &mut |_| {});
                 result.best_move
             }
         }
@@ -112,24 +112,82 @@ impl AIType {
 /// Configuration for a single AI player
 ///
 /// This stores all settings needed to create or invoke an AI player,
-/// including the algorithm type and difficulty level.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// including the algorithm type and difficulty level, plus the search state
+/// kept between the moves it generates.
+#[derive(Clone)]
 pub struct AIConfig {
     /// The AI algorithm to use
     pub ai_type: AIType,
     /// The difficulty/strength level
     pub difficulty: Difficulty,
+    /// Search threads (None = the difficulty's default)
+    pub threads: Option<usize>,
+    searcher: BackgroundSearcher,
 }
 
 impl AIConfig {
     /// Create a new AI configuration
     pub fn new(ai_type: AIType, difficulty: Difficulty) -> Self {
-        Self { ai_type, difficulty }
+        Self { ai_type, difficulty, threads: None, searcher: BackgroundSearcher::new() }
+    }
+
+    /// Use `threads` search threads instead of the difficulty's default
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Search threads used for this configuration's moves
+    pub fn threads(&self) -> usize {
+        self.threads.unwrap_or_else(|| self.difficulty.threads())
+    }
+
+    /// Generate a move using this configuration, blocking until it is found
+    pub fn generate_move(&mut self, position: &Position, color: Color) -> Option<Move> {
+        let threads = self.threads();
+        self.ai_type.generate_move(position, color, self.difficulty, threads, self.searcher.searcher_mut())
+    }
+
+    /// Generate a move in the background without blocking
+    ///
+    /// The first call starts a search of `position`, reached through the
+    /// positions with Zobrist keys `history`; later calls return `None` while
+    /// [`is_thinking`](Self::is_thinking) is true and the move once the search
+    /// has finished. A search whose worker thread died finishes without a
+    /// move.
+    pub fn poll_move(&mut self, position: &Position, history: &[u64]) -> Option<Move> {
+        match self.ai_type {
+            AIType::Negamax => {
+                // Only built when a search starts, not on every poll
+                let (threads, difficulty) = (self.threads(), self.difficulty);
+                let limits = || SearchLimits {
+                    threads,
+                    history: history.to_vec(),
+                    ..difficulty.search_limits()
+                };
+                self.searcher.poll_move(position, limits)?.best_move
+            }
+        }
+    }
+
+    /// Whether a background search is running
+    pub fn is_thinking(&self) -> bool {
+        self.searcher.is_thinking()
+    }
+
+    /// Make the background search finish with the best move found so far
+    pub fn stop_thinking(&self) {
+        self.searcher.stop();
+    }
+
+    /// Abandon the background search, e.g. when the move is taken back
+    pub fn cancel(&mut self) {
+        self.searcher.cancel();
     }
 
-    /// Generate a move using this configuration
-    pub fn generate_move(&self, position: &Position, color: Color) -> Option<Move> {
-        self.ai_type.generate_move(position, color, self.difficulty)
+    /// Forget the search state of the previous game
+    pub fn new_game(&mut self) {
+        self.searcher.new_game();
     }
 
     /// Create a Player instance from this configuration
@@ -145,10 +203,27 @@ impl AIConfig {
 
 impl Default for AIConfig {
     fn default() -> Self {
-        Self {
-            ai_type: AIType::default(),
-            difficulty: AIType::default().default_difficulty(),
-        }
+        Self::new(AIType::default(), AIType::default().default_difficulty())
+    }
+}
+
+/// Two configurations are equal when they play the same way; search state is
+/// not compared
+impl PartialEq for AIConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.ai_type == other.ai_type && self.difficulty == other.difficulty && self.threads == other.threads
+    }
+}
+
+impl Eq for AIConfig {}
+
+impl fmt::Debug for AIConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AIConfig")
+            .field("ai_type", &self.ai_type)
+            .field("difficulty", &self.difficulty)
+            .field("threads", &self.threads)
+            .finish_non_exhaustive()
     }
 }
 
@@ -195,4 +270,31 @@ mod tests {
         let config = AIConfig::new(AIType::Negamax, Difficulty::Hard);
         assert_eq!(config.display_string(), "Negamax (Hard)");
     }
+
+    #[test]
+    fn test_ai_config_threads() {
+        let config = AIConfig::new(AIType::Negamax, Difficulty::Hard);
+        assert_eq!(config.threads(), Difficulty::Hard.threads());
+
+        let config = config.with_threads(2);
+        assert_eq!(config.threads(), 2);
+        assert_ne!(config, AIConfig::new(AIType::Negamax, Difficulty::Hard));
+    }
+
+    #[test]
+    fn test_ai_config_poll_move() {
+        let position = Position::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - -");
+        let mut config = AIConfig::new(AIType::Negamax, Difficulty::Easy);
+        assert!(!config.is_thinking());
+
+        let mv = loop {
+            if let Some(mv) = config.poll_move(&position, &[]) {
+                break mv;
+            }
+            assert!(config.is_thinking());
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        };
+        assert_eq!(Some(mv), position.parse_san("Ra8").ok());
+        assert!(!config.is_thinking());
+    }
 }
@@ -636,7 +636,7 @@ mod tests {
 
     #[test]
     fn test_perspective_matters() {
-        let pos = Position::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNRQ");
+        let pos = Position::from_fen("rnbqkbnr/pppppppp/8/8/8/8/QPPPPPPP/RNBQKBNR");
         let score_white = evaluate(&pos, Color::White);
         let score_black = evaluate(&pos, Color::Black);
         // Scores should be opposite
@@ -0,0 +1,353 @@
+// Search Limits and Control
+//
+// A search ends when it reaches its maximum depth, runs out of time (a fixed
+// budget or the hard limit of a TimeManager), has searched its node budget,
+// or is told to stop from another thread (the UCI `stop` command).
+// SearchLimits describes those conditions; SearchControl is threaded through
+// negamax and quiescence to count nodes and notice when one of them is hit.
+//
+// SearchControl also keeps the Zobrist keys of the game so far and of the
+// current search path, so negamax can spot repetitions. A position repeated
+// once within the search counts as a draw (the side that could avoid the
+// repetition would already have done so), while one only repeated in the
+// game before the root needs two earlier occurrences, as in the rules.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use super::pv_table::PvTable;
+use super::searcher::SearchConfig;
+use super::time_manager::{Clock, TimeManager};
+
+// Use web-time for WASM compatibility (std::time::Instant doesn't work reliably in WASM)
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+/// Deepest iteration a search will start
+pub const MAX_SEARCH_DEPTH: u8 = 64;
+
+/// How often (in nodes) the clock and stop flag are checked
+const CHECK_INTERVAL: u64 = 1024;
+
+/// Conditions that end a search
+#[derive(Debug, Clone)]
+pub struct SearchLimits {
+    /// Maximum iterative deepening depth
+    pub max_depth: u8,
+    /// Fixed time budget in milliseconds
+    pub time_limit_ms: Option<u64>,
+    /// Clock-based soft and hard limits
+    pub time_manager: Option<TimeManager>,
+    /// Where elapsed time is read from
+    pub clock: Clock,
+    /// Node budget (negamax and quiescence nodes)
+    pub node_limit: Option<u64>,
+    /// Set from another thread to end the search early
+    pub stop: Option<Arc<AtomicBool>>,
+    /// Search threads; more than one runs a Lazy SMP search
+    pub threads: usize,
+    /// Best lines to search and report (MultiPV)
+    pub multi_pv: usize,
+    /// Zobrist keys of the positions played before the root, oldest first,
+    /// for repetition detection
+    pub history: Vec<u64>,
+}
+
+impl SearchLimits {
+    /// Search to `max_depth` with no other limit
+    pub fn depth(max_depth: u8) -> Self {
+        Self {
+            max_depth,
+            time_limit_ms: None,
+            time_manager: None,
+            clock: Clock::Wall,
+            node_limit: None,
+            stop: None,
+            threads: 1,
+            multi_pv: 1,
+            history: Vec::new(),
+        }
+    }
+}
+
+impl Default for SearchLimits {
+    fn default() -> Self {
+        Self::depth(MAX_SEARCH_DEPTH)
+    }
+}
+
+/// Node counter and stop detection for a running search
+pub struct SearchControl {
+    start_time: Instant,
+    limits: SearchLimits,
+    /// Nodes visited so far
+    pub nodes: u64,
+    /// Node count shared by the threads of a Lazy SMP search
+    shared_nodes: Option<Arc<AtomicU64>>,
+    /// Part of `nodes` already added to `shared_nodes`
+    flushed_nodes: u64,
+    /// Distance from the root, maintained around make/unmake
+    pub ply: i32,
+    /// Set once a limit is hit; every node then returns immediately
+    pub aborted: bool,
+    /// Search features in use, and contempt
+    pub config: SearchConfig,
+    /// Best line below every ply of the search path
+    pub pv: PvTable,
+    /// Game history, then the root and the positions of the search path
+    keys: Vec<u64>,
+    /// Index of the root in `keys`
+    root_index: usize,
+    /// Index in `keys` of the position after the latest null move on the
+    /// path; no repetition reaches back past a null move
+    null_index: Option<usize>,
+}
+
+impl SearchControl {
+    pub fn new(mut limits: SearchLimits) -> Self {
+        let keys = std::mem::take(&mut limits.history);
+        let root_index = keys.len();
+        Self {
+            start_time: Instant::now(),
+            limits,
+            nodes: 0,
+            shared_nodes: None,
+            flushed_nodes: 0,
+            ply: 0,
+            aborted: false,
+            config: SearchConfig::default(),
+            pv: PvTable::new(),
+            keys,
+            root_index,
+            null_index: None,
+        }
+    }
+
+    /// A control that never stops the search
+    #[cfg(test)]
+    pub fn unlimited() -> Self {
+        Self::new(SearchLimits::default())
+    }
+
+    /// Counts this search's nodes in `counter` as well, which the node
+    /// limit then applies to
+    pub fn share_nodes(&mut self, counter: Arc<AtomicU64>) {
+        self.shared_nodes = Some(counter);
+    }
+
+    /// Nodes visited by every thread sharing this search's node count
+    pub fn total_nodes(&mut self) -> u64 {
+        let Some(shared) = &self.shared_nodes else {
+            return self.nodes;
+        };
+        let unflushed = self.nodes - self.flushed_nodes;
+        self.flushed_nodes = self.nodes;
+        shared.fetch_add(unflushed, Ordering::Relaxed) + unflushed
+    }
+
+    pub fn max_depth(&self) -> u8 {
+        self.limits.max_depth.clamp(1, MAX_SEARCH_DEPTH)
+    }
+
+    pub fn multi_pv(&self) -> usize {
+        self.limits.multi_pv.max(1)
+    }
+
+    /// Records the root position, before the search starts
+    pub fn set_root(&mut self, key: u64) {
+        self.keys.truncate(self.root_index);
+        self.keys.push(key);
+    }
+
+    /// Steps into the position with Zobrist key `key`, after a move
+    pub fn enter(&mut self, key: u64) {
+        self.ply += 1;
+        self.keys.push(key);
+    }
+
+    /// Steps back out of the position last entered
+    pub fn leave(&mut self) {
+        self.ply -= 1;
+        self.keys.pop();
+    }
+
+    /// Steps into the position with key `key` after a null move, returning
+    /// what [`leave_null`](Self::leave_null) needs to step back out
+    pub fn enter_null(&mut self, key: u64) -> Option<usize> {
+        let previous = self.null_index.replace(self.keys.len());
+        self.enter(key);
+        previous
+    }
+
+    /// Steps back out of a position entered by [`enter_null`](Self::enter_null)
+    pub fn leave_null(&mut self, previous: Option<usize>) {
+        self.leave();
+        self.null_index = previous;
+    }
+
+    /// Whether the position last entered, `halfmove_clock` plies after the
+    /// last capture or pawn move, is a draw by repetition
+    pub fn is_repetition(&self, halfmove_clock: u32) -> bool {
+        let Some(&key) = self.keys.last() else {
+            return false;
+        };
+        let last = self.keys.len() - 1;
+        // Captures and pawn moves cannot be undone, so no earlier position recurs
+        let oldest = last.saturating_sub(halfmove_clock as usize).max(self.null_index.unwrap_or(0));
+
+        // Positions with the same side to move are two plies apart
+        let mut earlier_in_game = 0;
+        for index in (oldest..last.saturating_sub(1)).rev().step_by(2) {
+            if self.keys[index] == key {
+                if index >= self.root_index {
+                    return true;
+                }
+                earlier_in_game += 1;
+                if earlier_in_game == 2 {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Score of a draw for the side to move, after contempt
+    pub fn draw_score(&self) -> i32 {
+        let contempt = self.config.contempt;
+        if self.ply % 2 == 0 { -contempt } else { contempt }
+    }
+
+    /// Milliseconds since the search started
+    pub fn elapsed_ms(&self) -> u64 {
+        match self.limits.clock {
+            Clock::Wall => self.start_time.elapsed().as_millis() as u64,
+            Clock::Nodes { nodes_per_ms } => self.nodes / nodes_per_ms.max(1),
+        }
+    }
+
+    /// Time after which the search stops, if any
+    fn hard_limit_ms(&self) -> Option<u64> {
+        let managed = self.limits.time_manager.map(|tm| tm.hard_ms());
+        match (self.limits.time_limit_ms, managed) {
+            (Some(fixed), Some(managed)) => Some(fixed.min(managed)),
+            (fixed, managed) => fixed.or(managed),
+        }
+    }
+
+    /// Whether the time manager leaves room for another iteration, the last
+    /// one having taken `last_iteration_ms` with the best move changing
+    /// `best_move_changes` times recently
+    pub fn start_iteration(&self, last_iteration_ms: u64, best_move_changes: u32) -> bool {
+        self.limits
+            .time_manager
+            .is_none_or(|tm| tm.start_iteration(self.elapsed_ms(), last_iteration_ms, best_move_changes))
+    }
+
+    /// Counts a node and reports whether the search must stop
+    ///
+    /// The node limit is checked on every node; the clock, stop flag and
+    /// shared node count only every [`CHECK_INTERVAL`] nodes.
+    #[inline]
+    pub fn visit(&mut self) -> bool {
+        if self.aborted {
+            return true;
+        }
+        self.nodes += 1;
+        if self.limits.node_limit.is_some_and(|limit| self.nodes >= limit) {
+            self.aborted = true;
+        } else if self.nodes.is_multiple_of(CHECK_INTERVAL) {
+            self.check();
+        }
+        self.aborted
+    }
+
+    /// Checks the clock, stop flag and shared node count, returning whether
+    /// the search must stop
+    pub fn check(&mut self) -> bool {
+        let shared_limit_hit = match self.limits.node_limit {
+            Some(limit) if self.shared_nodes.is_some() => self.total_nodes() >= limit,
+            _ => false,
+        };
+        if shared_limit_hit
+            || self.limits.stop.as_ref().is_some_and(|stop| stop.load(Ordering::Relaxed))
+            || self.hard_limit_ms().is_some_and(|limit| self.elapsed_ms() >= limit)
+        {
+            self.aborted = true;
+        }
+        self.aborted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_limit() {
+        let mut ctl = SearchControl::new(SearchLimits { node_limit: Some(3), ..SearchLimits::default() });
+        assert!(!ctl.visit());
+        assert!(!ctl.visit());
+        assert!(ctl.visit());
+        assert!(ctl.aborted);
+    }
+
+    #[test]
+    fn test_shared_node_limit() {
+        // Another thread has already used most of the budget
+        let shared = Arc::new(AtomicU64::new(CHECK_INTERVAL * 3));
+        let mut ctl = SearchControl::new(SearchLimits { node_limit: Some(CHECK_INTERVAL * 4), ..SearchLimits::default() });
+        ctl.share_nodes(shared.clone());
+        while !ctl.visit() {}
+        assert_eq!(ctl.nodes, CHECK_INTERVAL);
+        assert_eq!(shared.load(Ordering::Relaxed), CHECK_INTERVAL * 4);
+    }
+
+    #[test]
+    fn test_repetition() {
+        // Two earlier occurrences in the game are needed, one in the search
+        let mut ctl = SearchControl::new(SearchLimits { history: vec![1, 2, 3, 4], ..SearchLimits::default() });
+        ctl.set_root(5);
+        ctl.enter(6);
+        ctl.enter(3);
+        assert!(!ctl.is_repetition(10));
+
+        let mut ctl = SearchControl::new(SearchLimits { history: vec![1, 2, 1, 2], ..SearchLimits::default() });
+        ctl.set_root(1);
+        assert!(ctl.is_repetition(4));
+        // ...unless a capture or pawn move came in between
+        assert!(!ctl.is_repetition(3));
+
+        ctl.enter(7);
+        ctl.enter(8);
+        ctl.enter(9);
+        assert!(!ctl.is_repetition(10));
+        ctl.enter(8);
+        assert!(ctl.is_repetition(10));
+        ctl.leave();
+
+        // Not across a null move
+        let previous = ctl.enter_null(8);
+        assert!(!ctl.is_repetition(10));
+        ctl.leave_null(previous);
+        assert_eq!(ctl.ply, 3);
+    }
+
+    #[test]
+    fn test_draw_score() {
+        let mut ctl = SearchControl::unlimited();
+        ctl.config.contempt = 20;
+        assert_eq!(ctl.draw_score(), -20);
+        ctl.enter(1);
+        assert_eq!(ctl.draw_score(), 20);
+    }
+
+    #[test]
+    fn test_stop_flag() {
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut ctl = SearchControl::new(SearchLimits { stop: Some(stop.clone()), ..SearchLimits::default() });
+        assert!(!ctl.check());
+        stop.store(true, Ordering::Relaxed);
+        assert!(ctl.check());
+    }
+}
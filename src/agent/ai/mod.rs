@@ -14,6 +14,11 @@ mod transposition_table;
 mod negamax;
 mod quiescence;
 mod search;
+mod limits;
+mod pv_table;
+mod time_manager;
+mod searcher;
+mod search_handle;
 mod negamax_player;
 mod evaluation;
 mod move_ordering;
@@ -24,5 +29,9 @@ pub use negamax_player::{NegamaxPlayer, Difficulty};
 pub use ai_type::{AIType, AIConfig};
 
 // Re-export useful types
-pub use search::SearchResult;
+pub use search::{iterative_deepening_search, PvLine, SearchInfo, SearchResult};
+pub use searcher::{SearchConfig, Searcher, DEFAULT_HASH_MB};
+pub use search_handle::{BackgroundSearcher, FinishedSearch, SearchHandle};
+pub use limits::{SearchLimits, MAX_SEARCH_DEPTH};
+pub use time_manager::{Clock, TimeManager, MOVE_OVERHEAD_MS};
 pub use transposition_table::TranspositionTable;
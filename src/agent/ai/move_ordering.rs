@@ -7,6 +7,7 @@ use crate::game_repr::{Move, Position};
 use smallvec::SmallVec;
 
 /// Killer move table - stores moves that caused beta cutoffs at each depth
+#[derive(Clone)]
 pub struct KillerMoves {
     // Store 2 killer moves per depth (most recent beta cutoffs)
     table: [[Option<Move>; 2]; 64],
@@ -43,13 +44,13 @@ impl KillerMoves {
     }
 
     /// Clear all killer moves (useful for new games)
-    #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.table = [[None; 2]; 64];
     }
 }
 
 /// History heuristic table - records move success rates
+#[derive(Clone)]
 pub struct HistoryTable {
     // Indexed by [from_square][to_square]
     table: [[i32; 64]; 64],
@@ -76,20 +77,28 @@ impl HistoryTable {
     }
 
     /// Clear all history scores (useful for new games)
-    #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.table = [[0; 64]; 64];
     }
+
+    /// Halve all history scores between searches, so moves that were good
+    /// earlier in the game count for less than recent ones
+    pub fn age(&mut self) {
+        for score in self.table.iter_mut().flatten() {
+            *score /= 2;
+        }
+    }
 }
 
 /// Generate moves in a good order for alpha-beta pruning
 ///
 /// Move ordering priority:
 /// 1. Hash move (from transposition table)
-/// 2. Captures (MVV-LVA: Most Valuable Victim - Least Valuable Attacker)
+/// 2. Captures that don't lose material (MVV-LVA: Most Valuable Victim - Least Valuable Attacker)
 /// 3. Killer moves (non-captures that caused beta cutoffs)
 /// 4. History heuristic (moves that were good in other positions)
 /// 5. Other moves
+/// 6. Captures that lose material by static exchange evaluation, least losing first
 pub fn generate_ordered_moves(
     pos: &Position,
     hash_move: Option<Move>,
@@ -112,7 +121,12 @@ pub fn generate_ordered_moves(
         let captured_piece = pos.position[to];
 
         // Captures: order by MVV-LVA (Most Valuable Victim - Least Valuable Attacker)
-        if captured_piece.piece_type != crate::game_repr::Type::None {
+        if captured_piece.piece_type != crate::game_repr::Type::None && pos.is_capture(mv) {
+            // Losing captures go after the quiet moves (whose keys are <= 0)
+            let see = pos.see(mv);
+            if see < 0 {
+                return -see;
+            }
             let victim_value = piece_value(captured_piece.piece_type);
             let attacker_value = piece_value(moving_piece.piece_type);
             return -(victim_value * 10 - attacker_value); // Negative for higher priority
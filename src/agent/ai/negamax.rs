@@ -10,16 +10,27 @@
 // - Killer move heuristic for move ordering
 // - History heuristic for quiet moves
 // - Null move pruning for early cutoffs
+// - Reverse futility pruning, razoring and futility pruning near the leaves
+// - Late move reductions for quiet moves ordered late
+// - Check extensions and internal iterative reductions
 // - Principal Variation Search (PVS) for efficiency
 // - Quiescence search to avoid horizon effect
 // - Mate distance pruning for faster mate detection
+// - Draws by repetition and the fifty-move rule, scored with contempt
+// - Triangular PV table collecting the principal variation
+//
+// The selective features (everything from reverse futility pruning on) can
+// be switched off through SearchConfig, to measure what each one is worth.
 //
 // The function returns (score, best_move) from the perspective of the side to move.
 
 use crate::game_repr::{Position, Color, Move};
+use super::evaluation::evaluate;
 use super::quiescence::quiescence_search;
+use super::limits::{SearchControl, MAX_SEARCH_DEPTH};
 use super::move_ordering::{generate_ordered_moves, KillerMoves, HistoryTable};
 use super::transposition_table::{TranspositionTable, TranspositionTableEntry, NodeType};
+use std::sync::LazyLock;
 
 /// Checkmate score - use large value but leave room for mate distance
 pub const MATE_SCORE: i32 = 30000;
@@ -37,6 +48,45 @@ const NULL_MOVE_REDUCTION: u8 = 2;
 /// Minimum depth to attempt null move pruning
 const NULL_MOVE_MIN_DEPTH: u8 = 3;
 
+/// Deepest ply checks are extended at, so long series of checks still end
+const MAX_EXTENDED_PLY: i32 = 2 * MAX_SEARCH_DEPTH as i32;
+
+/// Deepest node reverse futility pruning applies to
+const REVERSE_FUTILITY_MAX_DEPTH: u8 = 6;
+
+/// Reverse futility margin per ply of remaining depth
+const REVERSE_FUTILITY_MARGIN: i32 = 120;
+
+/// Deepest node razoring applies to
+const RAZORING_MAX_DEPTH: u8 = 2;
+
+/// Razoring margin per ply of remaining depth
+const RAZORING_MARGIN: i32 = 300;
+
+/// Futility margins by remaining depth; deeper nodes are not pruned
+const FUTILITY_MARGINS: [i32; 4] = [0, 200, 300, 500];
+
+/// Minimum depth for late move reductions
+const LMR_MIN_DEPTH: u8 = 3;
+
+/// Moves searched at full depth before reductions start
+const LMR_FULL_DEPTH_MOVES: usize = 3;
+
+/// Minimum depth for internal iterative reductions
+const IIR_MIN_DEPTH: u8 = 4;
+
+/// Late move reductions by remaining depth and move number, growing with
+/// the logarithm of both
+static LMR_REDUCTIONS: LazyLock<[[u8; 64]; 64]> = LazyLock::new(|| {
+    let mut table = [[0; 64]; 64];
+    for (depth, row) in table.iter_mut().enumerate().skip(1) {
+        for (index, reduction) in row.iter_mut().enumerate().skip(1) {
+            *reduction = (0.75 + (depth as f64).ln() * (index as f64).ln() / 2.25) as u8;
+        }
+    }
+    table
+});
+
 /// Negamax search with alpha-beta pruning
 ///
 /// This is the core search function that recursively searches the game tree
@@ -53,6 +103,8 @@ const NULL_MOVE_MIN_DEPTH: u8 = 3;
 /// * `tt` - Transposition table for caching positions
 /// * `killers` - Killer move table for move ordering
 /// * `history` - History heuristic table for move ordering
+/// * `ctl` - Node counter and stop detection; once it is aborted the returned
+///   score is meaningless and must be discarded
 ///
 /// # Returns
 ///
@@ -61,23 +113,52 @@ const NULL_MOVE_MIN_DEPTH: u8 = 3;
 #[allow(clippy::too_many_arguments)]
 pub fn negamax(
     pos: &mut Position,
-    depth: u8,
+    mut depth: u8,
     mut alpha: i32,
     beta: i32,
     color: Color,
     tt: &mut TranspositionTable,
     killers: &mut KillerMoves,
     history: &mut HistoryTable,
+    ctl: &mut SearchControl,
 ) -> (i32, Option<Move>) {
+    // Nothing found below this node yet
+    ctl.pv.clear(ctl.ply as usize);
+
+    // Draw by repetition or the fifty-move rule (checkmate still counts on
+    // the hundredth ply); the root is searched even when it is a draw
+    if ctl.ply > 0
+        && (ctl.is_repetition(pos.halfmove_clock)
+            || (pos.can_claim_fifty_move_draw() && !pos.is_checkmate(color)))
+    {
+        return (ctl.draw_score(), None);
+    }
+
+    // Check if we're in check (affects pruning, extensions and move generation)
+    let in_check = pos.is_in_check(color);
+
+    // Check extension: search one ply deeper out of check, so a check at the
+    // horizon is answered rather than left to the quiescence search
+    if in_check && ctl.config.check_extensions && ctl.ply < MAX_EXTENDED_PLY {
+        depth += 1;
+    }
+
     // Base case: reached maximum depth, use quiescence search
     if depth == 0 {
-        let score = quiescence_search(pos, alpha, beta, color);
+        let score = quiescence_search(pos, alpha, beta, color, tt, ctl);
         return (score, None);
     }
 
+    if ctl.visit() {
+        return (0, None);
+    }
+
+    // Null-window nodes only need to know whether the score beats alpha
+    let pv_node = beta.saturating_sub(alpha) > 1;
+
     // Transposition table lookup
-    let hash = TranspositionTable::compute_hash(pos);
-    let tt_entry = tt.probe(hash);
+    let hash = pos.zobrist;
+    let tt_entry = tt.probe(hash, ctl.ply);
     let mut hash_move = None;
 
     // Use transposition table entry if it's valid
@@ -112,8 +193,53 @@ pub fn negamax(
         hash_move = entry.best_move;
     }
 
-    // Check if we're in check (affects null move and move generation)
-    let in_check = pos.is_in_check(color);
+    // Internal iterative reduction: without a hash move the ordering is poor,
+    // so search shallower and leave a hash move for the next iteration
+    if hash_move.is_none() && depth >= IIR_MIN_DEPTH && ctl.config.internal_iterative_reductions {
+        depth -= 1;
+    }
+
+    // Static evaluation for the pruning decisions (meaningless in check)
+    let static_eval = if in_check { MIN_SCORE } else { evaluate(pos, color) };
+
+    // Reverse futility pruning: so far above beta that a shallow search will
+    // not bring the score back down
+    if !pv_node
+        && !in_check
+        && ctl.config.reverse_futility_pruning
+        && depth <= REVERSE_FUTILITY_MAX_DEPTH
+        && !is_mate_score(beta)
+        && static_eval - REVERSE_FUTILITY_MARGIN * depth as i32 >= beta
+    {
+        return (beta, None);
+    }
+
+    // Razoring: so far below alpha near the leaves that only captures could
+    // help; if the quiescence search confirms the fail low, trust it
+    if !pv_node
+        && !in_check
+        && ctl.config.razoring
+        && depth <= RAZORING_MAX_DEPTH
+        && !is_mate_score(alpha)
+        && static_eval + RAZORING_MARGIN * (depth as i32) < alpha
+    {
+        let score = quiescence_search(pos, alpha, beta, color, tt, ctl);
+        if ctl.aborted {
+            return (0, None);
+        }
+        if score <= alpha {
+            return (score, None);
+        }
+    }
+
+    // Futility pruning: quiet moves cannot lift a score this far below
+    // alpha within the remaining depth
+    let futile = !pv_node
+        && !in_check
+        && ctl.config.futility_pruning
+        && (depth as usize) < FUTILITY_MARGINS.len()
+        && !is_mate_score(alpha)
+        && static_eval + FUTILITY_MARGINS[depth as usize] <= alpha;
 
     // Null Move Pruning
     // If we can pass (do nothing) and still get a beta cutoff, position is too good
@@ -126,9 +252,8 @@ pub fn negamax(
         && beta.abs() < MATE_SCORE - 100
     {
         // Make null move (pass turn to opponent)
-        // We simulate this by adding a dummy move to prev_moves
-        let prev_moves_len = pos.prev_moves.len();
-        pos.prev_moves.push(Move::new(0, 0, crate::game_repr::MoveType::Normal));
+        let null_undo = pos.make_null_move();
+        let null_entry = ctl.enter_null(pos.zobrist);
 
         // Search with reduced depth from opponent's perspective
         let reduced_depth = depth.saturating_sub(NULL_MOVE_REDUCTION + 1);
@@ -141,10 +266,15 @@ pub fn negamax(
             tt,
             killers,
             history,
+            ctl,
         );
 
         // Undo null move
-        pos.prev_moves.truncate(prev_moves_len);
+        pos.unmake_null_move(null_undo);
+        ctl.leave_null(null_entry);
+        if ctl.aborted {
+            return (0, None);
+        }
 
         // If null move causes beta cutoff, position is too good
         if -null_score >= beta {
@@ -158,12 +288,12 @@ pub fn negamax(
     // If no legal moves, it's either checkmate or stalemate
     if moves.is_empty() {
         if in_check {
-            // Checkmate - return negative mate score adjusted by depth
-            // We prefer shorter mates (closer to current position)
-            return (-(MATE_SCORE - depth as i32), None);
+            // Checkmate - return negative mate score adjusted by distance
+            // from the root, so shorter mates score higher
+            return (-(MATE_SCORE - ctl.ply), None);
         } else {
             // Stalemate - draw
-            return (0, None);
+            return (ctl.draw_score(), None);
         }
     }
 
@@ -176,9 +306,21 @@ pub fn negamax(
     // Search first move with full window, rest with null window
     let mut is_first_move = true;
 
-    for mv in moves {
+    for (index, mv) in moves.into_iter().enumerate() {
+        let quiet = !pos.is_capture(mv) && !mv.move_type().is_promotion();
+        let gives_check = pos.gives_check(mv);
+
+        // Skip futile quiet moves once one move has been searched; they
+        // could still reach the futility bound, so the node's score may not
+        // claim less
+        if futile && quiet && !gives_check && !is_first_move {
+            best_score = best_score.max((static_eval + FUTILITY_MARGINS[depth as usize]).min(alpha));
+            continue;
+        }
+
         // Make the move
         let undo = pos.make_move_undoable(mv);
+        ctl.enter(pos.zobrist);
 
         let score = if is_first_move {
             // Search first move with full window
@@ -191,21 +333,61 @@ pub fn negamax(
                 tt,
                 killers,
                 history,
+                ctl,
             );
             -s
         } else {
+            // Late move reduction: quiet moves this late in the killer/history
+            // ordering rarely beat alpha, so they get a shallower scout search
+            let reduction = if quiet
+                && !in_check
+                && !gives_check
+                && ctl.config.late_move_reductions
+                && depth >= LMR_MIN_DEPTH
+                && index >= LMR_FULL_DEPTH_MOVES
+            {
+                let mut reduction = LMR_REDUCTIONS[(depth as usize).min(63)][index.min(63)];
+                // Less for the moves ordering trusts most, and on the PV
+                if killers.is_killer(depth, mv) {
+                    reduction = reduction.saturating_sub(1);
+                }
+                if pv_node {
+                    reduction = reduction.saturating_sub(1);
+                }
+                reduction.min(depth - 2)
+            } else {
+                0
+            };
+
             // Search remaining moves with null window (scout search)
             let (s, _) = negamax(
                 pos,
-                depth - 1,
+                depth - 1 - reduction,
                 -alpha - 1,
                 -alpha,
                 color.opposite(),
                 tt,
                 killers,
                 history,
+                ctl,
             );
-            let scout_score = -s;
+            let mut scout_score = -s;
+
+            // A reduced move that beats alpha gets a full-depth scout search
+            if reduction > 0 && scout_score > alpha {
+                let (s, _) = negamax(
+                    pos,
+                    depth - 1,
+                    -alpha - 1,
+                    -alpha,
+                    color.opposite(),
+                    tt,
+                    killers,
+                    history,
+                    ctl,
+                );
+                scout_score = -s;
+            }
 
             // If null window search fails high, re-search with full window
             if scout_score > alpha && scout_score < beta {
@@ -218,6 +400,7 @@ pub fn negamax(
                     tt,
                     killers,
                     history,
+                    ctl,
                 );
                 -s
             } else {
@@ -227,6 +410,10 @@ pub fn negamax(
 
         // Unmake the move
         pos.unmake_move(mv, undo);
+        ctl.leave();
+        if ctl.aborted {
+            return (0, None);
+        }
 
         // Update best score and move
         if score > best_score {
@@ -238,9 +425,7 @@ pub fn negamax(
         if score >= beta {
             // Beta cutoff - this move is too good, opponent won't allow it
             // Store killer move (for non-captures)
-            let to = mv._to();
-            let is_capture = pos.position[to].piece_type != crate::game_repr::Type::None;
-            if !is_capture {
+            if !pos.is_capture(mv) {
                 killers.store(depth, mv);
                 history.update(mv, depth);
             }
@@ -253,7 +438,7 @@ pub fn negamax(
                 best_move: Some(mv),
                 node_type: NodeType::LowerBound,
             };
-            tt.store(tt_entry);
+            tt.store(tt_entry, ctl.ply);
 
             return (beta, Some(mv));
         }
@@ -262,11 +447,10 @@ pub fn negamax(
         if score > alpha {
             alpha = score;
             node_type = NodeType::Exact; // We have an exact score (PV node)
+            ctl.pv.update(ctl.ply as usize, mv);
 
             // Update history heuristic for good quiet moves
-            let to = mv._to();
-            let is_capture = pos.position[to].piece_type != crate::game_repr::Type::None;
-            if !is_capture {
+            if !pos.is_capture(mv) {
                 history.update(mv, depth);
             }
         }
@@ -282,20 +466,18 @@ pub fn negamax(
         best_move,
         node_type,
     };
-    tt.store(tt_entry);
+    tt.store(tt_entry, ctl.ply);
 
     (best_score, best_move)
 }
 
 /// Helper function to detect if a score represents a mate
-#[allow(dead_code)]
 pub fn is_mate_score(score: i32) -> bool {
     score.abs() >= MATE_SCORE - 100
 }
 
 /// Get the number of moves until mate from a mate score
 /// Returns None if not a mate score
-#[allow(dead_code)]
 pub fn mate_distance(score: i32) -> Option<i32> {
     if !is_mate_score(score) {
         return None;
@@ -334,7 +516,7 @@ mod tests {
         let mut killers = KillerMoves::new();
         let mut history = HistoryTable::new();
 
-        let (score, _best_move) = negamax(&mut pos, 1, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history);
+        let (score, _best_move) = negamax(&mut pos, 1, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history, &mut SearchControl::unlimited());
 
         // Should detect mate
         assert!(is_mate_score(score), "Should detect mate, score: {}", score);
@@ -350,7 +532,7 @@ mod tests {
         let mut killers = KillerMoves::new();
         let mut history = HistoryTable::new();
 
-        let (score, _best_move) = negamax(&mut pos, 1, MIN_SCORE, MAX_SCORE, Color::Black, &mut tt, &mut killers, &mut history);
+        let (score, _best_move) = negamax(&mut pos, 1, MIN_SCORE, MAX_SCORE, Color::Black, &mut tt, &mut killers, &mut history, &mut SearchControl::unlimited());
 
         // Stalemate should give score of 0 (draw)
         assert_eq!(score, 0, "Stalemate should score 0, got: {}", score);
@@ -364,7 +546,7 @@ mod tests {
         let mut killers = KillerMoves::new();
         let mut history = HistoryTable::new();
 
-        let (score, best_move) = negamax(&mut pos, 3, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history);
+        let (score, best_move) = negamax(&mut pos, 3, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history, &mut SearchControl::unlimited());
 
         // Should find the queen capture
         assert!(best_move.is_some(), "Should find a best move");
@@ -385,11 +567,11 @@ mod tests {
         let mut history = HistoryTable::new();
 
         // Search with narrow window
-        let (score1, move1) = negamax(&mut pos, 2, -50, 50, Color::White, &mut tt, &mut killers, &mut history);
+        let (score1, move1) = negamax(&mut pos, 2, -50, 50, Color::White, &mut tt, &mut killers, &mut history, &mut SearchControl::unlimited());
 
         // Search with wide window
         tt.clear();
-        let (score2, move2) = negamax(&mut pos, 2, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history);
+        let (_score2, move2) = negamax(&mut pos, 2, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history, &mut SearchControl::unlimited());
 
         // Both should find a move
         assert!(move1.is_some() || score1.abs() >= 50, "Narrow window should find move or fail");
@@ -404,14 +586,14 @@ mod tests {
         let mut history = HistoryTable::new();
 
         // First search
-        let (score1, move1) = negamax(&mut pos, 3, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history);
+        let (score1, move1) = negamax(&mut pos, 3, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history, &mut SearchControl::unlimited());
 
         // TT should have entries now
         assert!(tt.size() > 0, "TT should have entries after search");
 
         // Second search should use TT
         let hits_before = tt.hits;
-        let (score2, move2) = negamax(&mut pos, 3, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history);
+        let (score2, move2) = negamax(&mut pos, 3, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history, &mut SearchControl::unlimited());
         let hits_after = tt.hits;
 
         // Should have TT hits in second search
@@ -430,12 +612,35 @@ mod tests {
         let mut history = HistoryTable::new();
 
         // Run search to populate killers
-        negamax(&mut pos, 4, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history);
+        negamax(&mut pos, 4, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history, &mut SearchControl::unlimited());
 
         // Killer moves should have been updated (at least at some depth)
         // We can't easily verify specific moves, but the table should be non-trivial
         // Just verify search completed successfully
-        assert!(true, "Search should complete without panics");
+    }
+
+    #[test]
+    fn test_futility_pruned_moves_bound_the_score() {
+        // Far behind with only quiet moves, and every reply already known to
+        // lose badly: all moves but the first are pruned, and the stored
+        // upper bound must leave room for what they could reach
+        let mut pos = Position::from_fen("3qk3/8/8/8/8/8/P7/4K3 w - -");
+        let static_eval = evaluate(&pos, Color::White);
+        let alpha = static_eval + FUTILITY_MARGINS[2] + 50;
+        let mut tt = TranspositionTable::new();
+        let mut killers = KillerMoves::new();
+        let mut history = HistoryTable::new();
+        for mv in pos.all_legal_moves() {
+            let undo = pos.make_move_undoable(mv);
+            let entry = TranspositionTableEntry { hash: pos.zobrist, depth: 10, score: 5000, best_move: None, node_type: NodeType::Exact };
+            tt.store(entry, 1);
+            pos.unmake_move(mv, undo);
+        }
+
+        negamax(&mut pos, 2, alpha, alpha + 1, Color::White, &mut tt, &mut killers, &mut history, &mut SearchControl::unlimited());
+        let entry = tt.probe(pos.zobrist, 0).expect("node should be stored");
+        assert_eq!(entry.node_type, NodeType::UpperBound);
+        assert!(entry.score >= static_eval + FUTILITY_MARGINS[2], "{} below the futility bound", entry.score);
     }
 
     #[test]
@@ -447,7 +652,7 @@ mod tests {
         let mut history = HistoryTable::new();
 
         // Depth 0 should call quiescence
-        let (score, mv) = negamax(&mut pos, 0, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history);
+        let (score, mv) = negamax(&mut pos, 0, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history, &mut SearchControl::unlimited());
 
         // Should recognize material disadvantage (down a queen)
         assert!(score < -700, "Should see we're down a queen: {}", score);
@@ -484,7 +689,7 @@ mod tests {
         let mut killers = KillerMoves::new();
         let mut history = HistoryTable::new();
 
-        let (score, best_move) = negamax(&mut pos, 2, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history);
+        let (score, best_move) = negamax(&mut pos, 2, MIN_SCORE, MAX_SCORE, Color::White, &mut tt, &mut killers, &mut history, &mut SearchControl::unlimited());
 
         // Starting position should be roughly balanced
         assert!(score.abs() < 200, "Starting position should be balanced, score: {}", score);
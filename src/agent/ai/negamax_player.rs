@@ -9,8 +9,9 @@
 //! # Architecture
 //!
 //! The NegamaxPlayer implements the Player trait and delegates move selection to
-//! the search module's `iterative_deepening_search` function. This provides a clean
-//! separation between the player interface and the search algorithm.
+//! a [`Searcher`] it owns for the whole game, so the transposition table and
+//! move ordering tables carry over from one move to the next. This provides a
+//! clean separation between the player interface and the search algorithm.
 //!
 //! # Difficulty Levels
 //!
@@ -37,7 +38,9 @@
 use crate::agent::player::Player;
 use crate::game_repr::{Color, Move, Type};
 use crate::board::Board;
-use super::search::iterative_deepening_search;
+use super::limits::SearchLimits;
+use super::search_handle::BackgroundSearcher;
+use super::search::SearchInfo;
 use std::sync::Arc;
 use std::cell::RefCell;
 use winit::event::WindowEvent;
@@ -71,7 +74,8 @@ pub enum Difficulty {
 
     /// Expert difficulty: Depth 8, 5 second time limit
     ///
-    /// Very strong play with deep calculation. Uses time control to limit computation.
+    /// Very strong play with deep calculation. Uses time control to limit computation
+    /// and searches on every core.
     /// Maximum move time: 5 seconds
     Expert,
 }
@@ -120,6 +124,27 @@ impl Difficulty {
         }
     }
 
+    /// Get the number of search threads for this difficulty level
+    ///
+    /// Expert uses every core for its timed search; the depth-limited levels
+    /// stay single-threaded so they play the same move every time.
+    pub fn threads(&self) -> usize {
+        match self {
+            Difficulty::Expert => num_cpus::get(),
+            _ => 1,
+        }
+    }
+
+    /// Search limits for this difficulty level
+    pub fn search_limits(&self) -> SearchLimits {
+        SearchLimits {
+            max_depth: self.max_depth(),
+            time_limit_ms: self.time_limit_ms(),
+            threads: self.threads(),
+            ..SearchLimits::default()
+        }
+    }
+
     /// Get a display name for this difficulty level
     pub fn name(&self) -> &'static str {
         match self {
@@ -149,7 +174,9 @@ impl Difficulty {
 /// # Thread Safety
 ///
 /// This player is not thread-safe and must be used on the main thread only.
-/// The shared `board` reference uses `RefCell` for interior mutability.
+/// The shared `board` reference uses `RefCell` for interior mutability. The
+/// search itself runs on a worker thread (inline on WASM) that owns the
+/// searcher until it finishes.
 pub struct NegamaxPlayer {
     /// Shared reference to the game board
     ///
@@ -164,6 +191,13 @@ pub struct NegamaxPlayer {
     ///
     /// Used in UI and logging. Can be customized via constructor.
     name: String,
+
+    /// Search state kept between moves, reset when the game ends; searches
+    /// run in the background so the window keeps rendering
+    searcher: BackgroundSearcher,
+
+    /// Set when a search came back without a move: its worker thread died
+    resigned: bool,
 }
 
 impl NegamaxPlayer {
@@ -195,6 +229,8 @@ impl NegamaxPlayer {
             board,
             difficulty,
             name,
+            searcher: BackgroundSearcher::new(),
+            resigned: false,
         }
     }
 
@@ -277,18 +313,28 @@ impl NegamaxPlayer {
             self.name = format!("AI ({})", difficulty.name());
         }
     }
+
+    /// Latest progress report (depth, score, PV, nodes) of the running
+    /// search, if the AI is thinking
+    pub fn thinking_info(&self) -> Option<&SearchInfo> {
+        self.searcher.info()
+    }
 }
 
 impl Player for NegamaxPlayer {
     /// Request the next move from the AI
     ///
-    /// This method performs a Negamax search with alpha-beta pruning to select
+    /// This method runs a Negamax search with alpha-beta pruning to select
     /// the best move for the given color. The search depth is determined by the
     /// difficulty level.
     ///
-    /// # Blocking Behavior
+    /// # Non-Blocking Behavior
     ///
-    /// This is a **blocking operation** that may take several seconds depending on:
+    /// The search runs on a worker thread. The first call starts it and
+    /// returns `None`; later calls return `None` while [`is_thinking`](Player::is_thinking)
+    /// is true and the best move once the search has finished. The orchestrator
+    /// polls every frame while the AI thinks, so the window stays responsive.
+    /// Search time depends on:
     /// - Position complexity (number of legal moves)
     /// - Search depth (set by difficulty level)
     /// - Time limit (for Expert difficulty)
@@ -296,8 +342,8 @@ impl Player for NegamaxPlayer {
     /// # Search Process
     ///
     /// 1. Get current position from the board
-    /// 2. Call iterative_deepening_search with difficulty parameters
-    /// 3. Return the best move found
+    /// 2. Search it in the background with the player's searcher and the difficulty's limits
+    /// 3. Return the best move found once the search is done
     ///
     /// # Arguments
     ///
@@ -306,7 +352,7 @@ impl Player for NegamaxPlayer {
     /// # Returns
     ///
     /// * `Some(Move)` - The best move found by the search
-    /// * `None` - If no legal moves are available (checkmate or stalemate)
+    /// * `None` - While searching, or if no legal moves are available (checkmate or stalemate)
     ///
     /// # Performance
     ///
@@ -316,23 +362,15 @@ impl Player for NegamaxPlayer {
     /// - Hard (depth 6): ~5 seconds
     /// - Expert (depth 8): up to 5 seconds (time limited)
     fn get_move(&mut self, color: Color) -> Option<Move> {
-        // Get the current position from the board
-        let position = {
-            let board = self.board.borrow();
-            board.position().clone()
-        };
-
-        // Get search parameters based on difficulty
-        let max_depth = self.difficulty.max_depth();
-        let time_limit_ms = self.difficulty.time_limit_ms();
-
-        // Perform iterative deepening search
-        let search_result = iterative_deepening_search(
-            &position,
-            color,
-            max_depth,
-            time_limit_ms,
-        );
+        let board = self.board.borrow();
+        let position = board.position();
+        debug_assert_eq!(color, position.side_to_move);
+
+        // Start or poll the background search with the difficulty's limits;
+        // the game so far, for repetitions, is only copied to start one
+        let difficulty = self.difficulty;
+        let limits = || SearchLimits { history: board.history_keys().to_vec(), ..difficulty.search_limits() };
+        let search_result = self.searcher.poll_move(position, limits)?;
 
         // Log search statistics in debug builds
         if cfg!(debug_assertions) {
@@ -344,13 +382,32 @@ impl Player for NegamaxPlayer {
                 search_result.score
             );
             if let Some(pv) = &search_result.principal_variation {
-                println!("  Principal variation: {} moves", pv.len());
+                println!("  Principal variation: {}", position.line_to_san(pv));
             }
         }
 
+        // The game is over before a position without legal moves is
+        // searched, so only a failed search finds no move
+        self.resigned = search_result.best_move.is_none();
         search_result.best_move
     }
 
+    /// Whether a background search is running
+    fn is_thinking(&self) -> bool {
+        self.searcher.is_thinking()
+    }
+
+    /// Makes the running search finish now; the next
+    /// [`get_move`](Player::get_move) returns the best move found so far
+    fn stop_thinking(&mut self) {
+        self.searcher.stop();
+    }
+
+    /// Whether the last search failed without finding a move
+    fn has_resigned(&self) -> bool {
+        self.resigned
+    }
+
     /// Handle window events
     ///
     /// AI players don't respond to window events (mouse, keyboard, etc.).
@@ -385,16 +442,14 @@ impl Player for NegamaxPlayer {
 
     /// Notification that the game has ended
     ///
-    /// Currently a no-op. Could be used for:
-    /// - Logging game results
-    /// - Saving statistics
-    /// - Cleaning up resources
+    /// Clears the searcher's tables so nothing learned in this game carries
+    /// over into the next one.
     ///
     /// # Arguments
     ///
     /// * `_result` - The final game result (win/loss/draw/stalemate)
     fn game_ended(&mut self, _result: crate::agent::player::GameResult) {
-        // No cleanup needed for Negamax player
+        self.searcher.new_game();
     }
 
     /// Get the display name of this AI player
@@ -457,6 +512,16 @@ mod tests {
         assert_eq!(Difficulty::Expert.time_limit_ms(), Some(5000));
     }
 
+    #[test]
+    fn test_difficulty_search_limits() {
+        let limits = Difficulty::Expert.search_limits();
+        assert_eq!(limits.max_depth, Difficulty::Expert.max_depth());
+        assert_eq!(limits.time_limit_ms, Some(5000));
+        assert_eq!(limits.node_limit, None);
+        assert_eq!(limits.threads, Difficulty::Expert.threads());
+        assert_eq!(Difficulty::Hard.search_limits().threads, 1);
+    }
+
     #[test]
     fn test_difficulty_names() {
         // Verify display names
@@ -477,8 +542,6 @@ mod tests {
 
     #[test]
     fn test_set_difficulty_updates_name() {
-        use crate::renderer::Renderer;
-        use crate::game_repr::Position;
 
         // Create a mock board (this would normally come from the application)
         // For testing, we can create a minimal setup
@@ -0,0 +1,72 @@
+// Triangular PV Table
+//
+// Collects the principal variation while negamax runs. Every ply owns a line:
+// a node clears its line on entry, and whenever a move raises alpha the line
+// becomes that move followed by the line the child left behind. The root's
+// line is then the best line found. Lines are cut short where the search
+// ended early (a transposition table cutoff or a draw), so the searcher
+// completes them from the table afterwards.
+
+use crate::game_repr::Move;
+
+/// Best line found below each ply of the current search path
+#[derive(Debug, Clone, Default)]
+pub struct PvTable {
+    lines: Vec<Vec<Move>>,
+}
+
+impl PvTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Empties the line of `ply`, on entering a node there
+    pub fn clear(&mut self, ply: usize) {
+        // Room for the child's line too, which update() reads
+        if self.lines.len() < ply + 2 {
+            self.lines.resize_with(ply + 2, Vec::new);
+        }
+        self.lines[ply].clear();
+    }
+
+    /// Makes the line of `ply` start with `mv`, followed by the line of the
+    /// child it leads to
+    pub fn update(&mut self, ply: usize, mv: Move) {
+        let (head, tail) = self.lines.split_at_mut(ply + 1);
+        let line = &mut head[ply];
+        line.clear();
+        line.push(mv);
+        line.extend_from_slice(&tail[0]);
+    }
+
+    /// The line found at `ply`
+    pub fn line(&self, ply: usize) -> &[Move] {
+        self.lines.get(ply).map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_repr::MoveType;
+
+    #[test]
+    fn test_lines_grow_towards_the_root() {
+        let moves: Vec<Move> = (0..3).map(|i| Move::new(i, i + 8, MoveType::Normal)).collect();
+        let mut pv = PvTable::new();
+
+        pv.clear(0);
+        pv.clear(1);
+        pv.clear(2);
+        pv.update(2, moves[2]);
+        pv.update(1, moves[1]);
+        pv.update(0, moves[0]);
+        assert_eq!(pv.line(0), &moves[..]);
+
+        // A new child starts empty, so a move without a line is a line of one
+        pv.clear(1);
+        pv.update(0, moves[2]);
+        assert_eq!(pv.line(0), &[moves[2]]);
+        assert!(pv.line(5).is_empty());
+    }
+}
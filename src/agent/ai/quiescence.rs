@@ -16,10 +16,18 @@
 // 1. Stand-pat: Current evaluation can cause beta cutoff without searching
 // 2. Delta pruning: Skip captures that can't possibly improve alpha
 // 3. MVV-LVA ordering: Search most promising captures first
-// 4. Depth limit: Prevent infinite recursion in complex tactical positions
+// 4. SEE pruning: Skip captures that lose material in the exchange
+// 5. Depth limit: Prevent infinite recursion in complex tactical positions
+//
+// A side in check may not stand pat, so there every evasion is searched and
+// mates at the horizon are seen. Optionally the first ply also tries quiet
+// checks, keeping its results in the transposition table.
 
 use crate::game_repr::{Position, Color, Move, MoveType, Type};
 use super::evaluation::evaluate;
+use super::limits::SearchControl;
+use super::negamax::MATE_SCORE;
+use super::transposition_table::{TranspositionTable, TranspositionTableEntry, NodeType};
 use smallvec::SmallVec;
 
 /// Maximum depth for quiescence search to prevent infinite recursion
@@ -53,37 +61,103 @@ fn piece_material_value(piece_type: Type) -> i32 {
 ///
 /// Only searches tactical moves (captures, promotions) to avoid
 /// the horizon effect where we evaluate positions in the middle
-/// of a tactical sequence.
+/// of a tactical sequence. A side in check cannot stand pat: all of
+/// its evasions are searched, and having none is checkmate.
 ///
 /// This function implements several optimizations:
 /// - Stand-pat: Use static evaluation as baseline
 /// - Delta pruning: Skip captures that can't improve alpha
 /// - MVV-LVA move ordering: Search best captures first
+/// - SEE pruning: Skip captures that lose material
 /// - Depth limiting: Prevent infinite recursion
 ///
+/// With `SearchConfig::quiescence_checks` the first ply also tries quiet
+/// moves that give check, and probes and stores the transposition table
+/// since it now costs more.
+///
 /// # Arguments
 ///
 /// * `pos` - Current position (mutable for make/unmake moves)
-/// * `mut alpha` - Lower bound (best score achievable by maximizing player)
+/// * `alpha` - Lower bound (best score achievable by maximizing player)
 /// * `beta` - Upper bound (best score opponent will allow)
 /// * `color` - Side to move
 /// * `qs_depth` - Current quiescence search depth (for limiting)
+/// * `tt` - Transposition table, used at the first ply with quiet checks
+/// * `ctl` - Node counter and stop detection
 ///
 /// # Returns
 ///
 /// Evaluation score from the perspective of `color` (positive = good for `color`)
 pub fn quiescence(
     pos: &mut Position,
-    mut alpha: i32,
+    alpha: i32,
     beta: i32,
     color: Color,
     qs_depth: i32,
+    tt: &mut TranspositionTable,
+    ctl: &mut SearchControl,
 ) -> i32 {
+    if ctl.visit() {
+        return 0;
+    }
+
     // Depth limit to prevent infinite recursion in complex tactical positions
     if qs_depth >= MAX_QSEARCH_DEPTH {
         return evaluate(pos, color);
     }
 
+    if pos.is_in_check(color) {
+        return search_evasions(pos, alpha, beta, color, qs_depth, tt, ctl);
+    }
+
+    if qs_depth > 0 || !ctl.config.quiescence_checks {
+        return search_tactical(pos, alpha, beta, color, qs_depth, tt, ctl);
+    }
+
+    // First ply with quiet checks: reuse and keep what was found here
+    let hash = pos.zobrist;
+    let entry = tt.probe(hash, ctl.ply);
+    if let Some(entry) = entry {
+        match entry.node_type {
+            NodeType::Exact => return entry.score,
+            NodeType::LowerBound if entry.score >= beta => return beta,
+            NodeType::UpperBound if entry.score <= alpha => return alpha,
+            _ => {}
+        }
+    }
+
+    let score = search_tactical(pos, alpha, beta, color, qs_depth, tt, ctl);
+    if ctl.aborted {
+        return 0;
+    }
+
+    // A bound from the main search, however loose here, is worth more
+    if entry.is_some_and(|entry| entry.depth > 0) {
+        return score;
+    }
+
+    let node_type = if score >= beta {
+        NodeType::LowerBound
+    } else if score <= alpha {
+        NodeType::UpperBound
+    } else {
+        NodeType::Exact
+    };
+    tt.store(TranspositionTableEntry { hash, depth: 0, score, best_move: None, node_type }, ctl.ply);
+    score
+}
+
+/// Stand pat, then search captures and promotions (and quiet checks on the
+/// first ply, if enabled)
+fn search_tactical(
+    pos: &mut Position,
+    mut alpha: i32,
+    beta: i32,
+    color: Color,
+    qs_depth: i32,
+    tt: &mut TranspositionTable,
+    ctl: &mut SearchControl,
+) -> i32 {
     // Stand-pat evaluation: current position value without any moves
     // This represents the option to "do nothing" and is our baseline
     let stand_pat = evaluate(pos, color);
@@ -109,8 +183,12 @@ pub fn quiescence(
         return alpha;
     }
 
-    // Generate and order tactical moves (captures and promotions)
-    let moves = generate_tactical_moves(pos);
+    // Generate and order tactical moves (captures and promotions),
+    // followed by the quiet checks
+    let mut moves = generate_tactical_moves(pos);
+    if qs_depth == 0 && ctl.config.quiescence_checks {
+        moves.extend(generate_quiet_checks(pos));
+    }
 
     // If no tactical moves, position is quiet - return stand-pat
     if moves.is_empty() {
@@ -120,6 +198,7 @@ pub fn quiescence(
     // Search tactical moves
     for mv in moves {
         // Delta pruning per move: Skip captures that can't improve alpha
+        // (a quiet check is worth what it threatens, not what it takes)
         let to = mv._to();
         let captured_value = if mv.move_type() == MoveType::EnPassant {
             PAWN_VALUE
@@ -134,18 +213,29 @@ pub fn quiescence(
         };
 
         // If this capture + promotion can't improve alpha even with margin, skip it
-        if stand_pat + captured_value + promotion_bonus + DELTA_MARGIN < alpha {
+        let tactical = pos.is_capture(mv) || mv.move_type().is_promotion();
+        if tactical && stand_pat + captured_value + promotion_bonus + DELTA_MARGIN < alpha {
             continue; // Delta pruning: this capture is too weak
         }
 
+        // SEE pruning: the exchange on the target square loses material
+        if !pos.see_ge(mv, 0) {
+            continue;
+        }
+
         // Make move and recursively search
         let undo = pos.make_move_undoable(mv);
+        ctl.enter(pos.zobrist);
 
         // Negamax: negate score from opponent's perspective
-        let score = -quiescence(pos, -beta, -alpha, color.opposite(), qs_depth + 1);
+        let score = -quiescence(pos, -beta, -alpha, color.opposite(), qs_depth + 1, tt, ctl);
 
         // Unmake move
+        ctl.leave();
         pos.unmake_move(mv, undo);
+        if ctl.aborted {
+            return 0;
+        }
 
         // Beta cutoff: This move is too good, opponent won't allow it
         if score >= beta {
@@ -161,6 +251,52 @@ pub fn quiescence(
     alpha
 }
 
+/// Search every move out of check; without one the side to move is mated
+fn search_evasions(
+    pos: &mut Position,
+    mut alpha: i32,
+    beta: i32,
+    color: Color,
+    qs_depth: i32,
+    tt: &mut TranspositionTable,
+    ctl: &mut SearchControl,
+) -> i32 {
+    let mut moves = pos.all_legal_moves();
+    if moves.is_empty() {
+        // Checkmate, scored by distance from the root like in negamax
+        return -(MATE_SCORE - ctl.ply);
+    }
+
+    // Captures and promotions first, by MVV-LVA
+    moves.sort_by_cached_key(|&mv| {
+        if pos.is_capture(mv) || mv.move_type().is_promotion() {
+            -score_capture(pos, mv)
+        } else {
+            0
+        }
+    });
+
+    for mv in moves {
+        let undo = pos.make_move_undoable(mv);
+        ctl.enter(pos.zobrist);
+        let score = -quiescence(pos, -beta, -alpha, color.opposite(), qs_depth + 1, tt, ctl);
+        ctl.leave();
+        pos.unmake_move(mv, undo);
+        if ctl.aborted {
+            return 0;
+        }
+
+        if score >= beta {
+            return beta;
+        }
+        if score > alpha {
+            alpha = score;
+        }
+    }
+
+    alpha
+}
+
 /// Public wrapper for quiescence search with initial depth of 0
 ///
 /// This is the main entry point for quiescence search, typically called
@@ -172,6 +308,8 @@ pub fn quiescence(
 /// * `alpha` - Lower bound
 /// * `beta` - Upper bound
 /// * `color` - Side to move
+/// * `tt` - Transposition table
+/// * `ctl` - Node counter and stop detection
 ///
 /// # Returns
 ///
@@ -181,8 +319,10 @@ pub fn quiescence_search(
     alpha: i32,
     beta: i32,
     color: Color,
+    tt: &mut TranspositionTable,
+    ctl: &mut SearchControl,
 ) -> i32 {
-    quiescence(pos, alpha, beta, color, 0)
+    quiescence(pos, alpha, beta, color, 0, tt, ctl)
 }
 
 /// Score a capture move using MVV-LVA (Most Valuable Victim - Least Valuable Attacker)
@@ -225,23 +365,8 @@ fn generate_tactical_moves(pos: &Position) -> SmallVec<[Move; 64]> {
     let mut tactical_moves = SmallVec::new();
 
     for mv in all_moves {
-        let to = mv._to();
-        let move_type = mv.move_type();
-
-        // Include captures
-        if pos.position[to].piece_type != Type::None {
-            tactical_moves.push(mv);
-            continue;
-        }
-
-        // Include promotions
-        if move_type.is_promotion() {
-            tactical_moves.push(mv);
-            continue;
-        }
-
-        // Include en passant (special capture)
-        if move_type == MoveType::EnPassant {
+        // Include captures (en passant included) and promotions
+        if pos.is_capture(mv) || mv.move_type().is_promotion() {
             tactical_moves.push(mv);
         }
     }
@@ -253,16 +378,31 @@ fn generate_tactical_moves(pos: &Position) -> SmallVec<[Move; 64]> {
     tactical_moves
 }
 
+/// Generate the quiet moves (neither captures nor promotions) that give check
+///
+/// Checks are found from the attack tables, without making the moves.
+fn generate_quiet_checks(pos: &Position) -> SmallVec<[Move; 64]> {
+    pos.all_legal_moves()
+        .into_iter()
+        .filter(|&mv| !pos.is_capture(mv) && !mv.move_type().is_promotion() && pos.gives_check(mv))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::negamax::is_mate_score;
     use crate::game_repr::Position;
 
+    fn tt() -> TranspositionTable {
+        TranspositionTable::with_size_mb(1)
+    }
+
     #[test]
     fn test_quiet_position_returns_evaluation() {
         // Position with no captures available - should return static eval
         let mut pos = Position::from_fen("4k3/8/8/8/8/8/8/4K3 w - -");
-        let score = quiescence_search(&mut pos, -10000, 10000, Color::White);
+        let score = quiescence_search(&mut pos, -10000, 10000, Color::White, &mut tt(), &mut SearchControl::unlimited());
 
         // Score should be close to static evaluation (no tactics to search)
         let static_eval = evaluate(&pos, Color::White);
@@ -275,7 +415,7 @@ mod tests {
         let mut pos = Position::from_fen("4k3/8/8/8/8/8/8/Q3K3 w - -");
 
         // Set beta very low - stand-pat should cause immediate cutoff
-        let score = quiescence_search(&mut pos, -10000, -500, Color::White);
+        let score = quiescence_search(&mut pos, -10000, -500, Color::White, &mut tt(), &mut SearchControl::unlimited());
 
         // Should return beta (fail-high)
         assert_eq!(score, -500, "Stand-pat should cause beta cutoff");
@@ -288,7 +428,7 @@ mod tests {
         let mut pos = Position::from_fen("4k3/8/8/8/4q3/8/8/4RK2 w - -");
 
         // Quiescence should see the queen capture
-        let score = quiescence_search(&mut pos, -10000, 10000, Color::White);
+        let score = quiescence_search(&mut pos, -10000, 10000, Color::White, &mut tt(), &mut SearchControl::unlimited());
 
         // White should be winning after capturing the queen
         assert!(score > 500, "Score should reflect queen capture: {}", score);
@@ -301,7 +441,7 @@ mod tests {
         let mut pos = Position::from_fen("4k3/8/8/8/4p3/8/8/4K3 w - -");
 
         // Set alpha very high - delta pruning should kick in
-        let score = quiescence_search(&mut pos, 5000, 10000, Color::White);
+        let score = quiescence_search(&mut pos, 5000, 10000, Color::White, &mut tt(), &mut SearchControl::unlimited());
 
         // Should return alpha (delta pruning optimization)
         assert_eq!(score, 5000, "Delta pruning should return alpha when captures can't help");
@@ -313,7 +453,7 @@ mod tests {
         // Black king on a8 (not blocking e8), white pawn on e7 can promote
         let mut pos = Position::from_fen("k7/4P3/8/8/8/8/8/4K3 w - -");
 
-        let score = quiescence_search(&mut pos, -10000, 10000, Color::White);
+        let score = quiescence_search(&mut pos, -10000, 10000, Color::White, &mut tt(), &mut SearchControl::unlimited());
 
         // Should see and value the promotion highly
         assert!(score > 700, "Should recognize promotion value: {}", score);
@@ -325,10 +465,9 @@ mod tests {
         let mut pos = Position::default();
 
         // Should complete without hanging, even in complex position
-        let _score = quiescence_search(&mut pos, -10000, 10000, Color::White);
+        let _score = quiescence_search(&mut pos, -10000, 10000, Color::White, &mut tt(), &mut SearchControl::unlimited());
 
         // If we get here without timeout, depth limit is working
-        assert!(true, "Depth limit should prevent infinite recursion");
     }
 
     #[test]
@@ -359,7 +498,7 @@ mod tests {
     #[test]
     fn test_mvv_lva_ordering() {
         // Position where multiple captures are available
-        let mut pos = Position::from_fen("4k3/8/8/2q5/1rnb4/8/3R4/4K3 w - -");
+        let pos = Position::from_fen("4k3/8/8/2q5/1rnb4/8/3R4/4K3 w - -");
 
         let tactical_moves = generate_tactical_moves(&pos);
 
@@ -377,7 +516,7 @@ mod tests {
     #[test]
     fn test_promotion_prioritized_over_captures() {
         // Position with both promotion and captures available
-        let mut pos = Position::from_fen("4k3/4P3/8/8/4q3/8/8/4K3 w - -");
+        let pos = Position::from_fen("4k3/4P3/8/8/4q3/8/8/4K3 w - -");
 
         let tactical_moves = generate_tactical_moves(&pos);
 
@@ -399,10 +538,10 @@ mod tests {
         let mut pos = Position::from_fen("r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4");
 
         // Run with narrow window
-        let score1 = quiescence_search(&mut pos, -100, 100, Color::White);
+        let score1 = quiescence_search(&mut pos, -100, 100, Color::White, &mut tt(), &mut SearchControl::unlimited());
 
         // Run with wide window
-        let score2 = quiescence_search(&mut pos, -10000, 10000, Color::White);
+        let score2 = quiescence_search(&mut pos, -10000, 10000, Color::White, &mut tt(), &mut SearchControl::unlimited());
 
         // Both should give valid scores
         assert!(score1.abs() <= 10000, "Score should be within bounds");
@@ -415,21 +554,83 @@ mod tests {
         // White rook on d2 can capture Black rook on d4
         let mut pos = Position::from_fen("4k3/8/8/8/3r4/8/3R4/4K3 w - -");
 
-        let score = quiescence_search(&mut pos, -10000, 10000, Color::White);
+        let score = quiescence_search(&mut pos, -10000, 10000, Color::White, &mut tt(), &mut SearchControl::unlimited());
 
         // White wins a rook (value ~500), so score should be positive
         assert!(score > 400, "Should see rook capture, score: {}", score);
     }
 
+    #[test]
+    fn test_see_pruning() {
+        // QxP defended by a pawn loses the queen: not even tried
+        let mut pos = Position::from_fen("4k3/8/4p3/3p4/8/8/8/3QK3 w - -");
+        let mut ctl = SearchControl::unlimited();
+        let score = quiescence_search(&mut pos, -10000, 10000, Color::White, &mut tt(), &mut ctl);
+
+        assert_eq!(score, evaluate(&pos, Color::White));
+        assert_eq!(ctl.nodes, 1, "Losing capture should be pruned");
+    }
+
+    #[test]
+    fn test_checkmate_at_horizon() {
+        // Black is mated; standing pat would miss it
+        let mut pos = Position::from_fen("R5k1/5ppp/8/8/8/8/5PPP/6K1 b - -");
+        let score = quiescence_search(&mut pos, -MATE_SCORE, MATE_SCORE, Color::Black, &mut tt(), &mut SearchControl::unlimited());
+        assert_eq!(score, -MATE_SCORE);
+
+        // The same check with a way out: taking the checking rook
+        let mut pos = Position::from_fen("6k1/5ppp/8/8/8/8/1B3PPP/r5K1 w - -");
+        let score = quiescence_search(&mut pos, -MATE_SCORE, MATE_SCORE, Color::White, &mut tt(), &mut SearchControl::unlimited());
+        assert!(score > 200, "Should take the rook out of check, score: {}", score);
+    }
+
+    #[test]
+    fn test_quiet_checks() {
+        // Back-rank mate by a quiet rook move, only found with quiet checks
+        let mut pos = Position::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - -");
+        let score = quiescence_search(&mut pos, -MATE_SCORE, MATE_SCORE, Color::White, &mut tt(), &mut SearchControl::unlimited());
+        assert!(!is_mate_score(score));
+
+        let mut tt = tt();
+        let mut ctl = SearchControl::unlimited();
+        ctl.config.quiescence_checks = true;
+        let score = quiescence_search(&mut pos, -MATE_SCORE, MATE_SCORE, Color::White, &mut tt, &mut ctl);
+        assert_eq!(score, MATE_SCORE - 1);
+
+        // The result is kept for the next visit
+        let entry = tt.probe(pos.zobrist, 0).expect("first ply should be stored");
+        assert_eq!((entry.score, entry.node_type), (score, NodeType::Exact));
+        let nodes = ctl.nodes;
+        quiescence_search(&mut pos, -MATE_SCORE, MATE_SCORE, Color::White, &mut tt, &mut ctl);
+        assert_eq!(ctl.nodes, nodes + 1);
+    }
+
+    #[test]
+    fn test_quiet_checks_keep_deeper_entry() {
+        // A main search bound that does not cut here is not replaced
+        let mut pos = Position::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - -");
+        let mut tt = tt();
+        let stored = TranspositionTableEntry { hash: pos.zobrist, depth: 5, score: 50, best_move: None, node_type: NodeType::LowerBound };
+        tt.store(stored, 0);
+
+        let mut ctl = SearchControl::unlimited();
+        ctl.config.quiescence_checks = true;
+        let score = quiescence_search(&mut pos, -MATE_SCORE, MATE_SCORE, Color::White, &mut tt, &mut ctl);
+        assert_eq!(score, MATE_SCORE - 1);
+
+        let entry = tt.probe(pos.zobrist, 0).expect("entry should still be there");
+        assert_eq!((entry.depth, entry.score), (5, 50));
+    }
+
     #[test]
     fn test_per_move_delta_pruning() {
         // Position where one capture is good, others are weak
         let mut pos = Position::from_fen("4k3/8/8/8/q2p4/8/3R4/4K3 w - -");
 
         // Set alpha moderately high
-        let score = quiescence_search(&mut pos, 0, 10000, Color::White);
+        let score = quiescence_search(&mut pos, 0, 10000, Color::White, &mut tt(), &mut SearchControl::unlimited());
 
         // Should search queen capture but delta-prune pawn capture
-        assert!(score > 0 || score == 0, "Should handle per-move delta pruning");
+        assert!(score >= 0, "Should handle per-move delta pruning");
     }
 }
@@ -1,19 +1,16 @@
-// Iterative Deepening Search Orchestrator
+// Iterative Deepening Search Results
 //
-// This module implements iterative deepening search for chess move selection.
-// It progressively searches deeper depths (1, 2, 3, ...) up to max_depth,
-// using results from previous iterations to improve move ordering.
+// This module holds what a search reports: the final SearchResult and a
+// SearchInfo per completed depth and line. A MultiPV search finds the best
+// few root moves, each with its own score and line. The iterative deepening
+// loop itself lives in Searcher; iterative_deepening_search is a one-off
+// search with fresh tables.
 
 use crate::game_repr::{Position, Move, Color};
 use super::transposition_table::TranspositionTable;
-use super::negamax::negamax;
-use super::move_ordering::{generate_ordered_moves, KillerMoves, HistoryTable};
-
-// Use web-time for WASM compatibility (std::time::Instant doesn't work reliably in WASM)
-#[cfg(target_arch = "wasm32")]
-use web_time::Instant;
-#[cfg(not(target_arch = "wasm32"))]
-use std::time::Instant;
+use super::negamax::mate_distance;
+use super::limits::SearchLimits;
+use super::searcher::Searcher;
 
 /// Result of a search operation
 #[derive(Debug, Clone, Default)]
@@ -22,9 +19,20 @@ pub struct SearchResult {
     pub score: i32,
     pub depth: u8,
     pub depth_reached: u8, // Alias for depth (for compatibility)
+    pub completed_depth: u8, // Deepest iteration searched to the end
     pub nodes_searched: u64,
     pub time_ms: u64,
     pub principal_variation: Option<Vec<Move>>, // PV line (optional)
+    pub lines: Vec<PvLine>, // Best lines, best first (several with MultiPV)
+}
+
+/// One of the best lines of a search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PvLine {
+    /// Score from the side to move's point of view
+    pub score: i32,
+    /// The line, starting with its root move
+    pub pv: Vec<Move>,
 }
 
 impl SearchResult {
@@ -35,372 +43,131 @@ impl SearchResult {
             score: 0,
             depth: 0,
             depth_reached: 0,
+            completed_depth: 0,
             nodes_searched: 0,
             time_ms: 0,
             principal_variation: None,
+            lines: Vec::new(),
         }
     }
 }
 
-/// Perform iterative deepening search to find the best move
-///
-/// # Arguments
-/// * `pos` - Current position to search
-/// * `color` - Color to move
-/// * `max_depth` - Maximum search depth
-/// * `time_limit_ms` - Optional time limit in milliseconds
-///
-/// # Returns
-/// SearchResult containing the best move and search statistics
-pub fn iterative_deepening_search(
-    pos: &Position,
-    color: Color,
-    max_depth: u8,
-    time_limit_ms: Option<u64>,
-) -> SearchResult {
-    let start_time = Instant::now();
-    let mut best_result = SearchResult::new();
-    let mut total_nodes = 0u64;
-
-    // Clone position for searching (we need a mutable copy)
-    let mut search_pos = pos.clone();
-
-    // Initialize transposition table (shared across all iterations)
-    let mut tt = TranspositionTable::new(); // Default size: 1M entries (~40-80MB)
-
-    // Initialize killer moves and history table
-    let mut killers = KillerMoves::new();
-    let mut history = HistoryTable::new();
-
-    // Principal variation (best move from previous iteration)
-    let mut pv_move: Option<Move> = None;
-
-    // Ensure we search at least depth 1
-    let max_depth = max_depth.max(1);
-
-    // Iterative deepening loop
-    for depth in 1..=max_depth {
-        // Check time limit before starting new depth
-        if let Some(time_limit) = time_limit_ms {
-            if is_time_up(&start_time, time_limit) {
-                // Time is up, return best result from previous iteration
-                if depth > 1 {
-                    print_search_info(depth - 1, &best_result, &start_time);
-                }
-                break;
-            }
-        }
-
-        // Generate and order moves for this position
-        let moves = generate_ordered_moves(&search_pos, pv_move, &killers, &history, depth);
-
-        // If no legal moves, position is checkmate or stalemate
-        if moves.is_empty() {
-            best_result.depth = depth;
-            best_result.depth_reached = depth;
-            best_result.time_ms = start_time.elapsed().as_millis() as u64;
-
-            if search_pos.is_in_check(color) {
-                // Checkmate - very bad score
-                best_result.score = -100000;
-            } else {
-                // Stalemate - draw
-                best_result.score = 0;
-            }
-            return best_result;
-        }
-
-        // Search each move
-        let mut best_score = i32::MIN + 1;
-        let mut best_move_this_depth: Option<Move> = None;
-        let mut nodes_this_depth = 0u64;
-
-        let alpha = i32::MIN + 1;
-        let beta = i32::MAX;
-
-        for &mov in &moves {
-            // Make the move
-            let undo = search_pos.make_move_undoable(mov);
-
-            // Search this position
-            let (score, _) = negamax(
-                &mut search_pos,
-                depth - 1,
-                -beta,
-                -alpha,
-                color.opposite(),
-                &mut tt,
-                &mut killers,
-                &mut history,
-            );
-            let score = -score;
-            nodes_this_depth += 1; // Count this node
-
-            // Unmake the move
-            search_pos.unmake_move(mov, undo);
-
-            // Check if this is the best move so far
-            if score > best_score {
-                best_score = score;
-                best_move_this_depth = Some(mov);
-            }
-
-            // Check time limit during search
-            if let Some(time_limit) = time_limit_ms {
-                if is_time_up(&start_time, time_limit) {
-                    // Time is up mid-search
-                    // If we completed at least one move, use results from this depth
-                    // Otherwise, use results from previous depth
-                    if best_move_this_depth.is_some() {
-                        best_result.best_move = best_move_this_depth;
-                        best_result.score = best_score;
-                        best_result.depth = depth;
-                        best_result.depth_reached = depth;
-                        best_result.nodes_searched = total_nodes + nodes_this_depth;
-                        best_result.time_ms = start_time.elapsed().as_millis() as u64;
-                        print_search_info(depth, &best_result, &start_time);
-                    }
-                    return best_result;
-                }
-            }
-        }
-
-        // Update total nodes
-        total_nodes += nodes_this_depth;
+/// Progress report for one line of a completed iteration
+#[derive(Debug, Clone)]
+pub struct SearchInfo {
+    pub depth: u8,
+    /// Rank of the line, 1 for the best (MultiPV)
+    pub multipv: usize,
+    /// Score from the side to move's point of view
+    pub score: i32,
+    pub nodes: u64,
+    pub time_ms: u64,
+    /// Transposition table use in permille
+    pub hashfull: u32,
+    /// Best line, starting with the best move
+    pub pv: Vec<Move>,
+}
 
-        // Update best result for this depth
-        best_result.best_move = best_move_this_depth;
-        best_result.score = best_score;
-        best_result.depth = depth;
-        best_result.depth_reached = depth;
-        best_result.nodes_searched = total_nodes;
-        best_result.time_ms = start_time.elapsed().as_millis() as u64;
+impl SearchInfo {
+    /// Nodes per second
+    pub fn nps(&self) -> u64 {
+        (self.nodes * 1000).checked_div(self.time_ms).unwrap_or(self.nodes)
+    }
 
-        // Update PV move for next iteration
-        pv_move = best_move_this_depth;
+    /// Moves until mate (negative when the side to move is mated), if the
+    /// score is a mate score
+    pub fn mate_in(&self) -> Option<i32> {
+        mate_distance(self.score)
+    }
 
-        // Print search info for this depth
-        print_search_info(depth, &best_result, &start_time);
+    /// The report as a UCI `info` line, with the PV written in long
+    /// algebraic notation from `pos`
+    pub fn to_uci(&self, pos: &Position) -> String {
+        let score = match self.mate_in() {
+            Some(moves) => format!("mate {}", moves),
+            None => format!("cp {}", self.score),
+        };
 
-        // Early exit conditions
-        // If we found a mate, no need to search deeper
-        // MATE_SCORE is 30000, so mate scores are around 29900+
-        if best_score.abs() > 29000 {
-            break;
-        }
+        let mut line = pos.clone();
+        let pv: Vec<String> = self
+            .pv
+            .iter()
+            .map(|&mv| {
+                let uci = line.move_to_uci(mv);
+                line.mk_move(mv);
+                uci
+            })
+            .collect();
+
+        format!(
+            "info depth {} multipv {} score {} nodes {} nps {} hashfull {} time {} pv {}",
+            self.depth,
+            self.multipv,
+            score,
+            self.nodes,
+            self.nps(),
+            self.hashfull,
+            self.time_ms,
+            pv.join(" ")
+        )
     }
-
-    best_result
 }
 
-/// Perform iterative deepening search with aspiration windows
+/// Perform iterative deepening search to find the best move
 ///
-/// This is an optimization that narrows the alpha-beta window based on
-/// the previous iteration's score, which can lead to more cutoffs.
+/// A one-off search with fresh tables; use a [`Searcher`] to keep them
+/// between moves.
 ///
 /// # Arguments
 /// * `pos` - Current position to search
 /// * `color` - Color to move
 /// * `max_depth` - Maximum search depth
 /// * `time_limit_ms` - Optional time limit in milliseconds
+/// * `on_info` - Called with the report of each completed depth
 ///
 /// # Returns
 /// SearchResult containing the best move and search statistics
-#[allow(dead_code)]
-pub fn iterative_deepening_search_with_aspiration(
+pub fn iterative_deepening_search(
     pos: &Position,
     color: Color,
     max_depth: u8,
     time_limit_ms: Option<u64>,
+    on_info: &mut dyn FnMut(&SearchInfo),
 ) -> SearchResult {
-    let start_time = Instant::now();
-    let mut best_result = SearchResult::new();
-    let mut total_nodes = 0u64;
-
-    // Clone position for searching (we need a mutable copy)
-    let mut search_pos = pos.clone();
-
-    // Initialize transposition table (shared across all iterations)
-    let mut tt = TranspositionTable::new(); // Default size: 1M entries (~40-80MB)
-
-    // Initialize killer moves and history table
-    let mut killers = KillerMoves::new();
-    let mut history = HistoryTable::new();
-
-    // Principal variation (best move from previous iteration)
-    let mut pv_move: Option<Move> = None;
-    let mut prev_score = 0;
-
-    // Ensure we search at least depth 1
-    let max_depth = max_depth.max(1);
-
-    // Iterative deepening loop
-    for depth in 1..=max_depth {
-        // Check time limit before starting new depth
-        if let Some(time_limit) = time_limit_ms {
-            if is_time_up(&start_time, time_limit) {
-                if depth > 1 {
-                    print_search_info(depth - 1, &best_result, &start_time);
-                }
-                break;
-            }
-        }
-
-        // Generate and order moves for this position
-        let moves = generate_ordered_moves(&search_pos, pv_move, &killers, &history, depth);
-
-        // If no legal moves, position is checkmate or stalemate
-        if moves.is_empty() {
-            best_result.depth = depth;
-            best_result.depth_reached = depth;
-            best_result.time_ms = start_time.elapsed().as_millis() as u64;
-
-            if search_pos.is_in_check(color) {
-                best_result.score = -100000;
-            } else {
-                best_result.score = 0;
-            }
-            return best_result;
-        }
-
-        // Aspiration window settings
-        let window_size = if depth <= 3 { 500 } else { 50 };
-        let mut alpha = prev_score - window_size;
-        let mut beta = prev_score + window_size;
+    debug_assert_eq!(color, pos.side_to_move);
+    let limits = SearchLimits { max_depth, time_limit_ms, ..SearchLimits::default() };
+    Searcher::new().search(pos, limits, on_info)
+}
 
-        // For shallow depths, use full window
-        if depth <= 2 {
-            alpha = i32::MIN + 1;
-            beta = i32::MAX;
+/// The legal part of `line`, a line collected by the search from `pos`,
+/// extended up to `max_len` moves by following the best moves stored in the
+/// transposition table
+///
+/// The collected line ends early where the search took a table cutoff; the
+/// extension stops at the first missing or illegal entry.
+pub(super) fn complete_pv(pos: &Position, tt: &mut TranspositionTable, line: &[Move], max_len: usize) -> Vec<Move> {
+    let mut position = pos.clone();
+    let mut pv = Vec::with_capacity(max_len.max(line.len()));
+    for &mv in line {
+        if !position.all_legal_moves().contains(&mv) {
+            break;
         }
+        pv.push(mv);
+        position.mk_move(mv);
+    }
 
-        // Search with aspiration window (with re-search if needed)
-        let mut search_attempt = 0;
-        let max_attempts = 3;
-
-        let (best_score, best_move_this_depth, nodes_this_depth) = loop {
-            let mut best_score = i32::MIN + 1;
-            let mut best_move_this_depth: Option<Move> = None;
-            let mut nodes_this_depth = 0u64;
-
-            for &mov in &moves {
-                // Make the move
-                let undo = search_pos.make_move_undoable(mov);
-
-                // Search this position
-                let (score, _) = negamax(
-                    &mut search_pos,
-                    depth - 1,
-                    -beta,
-                    -alpha,
-                    color.opposite(),
-                    &mut tt,
-                    &mut killers,
-                    &mut history,
-                );
-                let score = -score;
-                nodes_this_depth += 1;
-
-                // Unmake the move
-                search_pos.unmake_move(mov, undo);
-
-                // Check if this is the best move so far
-                if score > best_score {
-                    best_score = score;
-                    best_move_this_depth = Some(mov);
-                }
-
-                // Check time limit during search
-                if let Some(time_limit) = time_limit_ms {
-                    if is_time_up(&start_time, time_limit) {
-                        if best_move_this_depth.is_some() {
-                            best_result.best_move = best_move_this_depth;
-                            best_result.score = best_score;
-                            best_result.depth = depth;
-                            best_result.depth_reached = depth;
-                            best_result.nodes_searched = total_nodes + nodes_this_depth;
-                            best_result.time_ms = start_time.elapsed().as_millis() as u64;
-                            print_search_info(depth, &best_result, &start_time);
-                        }
-                        return best_result;
-                    }
-                }
-            }
-
-            // Check if we need to re-search with wider window
-            if best_score <= alpha {
-                // Failed low, widen alpha
-                alpha = i32::MIN + 1;
-                search_attempt += 1;
-                if search_attempt >= max_attempts {
-                    break (best_score, best_move_this_depth, nodes_this_depth);
-                }
-                continue;
-            } else if best_score >= beta {
-                // Failed high, widen beta
-                beta = i32::MAX;
-                search_attempt += 1;
-                if search_attempt >= max_attempts {
-                    break (best_score, best_move_this_depth, nodes_this_depth);
-                }
-                continue;
-            } else {
-                // Search succeeded within window
-                break (best_score, best_move_this_depth, nodes_this_depth);
-            }
+    while pv.len() < max_len {
+        let Some(mv) = tt.probe(position.zobrist, 0).and_then(|entry| entry.best_move) else {
+            break;
         };
-
-        // Update total nodes
-        total_nodes += nodes_this_depth;
-
-        // Update best result for this depth
-        best_result.best_move = best_move_this_depth;
-        best_result.score = best_score;
-        best_result.depth = depth;
-        best_result.depth_reached = depth;
-        best_result.nodes_searched = total_nodes;
-        best_result.time_ms = start_time.elapsed().as_millis() as u64;
-
-        // Update PV move and score for next iteration
-        pv_move = best_move_this_depth;
-        prev_score = best_score;
-
-        // Print search info for this depth
-        print_search_info(depth, &best_result, &start_time);
-
-        // Early exit conditions
-        // MATE_SCORE is 30000, so mate scores are around 29900+
-        if best_score.abs() > 29000 {
+        if !position.all_legal_moves().contains(&mv) {
             break;
         }
+        pv.push(mv);
+        position.mk_move(mv);
     }
 
-    best_result
-}
-
-/// Check if time limit has been exceeded
-fn is_time_up(start_time: &Instant, time_limit_ms: u64) -> bool {
-    start_time.elapsed().as_millis() as u64 >= time_limit_ms
-}
-
-/// Print search information for a completed depth
-fn print_search_info(depth: u8, result: &SearchResult, start_time: &Instant) {
-    let elapsed_ms = start_time.elapsed().as_millis() as u64;
-    let nps = if elapsed_ms > 0 {
-        (result.nodes_searched as f64 / elapsed_ms as f64 * 1000.0) as u64
-    } else {
-        result.nodes_searched
-    };
-
-    println!(
-        "info depth {} score cp {} nodes {} time {} nps {} pv {:?}",
-        depth,
-        result.score,
-        result.nodes_searched,
-        elapsed_ms,
-        nps,
-        result.best_move
-    );
+    pv
 }
 
 #[cfg(test)]
@@ -410,8 +177,8 @@ mod tests {
 
     #[test]
     fn test_search_starting_position() {
-        let mut pos = Position::default();
-        let result = iterative_deepening_search(&mut pos, Color::White, 3, None);
+        let pos = Position::default();
+        let result = iterative_deepening_search(&pos, Color::White, 3, None, &mut |_| {});
 
         // Should find a move
         assert!(result.best_move.is_some());
@@ -421,9 +188,9 @@ mod tests {
 
     #[test]
     fn test_search_with_time_limit() {
-        let mut pos = Position::default();
+        let pos = Position::default();
         // Very short time limit should still complete at least depth 1
-        let result = iterative_deepening_search(&mut pos, Color::White, 10, Some(100));
+        let result = iterative_deepening_search(&pos, Color::White, 10, Some(100), &mut |_| {});
 
         assert!(result.best_move.is_some());
         assert!(result.depth >= 1);
@@ -434,8 +201,8 @@ mod tests {
     fn test_search_mate_in_one() {
         // Position with mate in one: white to move
         // Back rank mate pattern
-        let mut pos = Position::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1");
-        let result = iterative_deepening_search(&mut pos, Color::White, 5, None);
+        let pos = Position::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1");
+        let result = iterative_deepening_search(&pos, Color::White, 5, None, &mut |_| {});
 
         // Should find the mate
         assert!(result.best_move.is_some());
@@ -447,8 +214,8 @@ mod tests {
     #[test]
     fn test_search_no_legal_moves_checkmate() {
         // Checkmate position
-        let mut pos = Position::from_fen("rnb1kbnr/pppp1ppp/4p3/8/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 0 1");
-        let result = iterative_deepening_search(&mut pos, Color::White, 3, None);
+        let pos = Position::from_fen("rnb1kbnr/pppp1ppp/4p3/8/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 0 1");
+        let result = iterative_deepening_search(&pos, Color::White, 3, None, &mut |_| {});
 
         // No legal moves, in check = checkmate
         assert!(result.best_move.is_none());
@@ -458,8 +225,8 @@ mod tests {
     #[test]
     fn test_search_no_legal_moves_stalemate() {
         // Stalemate position
-        let mut pos = Position::from_fen("k7/8/1Q6/8/8/8/8/K7 b - - 0 1");
-        let result = iterative_deepening_search(&mut pos, Color::Black, 3, None);
+        let pos = Position::from_fen("k7/8/1Q6/8/8/8/8/K7 b - - 0 1");
+        let result = iterative_deepening_search(&pos, Color::Black, 3, None, &mut |_| {});
 
         // No legal moves, not in check = stalemate
         assert!(result.best_move.is_none());
@@ -468,12 +235,73 @@ mod tests {
 
     #[test]
     fn test_aspiration_window_search() {
-        let mut pos = Position::default();
-        let result = iterative_deepening_search_with_aspiration(&mut pos, Color::White, 3, None);
+        let pos = Position::default();
+        let mut searcher = Searcher::new();
+        searcher.config.aspiration_windows = true;
+        let result = searcher.search(&pos, SearchLimits::depth(3), &mut |_| {});
 
         // Should find a move
         assert!(result.best_move.is_some());
         assert!(result.depth > 0);
         assert!(result.nodes_searched > 0);
     }
+
+    #[test]
+    fn test_search_node_limit() {
+        let pos = Position::default();
+        let limits = SearchLimits { node_limit: Some(2000), ..SearchLimits::default() };
+        let result = Searcher::new().search(&pos, limits, &mut |_| {});
+
+        assert!(result.best_move.is_some());
+        assert_eq!(result.nodes_searched, 2000);
+    }
+
+    #[test]
+    fn test_search_stop_flag_still_returns_move() {
+        use std::sync::atomic::AtomicBool;
+        use std::sync::Arc;
+
+        // Stopped before it starts: the search must still pick a legal move
+        let pos = Position::default();
+        let stop = Arc::new(AtomicBool::new(true));
+        let limits = SearchLimits { stop: Some(stop), node_limit: Some(1), ..SearchLimits::default() };
+        let result = Searcher::new().search(&pos, limits, &mut |_| {});
+
+        let mv = result.best_move.expect("a move even when stopped");
+        assert!(pos.all_legal_moves().contains(&mv));
+    }
+
+    #[test]
+    fn test_search_reports_every_depth() {
+        let pos = Position::default();
+        let mut reports = Vec::new();
+        let result = Searcher::new().search(&pos, SearchLimits::depth(3), &mut |info| {
+            reports.push(info.clone())
+        });
+
+        assert_eq!(reports.iter().map(|info| info.depth).collect::<Vec<_>>(), vec![1, 2, 3]);
+        let last = reports.last().unwrap();
+        assert_eq!(last.pv.first().copied(), result.best_move);
+        assert_eq!(last.nodes, result.nodes_searched);
+        assert!(reports.windows(2).all(|w| w[0].nodes < w[1].nodes));
+    }
+
+    #[test]
+    fn test_search_info_uci_line() {
+        let pos = Position::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1");
+        let mut last = None;
+        Searcher::new().search(&pos, SearchLimits::depth(3), &mut |info| {
+            last = Some(info.clone())
+        });
+
+        let info = last.unwrap();
+        assert_eq!(info.mate_in(), Some(1));
+        let line = info.to_uci(&pos);
+        assert!(line.starts_with("info depth "), "{}", line);
+        assert!(line.contains(" score mate 1 "), "{}", line);
+        assert!(line.ends_with(" pv a1a8"), "{}", line);
+
+        let info = SearchInfo { depth: 2, multipv: 1, score: -35, nodes: 500, time_ms: 250, hashfull: 12, pv: Vec::new() };
+        assert_eq!(info.to_uci(&pos), "info depth 2 multipv 1 score cp -35 nodes 500 nps 2000 hashfull 12 time 250 pv ");
+    }
 }
@@ -0,0 +1,331 @@
+// Search Handle - Searching Without Blocking the Caller
+//
+// A SearchHandle runs a Searcher on a worker thread. The caller polls it,
+// typically once per frame: every poll picks up the progress reports of the
+// iterations finished since the last one, and the final poll hands back the
+// result together with the searcher, so its tables carry over to the next
+// move. Setting the shared stop flag (or dropping the handle) ends the search
+// early; a stopped search still returns the best move found so far. Should
+// the worker thread die without a result, the next poll reports that as a
+// finished search without a move (or searcher) rather than waiting forever.
+//
+// There are no threads in the browser, so on wasm the search runs to
+// completion inside spawn() and the first poll returns its result.
+
+use crate::game_repr::Position;
+use super::limits::SearchLimits;
+use super::search::{SearchInfo, SearchResult};
+use super::searcher::Searcher;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+
+/// What the worker sends back
+enum SearchEvent {
+    Info(SearchInfo),
+    Finished(SearchResult, Box<Searcher>),
+}
+
+/// A completed background search
+pub struct FinishedSearch {
+    pub result: SearchResult,
+    /// The searcher the search was started with, for the next search;
+    /// `None` if the worker thread died, taking it along
+    pub searcher: Option<Searcher>,
+}
+
+/// A search running in the background
+pub struct SearchHandle {
+    position: Position,
+    stop: Arc<AtomicBool>,
+    events: Receiver<SearchEvent>,
+    /// Latest progress report of every line, best first
+    lines: Vec<SearchInfo>,
+    /// Whether the result has been returned
+    finished: bool,
+}
+
+impl SearchHandle {
+    /// Starts searching `position` with `searcher` within `limits`
+    ///
+    /// If `limits` has a stop flag, the handle shares it; otherwise it
+    /// creates one.
+    pub fn spawn(searcher: Searcher, position: Position, mut limits: SearchLimits) -> Self {
+        let stop = limits.stop.get_or_insert_with(|| Arc::new(AtomicBool::new(false))).clone();
+        let (sender, events) = mpsc::channel();
+
+
+        let pos = position.clone();
+        let run = move || search_and_send(searcher, &pos, limits, &sender);
+        #[cfg(target_arch = "wasm32")]
+        run();
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(run);
+
+        Self { position, stop, events, lines: Vec::new(), finished: false }
+    }
+
+    /// The position being searched
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+
+    /// The latest progress report of the best line received by
+    /// [`poll`](Self::poll)
+    pub fn info(&self) -> Option<&SearchInfo> {
+        self.lines.first()
+    }
+
+    /// The latest progress report of each line of a MultiPV search, best
+    /// first; a line not yet searched at the current depth keeps the report
+    /// from the depth before
+    pub fn lines(&self) -> &[SearchInfo] {
+        &self.lines
+    }
+
+    /// Asks the search to finish with the best move found so far
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Picks up progress reports without blocking, returning the result once
+    /// the search has finished
+    ///
+    /// If the worker thread died (it panicked), the result has no best move
+    /// and the searcher is gone. After it has returned the result once, it
+    /// keeps returning `None`.
+    pub fn poll(&mut self) -> Option<FinishedSearch> {
+        loop {
+            match self.events.try_recv() {
+                Ok(SearchEvent::Info(info)) => {
+                    let index = info.multipv.max(1) - 1;
+                    if index >= self.lines.len() {
+                        self.lines.resize(index + 1, info.clone());
+                    }
+                    self.lines[index] = info;
+                }
+                Ok(SearchEvent::Finished(result, searcher)) => {
+                    self.finished = true;
+                    return Some(FinishedSearch { result, searcher: Some(*searcher) });
+                }
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) if self.finished => return None,
+                Err(TryRecvError::Disconnected) => {
+                    self.finished = true;
+                    return Some(FinishedSearch { result: SearchResult::new(), searcher: None });
+                }
+            }
+        }
+    }
+}
+
+/// Stops the search if it is still running
+impl Drop for SearchHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// A searcher asked for moves over several polls, searching in the
+/// background in between
+///
+/// This is how the GUI players think without freezing the window: each frame
+/// they call [`poll_move`](Self::poll_move), which starts a search the first
+/// time and returns its result once it is done.
+pub struct BackgroundSearcher {
+    /// `None` while a search owns it, or after a search died with it
+    searcher: Option<Searcher>,
+    search: Option<SearchHandle>,
+}
+
+impl BackgroundSearcher {
+    pub fn new() -> Self {
+        Self { searcher: Some(Searcher::new()), search: None }
+    }
+
+    /// Result of the search of `position`, once it has finished
+    ///
+    /// Starts a search within the limits `limits` builds if none is running;
+    /// while one is, `limits` is not called. A search of a
+    /// different position (the game was taken back meanwhile) is abandoned
+    /// and a new one started. A search whose worker thread died returns a
+    /// result without a best move.
+    pub fn poll_move(&mut self, position: &Position, limits: impl FnOnce() -> SearchLimits) -> Option<SearchResult> {
+        if self.search.as_ref().is_some_and(|search| search.position().zobrist != position.zobrist) {
+            self.cancel();
+        }
+        let searcher = &mut self.searcher;
+        let search = self
+            .search
+            .get_or_insert_with(|| SearchHandle::spawn(searcher.take().unwrap_or_default(), position.clone(), limits()));
+
+        let finished = search.poll()?;
+        self.search = None;
+        self.searcher = finished.searcher;
+        Some(finished.result)
+    }
+
+    /// Whether a search is running
+    pub fn is_thinking(&self) -> bool {
+        self.search.is_some()
+    }
+
+    /// Latest progress report of the running search
+    pub fn info(&self) -> Option<&SearchInfo> {
+        self.search.as_ref().and_then(SearchHandle::info)
+    }
+
+    /// Latest progress report of each line of the running search
+    pub fn lines(&self) -> &[SearchInfo] {
+        self.search.as_ref().map_or(&[], SearchHandle::lines)
+    }
+
+    /// Makes the running search finish with the best move found so far
+    pub fn stop(&self) {
+        if let Some(search) = &self.search {
+            search.stop();
+        }
+    }
+
+    /// Abandons the running search along with the tables it was using
+    pub fn cancel(&mut self) {
+        self.search = None;
+    }
+
+    /// The searcher, for searching on the calling thread; abandons the
+    /// running search
+    pub fn searcher_mut(&mut self) -> &mut Searcher {
+        self.cancel();
+        self.searcher.get_or_insert_with(Searcher::new)
+    }
+
+    /// Forgets everything learned in the previous game
+    pub fn new_game(&mut self) {
+        self.searcher_mut().new_game();
+    }
+}
+
+impl Default for BackgroundSearcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Copies the searcher's tables; a running search is not copied
+impl Clone for BackgroundSearcher {
+    fn clone(&self) -> Self {
+        Self { searcher: Some(self.searcher.clone().unwrap_or_default()), search: None }
+    }
+}
+
+/// Worker body: sends every progress report, then the result and searcher
+///
+/// Send errors mean the handle was dropped and are ignored.
+fn search_and_send(mut searcher: Searcher, pos: &Position, limits: SearchLimits, sender: &Sender<SearchEvent>) {
+    let result = searcher.search(pos, limits, &mut |info| {
+        let _ = sender.send(SearchEvent::Info(info.clone()));
+    });
+    let _ = sender.send(SearchEvent::Finished(result, Box::new(searcher)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    fn wait(handle: &mut SearchHandle) -> FinishedSearch {
+        loop {
+            if let Some(finished) = handle.poll() {
+                return finished;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_poll_until_finished() {
+        // A free queen: no mate, so every depth gets searched
+        let pos = Position::from_fen("4k3/8/8/3q4/8/8/8/3RK3 w - -");
+        let mut handle = SearchHandle::spawn(Searcher::with_hash_mb(4), pos.clone(), SearchLimits::depth(3));
+        assert_eq!(handle.position().zobrist, pos.zobrist);
+
+        let finished = wait(&mut handle);
+        assert_eq!(finished.result.best_move, pos.parse_san("Rxd5").ok());
+        assert_eq!(finished.result.depth, 3);
+        assert!(handle.info().is_some_and(|info| info.pv.first() == finished.result.best_move.as_ref()));
+        assert!(finished.searcher.unwrap().transposition_table().size() > 0);
+
+        // Nothing more after the result
+        assert!(handle.poll().is_none());
+    }
+
+    #[test]
+    fn test_dead_worker_reported() {
+        // A worker that went away without sending anything
+        let pos = Position::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - -");
+        let (_, events) = mpsc::channel();
+        let mut handle = SearchHandle {
+            position: pos,
+            stop: Arc::new(AtomicBool::new(false)),
+            events,
+            lines: Vec::new(),
+            finished: false,
+        };
+
+        let finished = handle.poll().expect("a dead worker is reported");
+        assert!(finished.result.best_move.is_none());
+        assert!(finished.searcher.is_none());
+        assert!(handle.poll().is_none());
+    }
+
+    #[test]
+    fn test_stop() {
+        // Unlimited search, ended from the polling side
+        let mut handle = SearchHandle::spawn(Searcher::with_hash_mb(4), Position::default(), SearchLimits::default());
+        thread::sleep(Duration::from_millis(50));
+        assert!(handle.poll().is_none());
+
+        handle.stop();
+        let finished = wait(&mut handle);
+        assert!(finished.result.best_move.is_some());
+    }
+
+    #[test]
+    fn test_multi_pv_lines() {
+        let limits = SearchLimits { multi_pv: 3, ..SearchLimits::depth(3) };
+        let mut handle = SearchHandle::spawn(Searcher::with_hash_mb(4), Position::default(), limits);
+
+        let finished = wait(&mut handle);
+        let lines = handle.lines();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().enumerate().all(|(index, info)| info.multipv == index + 1 && info.depth == 3));
+        assert_eq!(lines[0].pv, finished.result.lines[0].pv);
+    }
+
+    #[test]
+    fn test_background_searcher() {
+        let pos = Position::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - -");
+        let mut searcher = BackgroundSearcher::new();
+        assert!(!searcher.is_thinking());
+
+        let result = loop {
+            if let Some(result) = searcher.poll_move(&pos, || SearchLimits::depth(3)) {
+                break result;
+            }
+            assert!(searcher.is_thinking());
+            thread::sleep(Duration::from_millis(1));
+        };
+        assert_eq!(result.best_move, pos.parse_san("Ra8").ok());
+        assert!(!searcher.is_thinking());
+
+        // A search of another position is replaced
+        assert!(searcher.poll_move(&Position::default(), SearchLimits::default).is_none());
+        assert!(searcher.poll_move(&pos, SearchLimits::default).is_none());
+        assert_eq!(searcher.search.as_ref().unwrap().position().zobrist, pos.zobrist);
+
+        searcher.cancel();
+        assert!(!searcher.is_thinking());
+        assert_eq!(searcher.searcher_mut().transposition_table().size(), 0);
+    }
+}
@@ -0,0 +1,691 @@
+// Searcher - Iterative Deepening With Persistent Tables
+//
+// A Searcher owns the transposition table, killer moves and history table and
+// keeps them between searches, so what it learned while choosing one move
+// still helps with the next. Tables are aged at the start of every search and
+// cleared by new_game().
+//
+// Each iteration searches the root moves in order, raising alpha as better
+// moves are found. With aspiration windows enabled, iterations from depth 3 on
+// start with a narrow window around the previous score and widen it when the
+// score falls outside.
+//
+// MultiPV: to find the best N lines, each iteration searches the root N
+// times, every time leaving out the moves of the lines already found. Each
+// line gets its own aspiration window around its score from the previous
+// iteration, whose lines are searched first.
+//
+// Lazy SMP: with more than one thread, helpers run the same iterative
+// deepening loop on their own copies of the move ordering tables. They share
+// nothing but the transposition table, where they leave results the main
+// thread then finds instead of searching them again. Helpers alternate their
+// starting depth so they do not all walk the same tree in lockstep.
+
+use crate::game_repr::{Move, Position};
+use super::limits::{SearchControl, SearchLimits};
+use super::move_ordering::{generate_ordered_moves, KillerMoves, HistoryTable};
+use super::negamax::{is_mate_score, negamax};
+use super::search::{complete_pv, PvLine, SearchInfo, SearchResult};
+use super::transposition_table::TranspositionTable;
+use std::cmp::Reverse;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+/// Transposition table size used by `Searcher::new`
+pub const DEFAULT_HASH_MB: usize = 40;
+
+/// Scores bounding a full-width root window
+const FULL_WINDOW: (i32, i32) = (i32::MIN + 1, i32::MAX);
+
+/// Search features that can be switched on and off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchConfig {
+    /// Start iterations in a narrow window around the previous score
+    pub aspiration_windows: bool,
+    /// Centipawns a draw is worth less than equality to the side searched
+    /// for; negative to prefer draws
+    pub contempt: i32,
+    /// Search quiet moves late in the ordering to a reduced depth
+    pub late_move_reductions: bool,
+    /// Cut nodes whose static evaluation is far above beta
+    pub reverse_futility_pruning: bool,
+    /// Skip quiet moves at nodes whose static evaluation is far below alpha
+    pub futility_pruning: bool,
+    /// Drop into the quiescence search at nodes far below alpha
+    pub razoring: bool,
+    /// Search one ply deeper when in check
+    pub check_extensions: bool,
+    /// Search one ply shallower at nodes without a hash move
+    pub internal_iterative_reductions: bool,
+    /// Also try quiet checks at the first ply of the quiescence search
+    pub quiescence_checks: bool,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            aspiration_windows: true,
+            contempt: 0,
+            late_move_reductions: true,
+            reverse_futility_pruning: true,
+            futility_pruning: true,
+            razoring: true,
+            check_extensions: true,
+            internal_iterative_reductions: true,
+            quiescence_checks: false,
+        }
+    }
+}
+
+/// Iterative deepening search that keeps its tables between moves
+#[derive(Clone)]
+pub struct Searcher {
+    tt: TranspositionTable,
+    killers: KillerMoves,
+    history: HistoryTable,
+    hash_mb: usize,
+    pub config: SearchConfig,
+}
+
+impl Searcher {
+    /// A searcher with a [`DEFAULT_HASH_MB`] transposition table
+    pub fn new() -> Self {
+        Self::with_hash_mb(DEFAULT_HASH_MB)
+    }
+
+    /// A searcher whose transposition table uses about `hash_mb` megabytes
+    pub fn with_hash_mb(hash_mb: usize) -> Self {
+        Self {
+            tt: TranspositionTable::with_size_mb(hash_mb),
+            killers: KillerMoves::new(),
+            history: HistoryTable::new(),
+            hash_mb,
+            config: SearchConfig::default(),
+        }
+    }
+
+    pub fn hash_mb(&self) -> usize {
+        self.hash_mb
+    }
+
+    /// Resizes the transposition table, discarding its contents
+    pub fn set_hash_mb(&mut self, hash_mb: usize) {
+        self.hash_mb = hash_mb;
+        self.tt = TranspositionTable::with_size_mb(hash_mb);
+    }
+
+    /// Forgets everything learned in the previous game
+    pub fn new_game(&mut self) {
+        self.tt.clear();
+        self.killers.clear();
+        self.history.clear();
+    }
+
+    pub fn transposition_table(&self) -> &TranspositionTable {
+        &self.tt
+    }
+
+    /// Searches `pos` for the side to move within `limits`
+    ///
+    /// `on_info` is called after every completed depth (and once more for a
+    /// depth cut short by a limit, if it produced a move). If a limit is hit
+    /// before any move has been searched, the first legal move is returned so
+    /// there is always a move to play.
+    ///
+    /// With `limits.multi_pv` above one, that many lines are reported per
+    /// depth and returned in [`SearchResult::lines`].
+    ///
+    /// With `limits.threads` above one, helper threads search the same
+    /// position through the shared transposition table (Lazy SMP). Only this
+    /// thread reports progress and honours the time limits; the node limit
+    /// counts the nodes of all threads. Helpers stop when this thread does,
+    /// and the deepest iteration any thread finished is returned (without
+    /// MultiPV, where the helpers only search one line).
+    pub fn search(
+        &mut self,
+        pos: &Position,
+        limits: SearchLimits,
+        on_info: &mut dyn FnMut(&SearchInfo),
+    ) -> SearchResult {
+        self.tt.new_search();
+        self.killers.clear();
+        self.history.age();
+
+        // No threads in the browser
+        let threads = if cfg!(target_arch = "wasm32") { 1 } else { limits.threads.max(1) };
+        let multi_pv = limits.multi_pv;
+        if threads == 1 {
+            return self.iterate(pos, SearchControl::new(limits), 1, true, on_info);
+        }
+
+        // Every thread counts its nodes here, so the node limit and the
+        // reported node count cover them all
+        let nodes = Arc::new(AtomicU64::new(0));
+        let stop_helpers = Arc::new(AtomicBool::new(false));
+        let helper_limits = SearchLimits {
+            max_depth: limits.max_depth,
+            node_limit: limits.node_limit,
+            history: limits.history.clone(),
+            stop: Some(stop_helpers.clone()),
+            ..SearchLimits::default()
+        };
+        let mut helpers: Vec<Searcher> = (1..threads).map(|_| self.helper()).collect();
+
+        let (mut result, helper_results) = thread::scope(|scope| {
+            let handles: Vec<_> = helpers
+                .iter_mut()
+                .enumerate()
+                .map(|(index, helper)| {
+                    let mut ctl = SearchControl::new(helper_limits.clone());
+                    ctl.share_nodes(nodes.clone());
+                    // Every other helper starts one ply deeper, so the
+                    // threads spread over neighbouring depths
+                    let start_depth = 1 + (index as u8 + 1) % 2;
+                    scope.spawn(move || helper.iterate(pos, ctl, start_depth, false, &mut |_| {}))
+                })
+                .collect();
+
+            let mut ctl = SearchControl::new(limits);
+            ctl.share_nodes(nodes.clone());
+            let result = self.iterate(pos, ctl, 1, true, on_info);
+            stop_helpers.store(true, Ordering::Relaxed);
+            let helper_results: Vec<SearchResult> =
+                handles.into_iter().map(|handle| handle.join().expect("search thread panicked")).collect();
+            (result, helper_results)
+        });
+
+        for helper in helper_results {
+            result.nodes_searched += helper.nodes_searched;
+            if multi_pv <= 1 && helper.completed_depth > result.completed_depth && helper.best_move.is_some() {
+                result.best_move = helper.best_move;
+                result.score = helper.score;
+                result.depth = helper.depth;
+                result.depth_reached = helper.depth_reached;
+                result.completed_depth = helper.completed_depth;
+                result.principal_variation = helper.principal_variation;
+                result.lines = helper.lines;
+            }
+        }
+        result
+    }
+
+    /// A searcher for a helper thread, sharing this one's transposition
+    /// table and starting from copies of its move ordering tables
+    fn helper(&self) -> Searcher {
+        Searcher {
+            tt: self.tt.share(),
+            killers: self.killers.clone(),
+            history: self.history.clone(),
+            hash_mb: self.hash_mb,
+            config: self.config,
+        }
+    }
+
+    /// Iterative deepening from `start_depth`
+    ///
+    /// With `keep_partial`, an iteration stopped after some root moves still
+    /// gives the result; otherwise the result is always the last iteration
+    /// searched to the end, as helper threads need.
+    fn iterate(
+        &mut self,
+        pos: &Position,
+        mut ctl: SearchControl,
+        start_depth: u8,
+        keep_partial: bool,
+        on_info: &mut dyn FnMut(&SearchInfo),
+    ) -> SearchResult {
+        let color = pos.side_to_move;
+        let mut best_result = SearchResult::new();
+        ctl.config = self.config;
+        ctl.set_root(pos.zobrist);
+        // Clone position for searching (we need a mutable copy)
+        let mut search_pos = pos.clone();
+
+        // Principal variation (best move from previous iteration)
+        let mut pv_move: Option<Move> = None;
+        let mut fallback_move: Option<Move> = None;
+        // Best lines of the previous iteration, best first
+        let mut lines: Vec<PvLine> = Vec::new();
+        let multi_pv = ctl.multi_pv();
+        // Recent best-move changes between iterations, decaying by half
+        // every iteration; an unstable best move earns more time
+        let mut best_move_changes = 0;
+
+        // Iterative deepening loop
+        for depth in start_depth.min(ctl.max_depth())..=ctl.max_depth() {
+            // Check limits before starting new depth (the first always starts)
+            if depth > start_depth && ctl.check() {
+                break;
+            }
+
+            // Generate and order moves for this position
+            let mut moves = generate_ordered_moves(&search_pos, pv_move, &self.killers, &self.history, depth);
+
+            // If no legal moves, position is checkmate or stalemate
+            if moves.is_empty() {
+                best_result.depth = depth;
+                best_result.depth_reached = depth;
+                best_result.completed_depth = depth;
+                best_result.time_ms = ctl.elapsed_ms();
+
+                if search_pos.is_in_check(color) {
+                    // Checkmate - very bad score
+                    best_result.score = -100000;
+                } else {
+                    // Stalemate - draw
+                    best_result.score = 0;
+                }
+                return best_result;
+            }
+            fallback_move = fallback_move.or(Some(moves[0]));
+            let iteration_start = ctl.elapsed_ms();
+
+            // The previous iteration's lines go first, in their order
+            moves.sort_by_key(|&mv| lines.iter().position(|line| line.pv[0] == mv).unwrap_or(usize::MAX));
+
+            // One root search per line, each without the moves already taken
+            let mut depth_lines: Vec<PvLine> = Vec::with_capacity(multi_pv);
+            let mut root_moves = moves;
+            while depth_lines.len() < multi_pv && !root_moves.is_empty() {
+                let prev_score = lines.get(depth_lines.len()).map_or(0, |line| line.score);
+
+                // Re-search with a wider window until the score falls inside it
+                let (mut alpha, mut beta) = self.aspiration_window(depth, prev_score);
+                let (score, best_move) = loop {
+                    let (score, best_move) =
+                        self.search_root(&mut search_pos, &root_moves, depth, alpha, beta, &mut ctl);
+                    if ctl.aborted {
+                        break (score, best_move);
+                    }
+                    if score <= alpha && alpha != FULL_WINDOW.0 {
+                        alpha = FULL_WINDOW.0;
+                    } else if score >= beta && beta != FULL_WINDOW.1 {
+                        beta = FULL_WINDOW.1;
+                    } else {
+                        break (score, best_move);
+                    }
+                };
+
+                let Some(best_move) = best_move else {
+                    break;
+                };
+                root_moves.retain(|mv| *mv != best_move);
+                let pv = complete_pv(&search_pos, &mut self.tt, ctl.pv.line(0), depth as usize);
+                debug_assert_eq!(pv.first(), Some(&best_move));
+                depth_lines.push(PvLine { score, pv });
+                if ctl.aborted {
+                    break;
+                }
+            }
+
+            // Stopped before any move at this depth finished: keep the
+            // previous depth's result
+            if depth_lines.is_empty() {
+                break;
+            }
+            if ctl.aborted && !keep_partial {
+                break;
+            }
+
+            // Stopped before every line was searched: the previous depth's
+            // lines fill the rest
+            for line in &lines {
+                if depth_lines.len() < multi_pv && !depth_lines.iter().any(|taken| taken.pv[0] == line.pv[0]) {
+                    depth_lines.push(line.clone());
+                }
+            }
+            depth_lines.sort_by_key(|line| Reverse(line.score));
+            lines = depth_lines;
+            let best_score = lines[0].score;
+            let best_move = lines[0].pv[0];
+
+            // Update best result for this depth
+            best_result.best_move = Some(best_move);
+            best_result.score = best_score;
+            best_result.depth = depth;
+            best_result.depth_reached = depth;
+            if !ctl.aborted {
+                best_result.completed_depth = depth;
+            }
+            best_result.nodes_searched = ctl.nodes;
+            best_result.time_ms = ctl.elapsed_ms();
+            best_result.principal_variation = Some(lines[0].pv.clone());
+            best_result.lines = lines.clone();
+
+            best_move_changes /= 2;
+            if pv_move.is_some_and(|previous| previous != best_move) {
+                best_move_changes += 2;
+            }
+
+            // Update PV move for next iteration
+            pv_move = Some(best_move);
+
+            let total_nodes = ctl.total_nodes();
+            let hashfull = self.tt.hashfull();
+            for (index, line) in lines.iter().enumerate() {
+                on_info(&SearchInfo {
+                    depth,
+                    multipv: index + 1,
+                    score: line.score,
+                    nodes: total_nodes,
+                    time_ms: best_result.time_ms,
+                    hashfull,
+                    pv: line.pv.clone(),
+                });
+            }
+
+            // Early exit conditions
+            // If we found a mate, no need to search deeper
+            // MATE_SCORE is 30000, so mate scores are around 29900+
+            if ctl.aborted || best_score.abs() > 29000 {
+                break;
+            }
+
+            // Stop at the time manager's soft limit, or when the next
+            // iteration is unlikely to finish in time
+            if !ctl.start_iteration(best_result.time_ms - iteration_start, best_move_changes) {
+                break;
+            }
+        }
+
+        if best_result.best_move.is_none() {
+            best_result.best_move = fallback_move;
+        }
+        best_result.nodes_searched = ctl.nodes;
+        best_result.time_ms = ctl.elapsed_ms();
+        best_result
+    }
+
+    /// Root window for an iteration: full width for the first iterations,
+    /// after a mate score, or with aspiration windows switched off
+    fn aspiration_window(&self, depth: u8, prev_score: i32) -> (i32, i32) {
+        if !self.config.aspiration_windows || depth <= 2 || is_mate_score(prev_score) {
+            return FULL_WINDOW;
+        }
+        let window_size = if depth <= 3 { 500 } else { 50 };
+        (prev_score - window_size, prev_score + window_size)
+    }
+
+    /// Searches every root move in `(alpha, beta)`, returning the best score
+    /// and move
+    ///
+    /// Stops early on a beta cutoff (the caller widens the window and searches
+    /// again) or when a limit is hit, in which case the move being searched
+    /// is not counted.
+    fn search_root(
+        &mut self,
+        pos: &mut Position,
+        moves: &[Move],
+        depth: u8,
+        mut alpha: i32,
+        beta: i32,
+        ctl: &mut SearchControl,
+    ) -> (i32, Option<Move>) {
+        let color = pos.side_to_move;
+        let mut best_score = FULL_WINDOW.0;
+        let mut best_move = None;
+        ctl.pv.clear(0);
+
+        for &mov in moves {
+            // Make the move
+            let undo = pos.make_move_undoable(mov);
+            ctl.enter(pos.zobrist);
+
+            // Search this position
+            let (score, _) = negamax(
+                pos,
+                depth - 1,
+                -beta,
+                -alpha,
+                color.opposite(),
+                &mut self.tt,
+                &mut self.killers,
+                &mut self.history,
+                ctl,
+            );
+            let score = -score;
+
+            // Unmake the move
+            pos.unmake_move(mov, undo);
+            ctl.leave();
+
+            // A move whose search was cut short has no usable score
+            if ctl.aborted {
+                break;
+            }
+
+            // Check if this is the best move so far
+            if score > best_score {
+                best_score = score;
+                best_move = Some(mov);
+                ctl.pv.update(0, mov);
+            }
+            if score > alpha {
+                alpha = score;
+            }
+            if score >= beta {
+                break;
+            }
+        }
+
+        (best_score, best_move)
+    }
+}
+
+impl Default for Searcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(searcher: &mut Searcher, pos: &Position, depth: u8) -> SearchResult {
+        searcher.search(pos, SearchLimits::depth(depth), &mut |_| {})
+    }
+
+    #[test]
+    fn test_tables_kept_between_searches() {
+        let pos = Position::default();
+        let mut searcher = Searcher::with_hash_mb(4);
+
+        let first = search(&mut searcher, &pos, 4);
+        assert!(searcher.transposition_table().size() > 0);
+
+        // The second search starts from the first one's table
+        let second = search(&mut searcher, &pos, 4);
+        assert!(second.best_move.is_some());
+        assert!(second.nodes_searched < first.nodes_searched);
+
+        searcher.new_game();
+        assert_eq!(searcher.transposition_table().size(), 0);
+    }
+
+    #[test]
+    fn test_set_hash_mb() {
+        let mut searcher = Searcher::new();
+        assert_eq!(searcher.hash_mb(), DEFAULT_HASH_MB);
+
+        search(&mut searcher, &Position::default(), 2);
+        searcher.set_hash_mb(1);
+        assert_eq!(searcher.hash_mb(), 1);
+        assert_eq!(searcher.transposition_table().size(), 0);
+    }
+
+    #[test]
+    fn test_aspiration_windows_find_same_move() {
+        // Winning the queen: the move is found with or without aspiration
+        let pos = Position::from_fen("4k3/8/8/8/3q4/8/3R4/4K3 w - -");
+        let mut with = Searcher::new();
+        let mut without = Searcher::new();
+        without.config.aspiration_windows = false;
+
+        let a = search(&mut with, &pos, 5);
+        let b = search(&mut without, &pos, 5);
+        assert_eq!(a.best_move, b.best_move);
+        assert_eq!(a.score, b.score);
+    }
+
+    #[test]
+    fn test_multi_pv() {
+        // Winning the queen stays the best line with two more alongside
+        let pos = Position::from_fen("4k3/8/8/8/3q4/8/3R4/4K3 w - -");
+        let single = search(&mut Searcher::new(), &pos, 4);
+        let mut reported = Vec::new();
+        let limits = SearchLimits { multi_pv: 3, ..SearchLimits::depth(4) };
+        let result = Searcher::new().search(&pos, limits, &mut |info| reported.push((info.depth, info.multipv)));
+
+        assert_eq!(result.lines.len(), 3);
+        assert_eq!(result.best_move, single.best_move);
+        assert_eq!(result.score, single.score);
+        assert_eq!(result.principal_variation.as_ref(), Some(&result.lines[0].pv));
+        assert!(result.lines.windows(2).all(|w| w[0].score >= w[1].score && w[0].pv[0] != w[1].pv[0]));
+        assert_eq!(&reported[reported.len() - 3..], &[(4, 1), (4, 2), (4, 3)]);
+
+        // Never more lines than legal moves
+        let pos = Position::from_fen("7k/8/8/8/8/8/8/K7 w - -");
+        let limits = SearchLimits { multi_pv: 10, ..SearchLimits::depth(2) };
+        let result = Searcher::new().search(&pos, limits, &mut |_| {});
+        assert_eq!(result.lines.len(), 3);
+    }
+
+    #[test]
+    fn test_repetition_in_game_history() {
+        // A queen down, Black heads for a position already seen twice
+        let pos = Position::from_fen("4k3/8/8/8/8/8/8/Q3K3 b - - 10 40");
+        let repeat = pos.parse_san("Kf8").unwrap();
+        let mut repeated = pos.clone();
+        repeated.mk_move(repeat);
+        let history = vec![1, repeated.zobrist, 2, repeated.zobrist];
+
+        let mut searcher = Searcher::with_hash_mb(4);
+        let limits = SearchLimits { history: history.clone(), ..SearchLimits::depth(3) };
+        let result = searcher.search(&pos, limits, &mut |_| {});
+        assert_eq!(result.best_move, Some(repeat));
+        assert_eq!(result.score, 0);
+
+        // Contempt makes the draw worth less, but still more than the queen
+        searcher.new_game();
+        searcher.config.contempt = 50;
+        let result = searcher.search(&pos, SearchLimits { history, ..SearchLimits::depth(3) }, &mut |_| {});
+        assert_eq!(result.best_move, Some(repeat));
+        assert_eq!(result.score, -50);
+
+        // Without the history it is just a lost position
+        let result = search(&mut Searcher::with_hash_mb(4), &pos, 3);
+        assert!(result.score < -500);
+    }
+
+    #[test]
+    fn test_perpetual_check() {
+        // Two queens down, White saves the game with Qe8+ Kh7 Qh5+ Kg8 Qe8+
+        let pos = Position::from_fen("7k/6p1/8/8/8/8/qq6/4Q1K1 w - - 0 1");
+        let result = search(&mut Searcher::with_hash_mb(4), &pos, 7);
+        assert_eq!(result.best_move, pos.parse_san("Qe8+").ok());
+        assert_eq!(result.score, 0);
+    }
+
+    #[test]
+    fn test_fifty_move_draw() {
+        let pos = Position::from_fen("4k3/8/8/8/8/8/8/Q3K3 b - - 99 80");
+        assert_eq!(search(&mut Searcher::with_hash_mb(4), &pos, 3).score, 0);
+
+        // White cannot win in time either
+        let pos = Position::from_fen("4k3/8/8/8/8/8/8/Q3K3 w - - 98 80");
+        assert_eq!(search(&mut Searcher::with_hash_mb(4), &pos, 3).score, 0);
+    }
+
+    #[test]
+    fn test_principal_variation() {
+        let pos = Position::default();
+        let mut reported = Vec::new();
+        let result = Searcher::with_hash_mb(4).search(&pos, SearchLimits::depth(5), &mut |info| {
+            reported.push(info.pv.clone())
+        });
+
+        // A full, legal line every iteration
+        for (depth, pv) in (1..).zip(&reported) {
+            assert_eq!(pv.len(), depth, "{:?}", pv);
+            let mut line = pos.clone();
+            for &mv in pv {
+                assert!(line.all_legal_moves().contains(&mv));
+                line.mk_move(mv);
+            }
+        }
+        assert_eq!(result.principal_variation.as_ref(), reported.last());
+        assert_eq!(result.principal_variation.unwrap()[0], result.best_move.unwrap());
+    }
+
+    /// Config with every selective search feature switched off
+    fn full_width() -> SearchConfig {
+        SearchConfig {
+            late_move_reductions: false,
+            reverse_futility_pruning: false,
+            futility_pruning: false,
+            razoring: false,
+            check_extensions: false,
+            internal_iterative_reductions: false,
+            ..SearchConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_selective_search() {
+        let pos = Position::default();
+        let mut full = Searcher::with_hash_mb(4);
+        full.config = full_width();
+        let selective = search(&mut Searcher::with_hash_mb(4), &pos, 6);
+        assert!(selective.nodes_searched < search(&mut full, &pos, 6).nodes_searched);
+
+        // Each feature alone still wins the queen
+        let pos = Position::from_fen("4k3/8/8/8/3q4/8/3R4/4K3 w - -");
+        let switches: [fn(&mut SearchConfig) -> &mut bool; 6] = [
+            |config| &mut config.late_move_reductions,
+            |config| &mut config.reverse_futility_pruning,
+            |config| &mut config.futility_pruning,
+            |config| &mut config.razoring,
+            |config| &mut config.check_extensions,
+            |config| &mut config.internal_iterative_reductions,
+        ];
+        for switch in switches {
+            let mut searcher = Searcher::with_hash_mb(4);
+            searcher.config = full_width();
+            *switch(&mut searcher.config) = true;
+            assert_eq!(search(&mut searcher, &pos, 5).best_move, pos.parse_san("Rxd4").ok());
+        }
+    }
+
+    #[test]
+    fn test_helper_threads() {
+        // Back-rank mate in one, found with helpers as without
+        let pos = Position::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - -");
+        let mut searcher = Searcher::new();
+        let mut reported = Vec::new();
+        let limits = SearchLimits { threads: 4, ..SearchLimits::depth(4) };
+        let result = searcher.search(&pos, limits, &mut |info| reported.push(info.nodes));
+
+        assert_eq!(result.best_move, pos.parse_san("Ra8").ok());
+        assert!(is_mate_score(result.score));
+        assert!(!reported.is_empty());
+        assert!(result.nodes_searched >= *reported.last().unwrap());
+
+        // The searcher can be reused after a threaded search
+        let result = search(&mut searcher, &Position::default(), 3);
+        assert!(result.best_move.is_some());
+    }
+
+    #[test]
+    fn test_helper_threads_share_node_limit() {
+        let mut searcher = Searcher::new();
+        let limits = SearchLimits { threads: 4, node_limit: Some(20_000), ..SearchLimits::default() };
+        let result = searcher.search(&Position::default(), limits, &mut |_| {});
+
+        assert!(result.best_move.is_some());
+        // Each thread may overshoot by up to one check interval
+        assert!(result.nodes_searched < 20_000 + 4 * 1024, "{} nodes", result.nodes_searched);
+        assert!(result.completed_depth <= result.depth);
+    }
+}
@@ -0,0 +1,161 @@
+// Time Management
+//
+// With a clock, a search gets two budgets. The soft limit is what a move
+// should normally take: no new iteration starts past it, and it stretches
+// when the best move keeps changing between iterations. The hard limit is
+// where a running iteration is abandoned; it is a few soft limits but never
+// more than the clock can spare. An iteration that would not finish before
+// the hard limit, judging by how long the last one took, is not started.
+//
+// Search time is read from a Clock, which for tests can be driven by the node
+// count instead of the wall clock so timed searches are reproducible.
+
+/// Milliseconds kept back from every clock-based budget for I/O and process
+/// scheduling
+pub const MOVE_OVERHEAD_MS: u64 = 30;
+
+/// Moves the remaining clock time is spread over when the time control does
+/// not say
+const DEFAULT_MOVES_TO_GO: u64 = 30;
+
+/// Hard limit in soft limits
+const HARD_LIMIT_FACTOR: u64 = 4;
+
+/// Assumed ratio between the time of an iteration and the one before it
+const ITERATION_GROWTH: u64 = 2;
+
+/// Most best-move changes that extend the soft limit
+const MAX_INSTABILITY: u32 = 4;
+
+/// Where a search reads its elapsed time from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Clock {
+    /// Wall-clock time
+    #[default]
+    Wall,
+    /// One millisecond per `nodes_per_ms` nodes searched, so a timed search
+    /// does the same work on every run (tests and benchmarks)
+    Nodes { nodes_per_ms: u64 },
+}
+
+/// Soft and hard time limits for one move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeManager {
+    soft_ms: u64,
+    hard_ms: u64,
+}
+
+impl TimeManager {
+    /// Limits for a move given the clock
+    ///
+    /// The soft limit spreads the remaining time over the moves left until
+    /// the next time control and adds most of the increment. The hard limit
+    /// is [`HARD_LIMIT_FACTOR`] soft limits, capped at half of what is left
+    /// on the clock (all of it on the last move before the time control),
+    /// less [`MOVE_OVERHEAD_MS`].
+    pub fn new(time_left_ms: u64, increment_ms: u64, moves_to_go: Option<u64>) -> Self {
+        let usable = time_left_ms.saturating_sub(MOVE_OVERHEAD_MS);
+        let last_move = moves_to_go == Some(1);
+        let moves_to_go = moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+
+        let soft_ms = (time_left_ms / moves_to_go + increment_ms * 3 / 4).min(usable).max(1);
+        let reserve = if last_move { usable } else { usable / 2 };
+        let hard_ms = (soft_ms * HARD_LIMIT_FACTOR).min(reserve).max(soft_ms);
+        Self { soft_ms, hard_ms }
+    }
+
+    pub fn soft_ms(&self) -> u64 {
+        self.soft_ms
+    }
+
+    pub fn hard_ms(&self) -> u64 {
+        self.hard_ms
+    }
+
+    /// Soft limit stretched by half for each recent best-move change, up to
+    /// the hard limit
+    pub fn soft_limit_ms(&self, best_move_changes: u32) -> u64 {
+        let changes = best_move_changes.min(MAX_INSTABILITY) as u64;
+        (self.soft_ms * (2 + changes) / 2).min(self.hard_ms)
+    }
+
+    /// Whether to start another iteration `elapsed_ms` into the search, the
+    /// last one having taken `last_iteration_ms`
+    pub fn start_iteration(&self, elapsed_ms: u64, last_iteration_ms: u64, best_move_changes: u32) -> bool {
+        elapsed_ms < self.soft_limit_ms(best_move_changes)
+            && elapsed_ms + last_iteration_ms * ITERATION_GROWTH <= self.hard_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::ai::limits::SearchLimits;
+    use crate::agent::ai::searcher::Searcher;
+    use crate::game_repr::Position;
+
+    #[test]
+    fn test_limits() {
+        let tm = TimeManager::new(60_000, 1000, Some(20));
+        assert_eq!(tm.soft_ms(), 3000 + 750);
+        assert_eq!(tm.hard_ms(), 4 * 3750);
+        assert_eq!(TimeManager::new(30_000, 0, None).soft_ms(), 1000);
+
+        // Never more than is left on the clock
+        let tm = TimeManager::new(100, 5000, None);
+        assert_eq!(tm.soft_ms(), 100 - MOVE_OVERHEAD_MS);
+        assert_eq!(tm.hard_ms(), 100 - MOVE_OVERHEAD_MS);
+        assert_eq!(TimeManager::new(1000, 200, None).hard_ms(), (1000 - MOVE_OVERHEAD_MS) / 2);
+        assert_eq!(TimeManager::new(1000, 0, Some(1)).hard_ms(), 1000 - MOVE_OVERHEAD_MS);
+        assert_eq!(TimeManager::new(0, 0, Some(0)), TimeManager { soft_ms: 1, hard_ms: 1 });
+    }
+
+    #[test]
+    fn test_start_iteration() {
+        let tm = TimeManager::new(30_000, 0, None);
+        assert!(tm.start_iteration(500, 100, 0));
+        // Past the soft limit
+        assert!(!tm.start_iteration(1000, 100, 0));
+        // ...unless the best move keeps changing
+        assert!(tm.start_iteration(1000, 100, 1));
+        assert_eq!(tm.soft_limit_ms(100), 3 * tm.soft_ms());
+        // The next iteration would run past the hard limit
+        assert!(!tm.start_iteration(500, 2000, 4));
+    }
+
+    /// Plays a game against itself on a node-driven clock, checking that
+    /// no move takes more time than is left
+    fn play_on_clock(time_ms: u64, increment_ms: u64, moves_per_session: Option<u64>) {
+        let mut pos = Position::default();
+        let mut searchers = [Searcher::with_hash_mb(4), Searcher::with_hash_mb(4)];
+        let mut clocks = [time_ms, time_ms];
+
+        for ply in 0..80 {
+            if !pos.has_legal_moves(pos.side_to_move) {
+                break;
+            }
+            let side = ply % 2;
+            let moves_to_go = moves_per_session.map(|moves| moves - (ply as u64 / 2) % moves);
+            let limits = SearchLimits {
+                time_manager: Some(TimeManager::new(clocks[side], increment_ms, moves_to_go)),
+                clock: Clock::Nodes { nodes_per_ms: 100 },
+                ..SearchLimits::default()
+            };
+
+            let result = searchers[side].search(&pos, limits, &mut |_| {});
+            assert!(result.time_ms < clocks[side], "ply {}: {} ms used with {} ms left", ply, result.time_ms, clocks[side]);
+            clocks[side] = clocks[side] - result.time_ms + increment_ms;
+            if moves_to_go == Some(1) {
+                clocks[side] += time_ms;
+            }
+            pos.mk_move(result.best_move.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_never_loses_on_time() {
+        play_on_clock(500, 0, None);
+        play_on_clock(200, 20, None);
+        play_on_clock(300, 0, Some(10));
+    }
+}
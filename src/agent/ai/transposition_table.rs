@@ -1,88 +1,22 @@
-use crate::game_repr::{Move, Position, Type, Color};
-use std::collections::HashMap;
-use std::sync::LazyLock;
-
-/// Zobrist hashing constants for chess positions
-///
-/// Zobrist hashing uses random 64-bit numbers to represent each possible
-/// piece-square combination, along with castling rights, en passant, and
-/// side to move. This allows for efficient incremental hash updates.
-pub struct ZobristKeys {
-    /// [piece_type][color][square] - 6 piece types * 2 colors * 64 squares
-    pub pieces: [[[u64; 64]; 2]; 6],
-    /// [castling_index] - 6 castling conditions
-    pub castling: [u64; 6],
-    /// [file] - en passant file (0-7)
-    pub en_passant: [u64; 8],
-    /// Side to move (toggle this when it's black's turn)
-    pub side_to_move: u64,
-}
-
-impl ZobristKeys {
-    /// Generate Zobrist keys using a seeded random number generator
-    /// This ensures the keys are random but reproducible
-    fn generate() -> Self {
-        use rand::{Rng, SeedableRng};
-        use rand::rngs::StdRng;
-
-        // Use a fixed seed for reproducibility
-        let mut rng = StdRng::seed_from_u64(0x517cc1b727220a95);
-
-        let mut pieces = [[[0u64; 64]; 2]; 6];
-
-        // Generate random numbers for each piece-square combination
-        for piece_type in &mut pieces {
-            for color in piece_type {
-                for square in color {
-                    *square = rng.gen();
-                }
-            }
-        }
-
-        let mut castling = [0u64; 6];
-        for castle in &mut castling {
-            *castle = rng.gen();
-        }
-
-        let mut en_passant = [0u64; 8];
-        for ep in &mut en_passant {
-            *ep = rng.gen();
-        }
-
-        Self {
-            pieces,
-            castling,
-            en_passant,
-            side_to_move: rng.gen(),
-        }
-    }
-
-    /// Get piece type index for Zobrist hashing
-    #[inline]
-    fn piece_index(piece_type: Type) -> usize {
-        match piece_type {
-            Type::Pawn => 0,
-            Type::Knight => 1,
-            Type::Bishop => 2,
-            Type::Rook => 3,
-            Type::Queen => 4,
-            Type::King => 5,
-            Type::None => panic!("Cannot hash Type::None"),
-        }
-    }
-
-    /// Get color index for Zobrist hashing
-    #[inline]
-    fn color_index(color: Color) -> usize {
-        match color {
-            Color::White => 0,
-            Color::Black => 1,
-        }
-    }
-}
-
-/// Global Zobrist keys - initialized once using LazyLock
-static ZOBRIST: LazyLock<ZobristKeys> = LazyLock::new(ZobristKeys::generate);
+// Transposition Table
+//
+// A fixed power-of-two array of two-slot buckets, sized in entries or MB and
+// shared without locking between the search threads. Each slot is two 64-bit
+// words: the packed entry, and the full Zobrist key XORed with it.
+//
+// Slots keep the full key rather than a 16- or 32-bit verification key.
+// The packed entry already fills its 64-bit word (a 32-bit score alongside
+// the move, depth, generation and node type), so a shorter key would still
+// need a second word and the slot would stay 16 bytes. With the whole key the
+// XOR check also catches an entry torn by two threads writing one slot at
+// once, and a probe never matches a different position that happens to share
+// the index and the key bits, which a truncated key would allow. The cost is
+// fewer entries per MB than an 8-byte slot would give.
+
+use crate::game_repr::Move;
+use super::negamax::is_mate_score;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::Arc;
 
 /// Node type for transposition table entries
 ///
@@ -115,16 +49,132 @@ pub struct TranspositionTableEntry {
     pub node_type: NodeType,
 }
 
+/// Entry as it is packed into a slot
+#[derive(Debug, Clone, Copy)]
+struct SlotData {
+    score: i32,
+    best_move: Option<Move>,
+    depth: u8,
+    /// `new_search` counter value when the entry was stored
+    generation: u8,
+    node_type: NodeType,
+}
+
+/// Bits of the generation kept in a slot
+const GENERATION_MASK: u8 = 0x3F;
+
+impl SlotData {
+    // | 2 bits | 6 bits     | 8 bits | 16 bits | 32 bits |
+    // | Node   | Generation | Depth  | Move    | Score   |
+    //
+    // A node type of 0 marks an empty slot.
+    fn pack(self) -> u64 {
+        let node_type = match self.node_type {
+            NodeType::Exact => 1,
+            NodeType::LowerBound => 2,
+            NodeType::UpperBound => 3,
+        };
+        let best_move = self
+            .best_move
+            .map_or(0, |mv| (mv._from() as u64) << 10 | (mv._to() as u64) << 4 | mv.move_type() as u64);
+
+        self.score as u32 as u64
+            | best_move << 32
+            | (self.depth as u64) << 48
+            | ((self.generation & GENERATION_MASK) as u64) << 56
+            | node_type << 62
+    }
+
+    fn unpack(data: u64) -> Option<Self> {
+        let node_type = match data >> 62 {
+            1 => NodeType::Exact,
+            2 => NodeType::LowerBound,
+            3 => NodeType::UpperBound,
+            _ => return None,
+        };
+        let best_move = match (data >> 32) as u16 {
+            0 => None,
+            mv => Some(Move::new((mv >> 10) as u8, ((mv >> 4) & 0x3F) as u8, (mv & 0xF).into())),
+        };
+
+        Some(Self {
+            score: data as u32 as i32,
+            best_move,
+            depth: (data >> 48) as u8,
+            generation: (data >> 56) as u8 & GENERATION_MASK,
+            node_type,
+        })
+    }
+}
+
+/// Slot of a bucket, holding one position's entry
+///
+/// The key is stored XORed with the packed entry, so an entry torn by two
+/// threads writing the slot at once fails verification instead of being read
+/// back for the wrong position.
+#[derive(Debug, Default)]
+struct Slot {
+    key: AtomicU64,
+    data: AtomicU64,
+}
+
+impl Slot {
+    /// The stored hash and entry, if the slot is used
+    fn load(&self) -> Option<(u64, SlotData)> {
+        let data = self.data.load(Ordering::Relaxed);
+        let key = self.key.load(Ordering::Relaxed);
+        SlotData::unpack(data).map(|slot| (key ^ data, slot))
+    }
+
+    fn save(&self, hash: u64, slot: SlotData) {
+        let data = slot.pack();
+        self.key.store(hash ^ data, Ordering::Relaxed);
+        self.data.store(data, Ordering::Relaxed);
+    }
+
+    fn clear(&self) {
+        self.key.store(0, Ordering::Relaxed);
+        self.data.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Two slots sharing one index: entries go to `depth_preferred` when they are
+/// at least as deep as what it holds (or it is stale), otherwise to
+/// `always_replace`, which takes whatever comes
+#[derive(Debug, Default)]
+struct Bucket {
+    depth_preferred: Slot,
+    always_replace: Slot,
+}
+
+/// Buckets sampled by [`TranspositionTable::hashfull`]
+const HASHFULL_SAMPLE: usize = 1000;
+
+/// Storage shared by every handle to one table
+struct Table {
+    buckets: Box<[Bucket]>,
+    /// Incremented by `new_search`, so entries from earlier searches can be
+    /// replaced first
+    generation: AtomicU8,
+}
+
 /// Transposition Table for storing previously evaluated positions
 ///
 /// This is a critical optimization for chess engines. It stores positions
 /// that have been evaluated before so we don't re-evaluate them. Uses
 /// Zobrist hashing for position identification.
+///
+/// The table is a fixed power-of-two array of buckets indexed by the low bits
+/// of the hash, so it never grows and never stops accepting entries: new
+/// entries replace stale or shallower ones. Mate scores are stored relative
+/// to the position rather than the root, so they stay correct when the
+/// position is reached at a different ply.
+///
+/// Search threads each hold a handle from [`share`](Self::share); the
+/// entries are shared without locking, the statistics are per handle.
+/// Cloning copies the entries into a new table.
 pub struct TranspositionTable {
-    /// HashMap storing entries by hash
-    table: HashMap<u64, TranspositionTableEntry>,
-    /// Maximum number of entries allowed
-    max_size: usize,
+    table: Arc<Table>,
     /// Statistics: number of successful probes
     pub hits: u64,
     /// Statistics: number of failed probes
@@ -132,259 +182,176 @@ pub struct TranspositionTable {
 }
 
 impl TranspositionTable {
-    /// Create a transposition table with default size (1 million entries)
+    /// Create a transposition table with default size (about 1 million entries)
     pub fn new() -> Self {
         Self::with_capacity(1_000_000)
     }
 
-    /// Create a new transposition table with specified maximum size
+    /// Create a new transposition table holding at most `max_size` entries
+    ///
+    /// The bucket count is rounded down to a power of two.
     ///
     /// # Arguments
-    /// * `max_size` - Maximum number of entries (typical: 1_000_000 for ~100MB)
+    /// * `max_size` - Maximum number of entries (typical: 1_000_000 for ~16MB)
     pub fn with_capacity(max_size: usize) -> Self {
+        Self::with_buckets(max_size / 2)
+    }
+
+    /// Create a transposition table using at most `mb` megabytes
+    pub fn with_size_mb(mb: usize) -> Self {
+        Self::with_buckets(mb * 1024 * 1024 / std::mem::size_of::<Bucket>())
+    }
+
+    fn with_buckets(count: usize) -> Self {
+        let count = match count {
+            0 => 1,
+            n if n.is_power_of_two() => n,
+            n => n.next_power_of_two() / 2,
+        };
         Self {
-            table: HashMap::with_capacity(max_size.min(100_000)),
-            max_size,
+            table: Arc::new(Table {
+                buckets: (0..count).map(|_| Bucket::default()).collect(),
+                generation: AtomicU8::new(0),
+            }),
             hits: 0,
             misses: 0,
         }
     }
 
-    /// Compute Zobrist hash for a position
-    ///
-    /// This is the main hashing function that combines all position features
-    /// into a single 64-bit hash value.
-    pub fn compute_hash(pos: &Position) -> u64 {
-        let mut hash = 0u64;
-
-        // Hash all pieces on the board
-        for square in 0..64 {
-            let piece = pos.position[square];
-            if piece.piece_type != Type::None {
-                let piece_idx = ZobristKeys::piece_index(piece.piece_type);
-                let color_idx = ZobristKeys::color_index(piece.color);
-                hash ^= ZOBRIST.pieces[piece_idx][color_idx][square];
-            }
-        }
-
-        // Hash castling rights
-        for i in 0..6 {
-            if pos.castling_cond[i] {
-                hash ^= ZOBRIST.castling[i];
-            }
-        }
-
-        // Hash en passant square if available
-        if let Some(last_move) = pos.prev_moves.last() {
-            let from = last_move._from();
-            let to = last_move._to();
-            let moved_piece = pos.position[to];
-
-            // Check if it was a pawn double move
-            if moved_piece.piece_type == Type::Pawn {
-                let distance = to.abs_diff(from);
-                if distance == 16 {
-                    // En passant square is between from and to
-                    let ep_square = (from + to) / 2;
-                    let file = ep_square % 8;
-                    hash ^= ZOBRIST.en_passant[file];
-                }
-            }
-        }
-
-        // Hash side to move (black to move toggles this bit)
-        if pos.prev_moves.len() % 2 == 1 {
-            hash ^= ZOBRIST.side_to_move;
-        }
-
-        hash
+    /// A handle to the same entries, with its own statistics, for another
+    /// search thread
+    pub fn share(&self) -> Self {
+        Self { table: Arc::clone(&self.table), hits: 0, misses: 0 }
     }
 
-    /// Update hash incrementally after a move
-    ///
-    /// This is more efficient than recomputing the entire hash from scratch.
-    /// Returns the new hash value.
-    ///
-    /// # Arguments
-    /// * `old_hash` - Previous position hash
-    /// * `mv` - Move being made
-    /// * `pos` - Position BEFORE the move is made
-    pub fn update_hash(old_hash: u64, mv: Move, pos: &Position) -> u64 {
-        let mut hash = old_hash;
-
-        let from = mv._from();
-        let to = mv._to();
-        let moving_piece = pos.position[from];
-        let captured_piece = pos.position[to];
-
-        // Remove piece from source square
-        let piece_idx = ZobristKeys::piece_index(moving_piece.piece_type);
-        let color_idx = ZobristKeys::color_index(moving_piece.color);
-        hash ^= ZOBRIST.pieces[piece_idx][color_idx][from];
-
-        // Remove captured piece if any (for normal moves)
-        if captured_piece.piece_type != Type::None {
-            let cap_piece_idx = ZobristKeys::piece_index(captured_piece.piece_type);
-            let cap_color_idx = ZobristKeys::color_index(captured_piece.color);
-            hash ^= ZOBRIST.pieces[cap_piece_idx][cap_color_idx][to];
-        }
-
-        // Handle special moves
-        match mv.move_type() {
-            crate::game_repr::MoveType::Normal => {
-                // Add piece to destination square
-                hash ^= ZOBRIST.pieces[piece_idx][color_idx][to];
-            },
-            crate::game_repr::MoveType::EnPassant => {
-                // Add pawn to destination
-                hash ^= ZOBRIST.pieces[piece_idx][color_idx][to];
-
-                // Remove captured pawn (not at destination square)
-                let captured_sq = match moving_piece.color {
-                    Color::White => to - 8,
-                    Color::Black => to + 8,
-                };
-                let cap_color_idx = ZobristKeys::color_index(moving_piece.color.opposite());
-                hash ^= ZOBRIST.pieces[0][cap_color_idx][captured_sq]; // 0 = Pawn
-            },
-            crate::game_repr::MoveType::Castling => {
-                // Add king to destination
-                hash ^= ZOBRIST.pieces[piece_idx][color_idx][to];
-
-                // Move the rook
-                let is_kingside = to > from;
-                let (rook_from, rook_to) = match (moving_piece.color, is_kingside) {
-                    (Color::White, true) => (7, 5),
-                    (Color::White, false) => (0, 3),
-                    (Color::Black, true) => (63, 61),
-                    (Color::Black, false) => (56, 59),
-                };
-
-                let rook_idx = ZobristKeys::piece_index(Type::Rook);
-                hash ^= ZOBRIST.pieces[rook_idx][color_idx][rook_from];
-                hash ^= ZOBRIST.pieces[rook_idx][color_idx][rook_to];
-            },
-            crate::game_repr::MoveType::PromotionQueen |
-            crate::game_repr::MoveType::PromotionRook |
-            crate::game_repr::MoveType::PromotionBishop |
-            crate::game_repr::MoveType::PromotionKnight => {
-                // Determine promoted piece type
-                let promoted_type = match mv.move_type() {
-                    crate::game_repr::MoveType::PromotionQueen => Type::Queen,
-                    crate::game_repr::MoveType::PromotionRook => Type::Rook,
-                    crate::game_repr::MoveType::PromotionBishop => Type::Bishop,
-                    crate::game_repr::MoveType::PromotionKnight => Type::Knight,
-                    _ => unreachable!(),
-                };
-
-                // Add promoted piece to destination
-                let promoted_idx = ZobristKeys::piece_index(promoted_type);
-                hash ^= ZOBRIST.pieces[promoted_idx][color_idx][to];
-            },
-        }
-
-        // Update castling rights (simplified - just XOR old and new)
-        // This could be optimized further with incremental tracking
-        for i in 0..6 {
-            if pos.castling_cond[i] {
-                hash ^= ZOBRIST.castling[i];
-            }
-        }
-
-        // Update new castling rights after the move would be applied
-        // (This is approximate - for full accuracy, apply move and recompute)
-
-        // Update en passant
-        // Remove old en passant if it existed
-        if let Some(last_move) = pos.prev_moves.last() {
-            let last_from = last_move._from();
-            let last_to = last_move._to();
-            let last_piece = pos.position[last_to];
-
-            if last_piece.piece_type == Type::Pawn {
-                let distance = last_to.abs_diff(last_from);
-                if distance == 16 {
-                    let ep_square = (last_from + last_to) / 2;
-                    let file = ep_square % 8;
-                    hash ^= ZOBRIST.en_passant[file];
-                }
-            }
-        }
-
-        // Add new en passant if this is a pawn double move
-        if moving_piece.piece_type == Type::Pawn {
-            let distance = to.abs_diff(from);
-            if distance == 16 {
-                let ep_square = (from + to) / 2;
-                let file = ep_square % 8;
-                hash ^= ZOBRIST.en_passant[file];
-            }
-        }
-
-        // Toggle side to move
-        hash ^= ZOBRIST.side_to_move;
+    fn bucket(&self, hash: u64) -> &Bucket {
+        let buckets = &self.table.buckets;
+        &buckets[hash as usize & (buckets.len() - 1)]
+    }
 
-        hash
+    fn generation(&self) -> u8 {
+        self.table.generation.load(Ordering::Relaxed) & GENERATION_MASK
     }
 
     /// Probe the transposition table for a position
     ///
+    /// `ply` is the distance of the position from the search root; mate
+    /// scores are converted back from position-relative to root-relative.
     /// Returns the entry if found, None otherwise.
     /// Updates hit/miss statistics.
-    pub fn probe(&mut self, hash: u64) -> Option<&TranspositionTableEntry> {
-        if let Some(entry) = self.table.get(&hash) {
-            // Verify hash matches (collision detection)
-            if entry.hash == hash {
+    pub fn probe(&mut self, hash: u64, ply: i32) -> Option<TranspositionTableEntry> {
+        let bucket = self.bucket(hash);
+        let found = [&bucket.depth_preferred, &bucket.always_replace]
+            .into_iter()
+            .filter_map(Slot::load)
+            .find(|&(key, _)| key == hash);
+
+        match found {
+            Some((_, slot)) => {
                 self.hits += 1;
-                return Some(entry);
+                Some(TranspositionTableEntry {
+                    hash,
+                    depth: slot.depth,
+                    score: score_from_tt(slot.score, ply),
+                    best_move: slot.best_move,
+                    node_type: slot.node_type,
+                })
+            }
+            None => {
+                self.misses += 1;
+                None
             }
         }
-        self.misses += 1;
-        None
     }
 
     /// Store an entry in the transposition table
     ///
-    /// Uses replacement strategy: always replace if:
-    /// 1. Slot is empty, OR
-    /// 2. New entry has greater depth, OR
-    /// 3. New entry is exact and old is bound
+    /// `ply` is the distance of the position from the search root, used to
+    /// store mate scores relative to the position.
     ///
-    /// If table is full, evicts based on depth (keeps deeper searches)
-    pub fn store(&mut self, entry: TranspositionTableEntry) {
-        // Check if we need to evict
-        if self.table.len() >= self.max_size {
-            if let Some(existing) = self.table.get(&entry.hash) {
-                // Replacement strategy: prefer deeper searches and exact scores
-                let should_replace = entry.depth >= existing.depth
-                    || (entry.node_type == NodeType::Exact
-                        && existing.node_type != NodeType::Exact);
-
-                if !should_replace {
-                    return; // Don't replace
+    /// Replacement strategy: the depth-preferred slot takes the entry if it
+    /// is empty, holds the same position, is from an earlier search, or is
+    /// not deeper than the new entry; whatever it held moves to the
+    /// always-replace slot. Otherwise the entry goes to the always-replace
+    /// slot. A stored move is kept when the same position is stored again
+    /// without one.
+    pub fn store(&mut self, entry: TranspositionTableEntry, ply: i32) {
+        let generation = self.generation();
+        let bucket = self.bucket(entry.hash);
+        let slot = SlotData {
+            score: score_to_tt(entry.score, ply),
+            best_move: entry.best_move,
+            depth: entry.depth,
+            generation,
+            node_type: entry.node_type,
+        };
+
+        // Older entry for the same position, kept for its move
+        let previous_move = |target: &Slot| {
+            target.load().filter(|&(key, _)| key == entry.hash).and_then(|(_, old)| old.best_move)
+        };
+
+        match bucket.depth_preferred.load() {
+            Some((key, old)) if key != entry.hash && old.generation == generation && entry.depth < old.depth => {
+                let best_move = slot.best_move.or_else(|| previous_move(&bucket.always_replace));
+                bucket.always_replace.save(entry.hash, SlotData { best_move, ..slot });
+            }
+            deep => {
+                let best_move = slot.best_move.or_else(|| previous_move(&bucket.depth_preferred));
+                if let Some((key, old)) = deep.filter(|&(key, _)| key != entry.hash) {
+                    bucket.always_replace.save(key, old);
                 }
-            } else {
-                // Table is full but this hash isn't present
-                // We could implement FIFO or depth-based eviction here
-                // For simplicity, we'll just skip insertion
-                // A production engine might evict the shallowest entry
-                return;
+                bucket.depth_preferred.save(entry.hash, SlotData { best_move, ..slot });
             }
         }
+    }
 
-        self.table.insert(entry.hash, entry);
+    /// Ages the table before a new search
+    ///
+    /// Entries are kept across searches, since most of them are still useful
+    /// after a move, but entries from earlier searches lose their claim on
+    /// the depth-preferred slots.
+    pub fn new_search(&mut self) {
+        self.table.generation.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Clear the transposition table
     pub fn clear(&mut self) {
-        self.table.clear();
+        for slot in self.slots() {
+            slot.clear();
+        }
+        self.table.generation.store(0, Ordering::Relaxed);
         self.hits = 0;
         self.misses = 0;
     }
 
-    /// Get current table size
+    fn slots(&self) -> impl Iterator<Item = &Slot> {
+        self.table.buckets.iter().flat_map(|bucket| [&bucket.depth_preferred, &bucket.always_replace])
+    }
+
+    /// Get the number of stored entries
+    ///
+    /// Scans the whole table.
     pub fn size(&self) -> usize {
-        self.table.len()
+        self.slots().filter(|slot| slot.load().is_some()).count()
+    }
+
+    /// Get the maximum number of entries
+    pub fn capacity(&self) -> usize {
+        self.table.buckets.len() * 2
+    }
+
+    /// Permille of entries used by the current search, estimated from the
+    /// first buckets (UCI `hashfull`)
+    pub fn hashfull(&self) -> u32 {
+        let generation = self.generation();
+        let sample = self.capacity().min(HASHFULL_SAMPLE * 2);
+        let used = self
+            .slots()
+            .take(sample)
+            .filter(|slot| slot.load().is_some_and(|(_, data)| data.generation == generation))
+            .count();
+        (used * 1000 / sample) as u32
     }
 
     /// Get hit rate (0.0 to 1.0)
@@ -397,13 +364,21 @@ impl TranspositionTable {
         }
     }
 
-    /// Get memory usage estimate in bytes
+    /// Get memory usage in bytes
     pub fn memory_usage(&self) -> usize {
-        // Each entry: u64 (hash) + u8 (depth) + i32 (score) + Option<Move> + NodeType
-        // Move is u16, Option adds 2 bytes, NodeType is 1 byte
-        // Total per entry: ~24 bytes + HashMap overhead
-        // Rough estimate: 40 bytes per entry
-        self.table.len() * 40
+        self.table.buckets.len() * std::mem::size_of::<Bucket>()
+    }
+}
+
+impl Clone for TranspositionTable {
+    fn clone(&self) -> Self {
+        let copy = Self::with_buckets(self.table.buckets.len());
+        for (from, to) in self.slots().zip(copy.slots()) {
+            to.key.store(from.key.load(Ordering::Relaxed), Ordering::Relaxed);
+            to.data.store(from.data.load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        copy.table.generation.store(self.table.generation.load(Ordering::Relaxed), Ordering::Relaxed);
+        Self { hits: self.hits, misses: self.misses, ..copy }
     }
 }
 
@@ -413,49 +388,34 @@ impl Default for TranspositionTable {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::game_repr::MoveType;
-
-    #[test]
-    fn test_zobrist_hash_starting_position() {
-        let pos = Position::default();
-        let hash1 = TranspositionTable::compute_hash(&pos);
-        let hash2 = TranspositionTable::compute_hash(&pos);
-
-        // Same position should give same hash
-        assert_eq!(hash1, hash2);
-
-        // Hash should be non-zero
-        assert_ne!(hash1, 0);
+/// Converts a root-relative mate score to one relative to the position `ply`
+/// plies from the root
+fn score_to_tt(score: i32, ply: i32) -> i32 {
+    if !is_mate_score(score) {
+        score
+    } else if score > 0 {
+        score + ply
+    } else {
+        score - ply
     }
+}
 
-    #[test]
-    fn test_zobrist_hash_different_positions() {
-        let pos1 = Position::default();
-        let pos2 = Position::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
-
-        let hash1 = TranspositionTable::compute_hash(&pos1);
-        let hash2 = TranspositionTable::compute_hash(&pos2);
-
-        // Different positions should give different hashes
-        assert_ne!(hash1, hash2);
+/// Inverse of [`score_to_tt`]
+fn score_from_tt(score: i32, ply: i32) -> i32 {
+    if !is_mate_score(score) {
+        score
+    } else if score > 0 {
+        score - ply
+    } else {
+        score + ply
     }
+}
 
-    #[test]
-    fn test_zobrist_hash_side_to_move() {
-        // Same position, different side to move
-        let pos_white = Position::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
-        let pos_black = Position::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1");
-
-        let hash_white = TranspositionTable::compute_hash(&pos_white);
-        let hash_black = TranspositionTable::compute_hash(&pos_black);
-
-        // Should differ only by side-to-move bit
-        assert_ne!(hash_white, hash_black);
-        assert_eq!(hash_white ^ hash_black, ZOBRIST.side_to_move);
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_repr::MoveType;
+    use crate::agent::ai::negamax::MATE_SCORE;
 
     #[test]
     fn test_transposition_table_store_and_probe() {
@@ -470,9 +430,9 @@ mod tests {
             node_type: NodeType::Exact,
         };
 
-        table.store(entry);
+        table.store(entry, 0);
 
-        let probed = table.probe(hash);
+        let probed = table.probe(hash, 0);
         assert!(probed.is_some());
 
         let retrieved = probed.unwrap();
@@ -495,7 +455,7 @@ mod tests {
             best_move: None,
             node_type: NodeType::LowerBound,
         };
-        table.store(entry1);
+        table.store(entry1, 0);
 
         // Store deeper search - should replace
         let entry2 = TranspositionTableEntry {
@@ -505,9 +465,9 @@ mod tests {
             best_move: Some(Move::new(12, 28, MoveType::Normal)),
             node_type: NodeType::Exact,
         };
-        table.store(entry2);
+        table.store(entry2, 0);
 
-        let probed = table.probe(hash).unwrap();
+        let probed = table.probe(hash, 0).unwrap();
         assert_eq!(probed.depth, 5);
         assert_eq!(probed.score, 100);
     }
@@ -522,7 +482,7 @@ mod tests {
             score: 100,
             best_move: None,
             node_type: NodeType::Exact,
-        });
+        }, 0);
 
         assert_eq!(table.size(), 1);
 
@@ -533,6 +493,121 @@ mod tests {
         assert_eq!(table.misses, 0);
     }
 
+    fn entry(hash: u64, depth: u8) -> TranspositionTableEntry {
+        TranspositionTableEntry { hash, depth, score: 0, best_move: None, node_type: NodeType::Exact }
+    }
+
+    #[test]
+    fn test_size_in_mb() {
+        let table = TranspositionTable::with_size_mb(1);
+        assert_eq!(table.memory_usage(), 1024 * 1024);
+        assert!(table.capacity().is_power_of_two());
+
+        // Rounded down to a power of two
+        assert_eq!(TranspositionTable::with_size_mb(3).memory_usage(), 2 * 1024 * 1024);
+        assert_eq!(TranspositionTable::with_capacity(100).capacity(), 64);
+    }
+
+    #[test]
+    fn test_always_replace_slot() {
+        let mut table = TranspositionTable::with_capacity(2);
+        let (deep, shallow, newer) = (1 << 32, 2 << 32, 3 << 32);
+
+        // All three share the only bucket
+        table.store(entry(deep, 8), 0);
+        table.store(entry(shallow, 2), 0);
+        assert_eq!(table.probe(deep, 0).unwrap().depth, 8);
+        assert_eq!(table.probe(shallow, 0).unwrap().depth, 2);
+
+        // A shallow entry only displaces the always-replace slot
+        table.store(entry(newer, 1), 0);
+        assert!(table.probe(deep, 0).is_some());
+        assert!(table.probe(shallow, 0).is_none());
+
+        // Entries from an earlier search lose the depth-preferred slot
+        table.new_search();
+        table.store(entry(shallow, 1), 0);
+        assert_eq!(table.probe(shallow, 0).unwrap().depth, 1);
+        assert!(table.probe(deep, 0).is_some());
+        assert!(table.probe(newer, 0).is_none());
+    }
+
+    #[test]
+    fn test_best_move_kept_without_new_move() {
+        let mut table = TranspositionTable::with_capacity(100);
+        let mov = Move::new(12, 28, MoveType::Normal);
+        table.store(TranspositionTableEntry { best_move: Some(mov), ..entry(7, 3) }, 0);
+        table.store(entry(7, 4), 0);
+
+        let probed = table.probe(7, 0).unwrap();
+        assert_eq!(probed.depth, 4);
+        assert_eq!(probed.best_move, Some(mov));
+    }
+
+    #[test]
+    fn test_mate_scores_adjusted_by_ply() {
+        let mut table = TranspositionTable::with_capacity(100);
+
+        // Mate found 5 plies from the root, stored at ply 3: mate in 2 from there
+        let mate = MATE_SCORE - 5;
+        table.store(TranspositionTableEntry { score: mate, ..entry(1, 4) }, 3);
+        assert_eq!(table.probe(1, 3).unwrap().score, mate);
+        // Reached at ply 1 instead, the mate is 3 plies from the root
+        assert_eq!(table.probe(1, 1).unwrap().score, MATE_SCORE - 3);
+
+        table.store(TranspositionTableEntry { score: -mate, ..entry(2, 4) }, 3);
+        assert_eq!(table.probe(2, 1).unwrap().score, -(MATE_SCORE - 3));
+
+        // Ordinary scores are left alone
+        table.store(TranspositionTableEntry { score: 250, ..entry(3, 4) }, 3);
+        assert_eq!(table.probe(3, 1).unwrap().score, 250);
+    }
+
+    #[test]
+    fn test_hashfull() {
+        let mut table = TranspositionTable::with_capacity(8);
+        assert_eq!(table.hashfull(), 0);
+
+        for hash in 0..4 {
+            table.store(entry(hash, 1), 0);
+        }
+        assert_eq!(table.hashfull(), 500);
+
+        // Only entries from the current search count
+        table.new_search();
+        assert_eq!(table.hashfull(), 0);
+        assert_eq!(table.size(), 4);
+    }
+
+    #[test]
+    fn test_shared_handles() {
+        let mut table = TranspositionTable::with_capacity(100);
+        let mut handle = table.share();
+        let mut copy = table.clone();
+
+        // Entries stored through one handle are seen through the other
+        handle.store(entry(42, 3), 0);
+        assert_eq!(table.probe(42, 0).unwrap().depth, 3);
+        assert_eq!(table.hits, 1);
+        assert_eq!(handle.hits, 0);
+
+        // A clone is a separate table
+        assert!(copy.probe(42, 0).is_none());
+
+        // Shared storage still works across threads
+        std::thread::scope(|scope| {
+            for thread in 0..4u64 {
+                let mut handle = table.share();
+                scope.spawn(move || {
+                    for i in 0..8 {
+                        handle.store(entry(thread * 8 + i + 100, 1), 0);
+                    }
+                });
+            }
+        });
+        assert!((100..132).all(|hash| table.probe(hash, 0).is_some()));
+    }
+
     #[test]
     fn test_hit_rate() {
         let mut table = TranspositionTable::with_capacity(100);
@@ -544,34 +619,13 @@ mod tests {
             best_move: None,
             node_type: NodeType::Exact,
         };
-        table.store(entry);
+        table.store(entry, 0);
 
         // One hit
-        table.probe(123);
+        table.probe(123, 0);
         // One miss
-        table.probe(456);
+        table.probe(456, 0);
 
         assert_eq!(table.hit_rate(), 0.5);
     }
-
-    #[test]
-    fn test_incremental_hash_update() {
-        let mut pos = Position::default();
-        let initial_hash = TranspositionTable::compute_hash(&pos);
-
-        // Make a move (e2-e4)
-        let mv = Move::new(12, 28, MoveType::Normal);
-        let updated_hash = TranspositionTable::update_hash(initial_hash, mv, &pos);
-
-        // Apply the move
-        pos.mk_move(mv);
-        let recomputed_hash = TranspositionTable::compute_hash(&pos);
-
-        // Hashes should be different from initial
-        assert_ne!(updated_hash, initial_hash);
-        assert_ne!(recomputed_hash, initial_hash);
-
-        // Note: incremental update might not match recomputed due to castling complexity
-        // In production, you'd either compute from scratch or track castling changes
-    }
 }
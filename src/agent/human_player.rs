@@ -66,7 +66,7 @@
 //! ```
 
 use crate::board::Board;
-use crate::game_repr::{Color, Move};
+use crate::game_repr::{Color, Move, MoveType};
 use crate::agent::player::Player;
 use std::cell::RefCell;
 use std::sync::Arc;
@@ -189,14 +189,21 @@ impl HumanPlayer {
             board.legal_moves_for_selection().to_vec()
         };
 
-        // Look for a legal move matching this from/to combination
-        for mv in &legal_moves {
-            if mv._from() == from as usize && mv._to() == clicked_tile as usize {
-                // Valid move found!
-                self.pending_move = Some(*mv);
-                self.board.borrow_mut().set_selected_tile(None);
-                return;
-            }
+        // Look for a legal move matching this from/to combination. Castling is
+        // the king taking its own rook, but clicking the king's landing square
+        // also castles unless a plain king move goes there.
+        let exact = legal_moves.iter()
+            .find(|mv| mv._from() == from as usize && mv._to() == clicked_tile as usize);
+        let castle = || legal_moves.iter().find(|mv| {
+            mv._from() == from as usize
+                && mv.move_type() == MoveType::Castling
+                && mv.castling_destinations().0 == clicked_tile as usize
+        });
+        if let Some(mv) = exact.or_else(castle) {
+            // Valid move found!
+            self.pending_move = Some(*mv);
+            self.board.borrow_mut().set_selected_tile(None);
+            return;
         }
 
         // Case 3: Click didn't create a legal move
@@ -329,6 +336,7 @@ impl Player for HumanPlayer {
 }
 
 #[cfg(test)]
+#[allow(clippy::arc_with_non_send_sync)]
 mod tests {
     use super::*;
     use crate::renderer::Renderer;
@@ -379,7 +387,7 @@ mod tests {
         fn is_coord_in_start_button(&self, _coords: PhysicalPosition<f64>) -> bool {
             false
         }
-        fn draw_menu_state(&mut self, _state: &crate::menu::MenuState) {}
+        fn draw_menu_state(&mut self, _state: &crate::menu::MenuState, _chess960: bool) {}
         fn window_size(&self) -> (u32, u32) {
             (800, 800)
         }
@@ -30,17 +30,16 @@
 //! // let ai_player = NegamaxPlayer::new(board.clone(), Difficulty::Hard, "Stockfish".to_string());
 //! ```
 //!
-//! # Synchronous Design
+//! # Polling Design
 //!
-//! The `get_move()` method is intentionally synchronous (blocking) rather than async.
-//! This design choice simplifies the control flow for a turn-based game:
-//! - `HumanPlayer` can block waiting for user input events
-//! - `NegamaxPlayer` can block during move search/computation
-//! - The orchestrator simply calls `get_move()` and waits for a result
+//! The `get_move()` method is synchronous but must not block for long. The
+//! orchestrator polls it after input events, and every frame while
+//! [`Player::is_thinking`] is true:
+//! - `HumanPlayer` returns `None` until the user has completed a move
+//! - `NegamaxPlayer` searches on a worker thread and returns `None` until it is done
+//! - The orchestrator processes the move as soon as one is returned
 //!
-//! This approach is sufficient for single-threaded gameplay. If future requirements
-//! demand non-blocking behavior (e.g., background AI computation, network operations),
-//! the trait can be migrated to async methods.
+//! This keeps the window rendering and responsive while an AI thinks.
 
 use crate::game_repr::{Color, Move};
 use winit::event::WindowEvent;
@@ -59,6 +58,12 @@ pub enum GameResult {
     Draw,
     /// Game ended in stalemate (player to move has no legal moves but is not in check)
     Stalemate,
+    /// Game drawn because the same position occurred three times
+    ThreefoldRepetition,
+    /// Draw claimed after fifty moves by each side without a capture or pawn move
+    FiftyMoveRule,
+    /// Automatic draw after seventy-five moves by each side without a capture or pawn move
+    SeventyFiveMoveRule,
 }
 
 impl GameResult {
@@ -88,8 +93,8 @@ impl GameResult {
 /// # Method Behavior
 ///
 /// ## `get_move()`
-/// - **Blocking**: This method may block until a move is available
-/// - **Returns `None`**: If the player cancels, resigns, or disconnects
+/// - **Polled**: Returns `None` until a move is available
+/// - **Returns `None`**: If the player cancels, resigns (see `has_resigned()`), or disconnects
 /// - **Returns `Some(Move)`**: When a valid move is selected
 /// - The move returned must be legal in the current position (validation is typically
 ///   done by the caller/orchestrator)
@@ -121,18 +126,18 @@ pub trait Player {
     /// This method is called when it's this player's turn to move. The `color` parameter
     /// indicates which side the player is playing (White or Black).
     ///
-    /// # Blocking Behavior
+    /// # Polling Behavior
     ///
-    /// This method **may block** until a move is available:
-    /// - `HumanPlayer` blocks until the user makes a selection via the UI
-    /// - `NegamaxPlayer` blocks during search/computation
-    /// - `NetworkPlayer` blocks waiting for data from the network
+    /// This method is called repeatedly and returns `None` until a move is available:
+    /// - `HumanPlayer` returns a move once the user makes a selection via the UI
+    /// - `NegamaxPlayer` returns a move once its background search finishes
+    /// - `NetworkPlayer` returns a move once it arrives from the network
     ///
     /// # Return Value
     ///
     /// - `Some(Move)`: A valid move selected by the player
-    /// - `None`: The player cannot or will not provide a move (resignation, cancellation,
-    ///   disconnection, etc.)
+    /// - `None`: No move yet, or the player cannot or will not provide a move
+    ///   (resignation, cancellation, disconnection, etc.)
     ///
     /// # Examples
     ///
@@ -248,6 +253,43 @@ pub trait Player {
         "Player"
     }
 
+    /// Whether the player is still working out a move in the background.
+    ///
+    /// While this is true the orchestrator keeps redrawing and polls
+    /// [`get_move()`](Player::get_move) every frame instead of waiting for input.
+    ///
+    /// # Default Implementation
+    ///
+    /// Returns `false`.
+    fn is_thinking(&self) -> bool {
+        false
+    }
+
+    /// Ask the player to move now.
+    ///
+    /// A thinking player should return the best move it has found so far from
+    /// its next [`get_move()`](Player::get_move) call.
+    ///
+    /// # Default Implementation
+    ///
+    /// Does nothing.
+    fn stop_thinking(&mut self) {
+        // Default: do nothing
+    }
+
+    /// Whether the player has given up the game.
+    ///
+    /// Checked when [`get_move()`](Player::get_move) returns `None`; a player
+    /// that has resigned loses the game. AI players resign when their search
+    /// fails.
+    ///
+    /// # Default Implementation
+    ///
+    /// Returns `false`.
+    fn has_resigned(&self) -> bool {
+        false
+    }
+
     /// Get automatic promotion piece choice for this player.
     ///
     /// This method is called when a pawn reaches the back rank and needs to be promoted.
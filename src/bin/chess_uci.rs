@@ -0,0 +1,450 @@
+//! Headless UCI engine speaking the protocol on stdin/stdout.
+//!
+//! ```text
+//! chess-uci
+//! ```
+//!
+//! Supports `uci`, `isready`, `ucinewgame`, `position`, `go` (`depth`,
+//! `movetime`, `wtime`/`btime`, `winc`/`binc`, `movestogo`, `nodes`,
+//! `infinite`), `stop`, `quit` and `setoption` for `Hash`, `Threads`,
+//! `MultiPV`, `Contempt` and `UCI_Chess960`, plus one check option per
+//! selective search feature (`LateMoveReductions`, `ReverseFutilityPruning`,
+//! `FutilityPruning`, `Razoring`, `CheckExtensions`,
+//! `InternalIterativeReductions`, `QuiescenceChecks`) for measuring them in
+//! self-play. The search runs on a worker thread so `stop` and `isready` are
+//! answered while it thinks.
+
+use chess_engine::agent::ai::{
+    SearchConfig, SearchLimits, Searcher, TimeManager, DEFAULT_HASH_MB, MAX_SEARCH_DEPTH, MOVE_OVERHEAD_MS,
+};
+use chess_engine::game_repr::{Color, Position};
+use std::io::BufRead;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+const ENGINE_NAME: &str = concat!("chess_engine ", env!("CARGO_PKG_VERSION"));
+
+const MAX_HASH_MB: usize = 4096;
+const MAX_THREADS: usize = 256;
+const MAX_MULTI_PV: usize = 256;
+const MAX_CONTEMPT: i32 = 1000;
+
+/// The flag a check option sets in the search config
+type Switch = fn(&mut SearchConfig) -> &mut bool;
+
+/// Check options switching search features on and off
+const SEARCH_SWITCHES: [(&str, Switch); 7] = [
+    ("LateMoveReductions", |config| &mut config.late_move_reductions),
+    ("ReverseFutilityPruning", |config| &mut config.reverse_futility_pruning),
+    ("FutilityPruning", |config| &mut config.futility_pruning),
+    ("Razoring", |config| &mut config.razoring),
+    ("CheckExtensions", |config| &mut config.check_extensions),
+    ("InternalIterativeReductions", |config| &mut config.internal_iterative_reductions),
+    ("QuiescenceChecks", |config| &mut config.quiescence_checks),
+];
+
+/// Parameters of a `go` command
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct GoParams {
+    depth: Option<u8>,
+    movetime: Option<u64>,
+    wtime: Option<u64>,
+    btime: Option<u64>,
+    winc: Option<u64>,
+    binc: Option<u64>,
+    movestogo: Option<u64>,
+    nodes: Option<u64>,
+    infinite: bool,
+}
+
+impl GoParams {
+    /// Parses the arguments after `go`, ignoring unknown ones
+    fn parse(args: &[&str]) -> Self {
+        let mut params = GoParams::default();
+        let mut iter = args.iter();
+
+        while let Some(&arg) = iter.next() {
+            // Clock values may be negative when a GUI lets the clock run out
+            let mut value = || iter.next().and_then(|v| v.parse::<i64>().ok()).map(|v| v.max(0) as u64);
+            match arg {
+                "depth" => params.depth = value().map(|d| d.clamp(1, MAX_SEARCH_DEPTH as u64) as u8),
+                "movetime" => params.movetime = value(),
+                "wtime" => params.wtime = value(),
+                "btime" => params.btime = value(),
+                "winc" => params.winc = value(),
+                "binc" => params.binc = value(),
+                "movestogo" => params.movestogo = value(),
+                "nodes" => params.nodes = value(),
+                "infinite" => params.infinite = true,
+                _ => {}
+            }
+        }
+
+        params
+    }
+
+    /// Fixed time for this move, from `movetime`
+    fn time_limit_ms(&self) -> Option<u64> {
+        if self.infinite {
+            return None;
+        }
+        self.movetime.map(|movetime| movetime.saturating_sub(MOVE_OVERHEAD_MS).max(1))
+    }
+
+    /// Time limits from `side`'s clock, unless `movetime` or `infinite` is
+    /// given
+    fn time_manager(&self, side: Color) -> Option<TimeManager> {
+        if self.infinite || self.movetime.is_some() {
+            return None;
+        }
+
+        let (time, inc) = match side {
+            Color::White => (self.wtime?, self.winc.unwrap_or(0)),
+            Color::Black => (self.btime?, self.binc.unwrap_or(0)),
+        };
+        Some(TimeManager::new(time, inc, self.movestogo))
+    }
+
+    fn limits(&self, side: Color, threads: usize, multi_pv: usize, stop: Arc<AtomicBool>) -> SearchLimits {
+        SearchLimits {
+            max_depth: self.depth.unwrap_or(MAX_SEARCH_DEPTH),
+            time_limit_ms: self.time_limit_ms(),
+            time_manager: self.time_manager(side),
+            node_limit: if self.infinite { None } else { self.nodes },
+            stop: Some(stop),
+            threads,
+            multi_pv,
+            ..SearchLimits::default()
+        }
+    }
+}
+
+/// Builds the position of a `position` command, along with the Zobrist keys
+/// of the positions the moves went through
+///
+/// `args` is everything after `position`: `startpos` or `fen <fields>`,
+/// optionally followed by `moves` and moves in long algebraic notation.
+fn parse_position(args: &[&str], chess960: bool) -> Result<(Position, Vec<u64>), String> {
+    let moves_at = args.iter().position(|&arg| arg == "moves").unwrap_or(args.len());
+    let (setup, moves) = args.split_at(moves_at);
+
+    let mut pos = match setup {
+        ["startpos"] => Position::default(),
+        ["fen", fields @ ..] if !fields.is_empty() => {
+            Position::try_from_fen(&fields.join(" ")).map_err(|err| err.to_string())?
+        }
+        _ => return Err(format!("invalid position command '{}'", setup.join(" "))),
+    };
+    pos.chess960 = chess960;
+
+    let mut history = Vec::new();
+    for text in moves.iter().skip(1) {
+        let mv = pos.parse_uci_move(text).map_err(|err| format!("move '{}': {}", text, err))?;
+        history.push(pos.zobrist);
+        pos.mk_move(mv);
+    }
+
+    Ok((pos, history))
+}
+
+/// Parses `name <id> [value <x>]` after `setoption`
+fn parse_setoption(args: &[&str]) -> Option<(String, String)> {
+    let (&"name", rest) = args.split_first()? else {
+        return None;
+    };
+    let value_at = rest.iter().position(|&arg| arg == "value").unwrap_or(rest.len());
+    let name = rest[..value_at].join(" ");
+    let value = rest.get(value_at + 1..).unwrap_or_default().join(" ");
+    (!name.is_empty()).then_some((name, value))
+}
+
+/// A search running on the worker thread
+struct RunningSearch {
+    stop: Arc<AtomicBool>,
+    /// Hands the searcher back when the search ends
+    handle: JoinHandle<Searcher>,
+}
+
+struct Engine {
+    position: Position,
+    /// Keys of the positions before `position`, for repetitions
+    history: Vec<u64>,
+    chess960: bool,
+    threads: usize,
+    multi_pv: usize,
+    /// `None` while a search owns it
+    searcher: Option<Searcher>,
+    search: Option<RunningSearch>,
+}
+
+impl Engine {
+    fn new() -> Self {
+        Self {
+            position: Position::default(),
+            history: Vec::new(),
+            chess960: false,
+            threads: 1,
+            multi_pv: 1,
+            searcher: Some(Searcher::new()),
+            search: None,
+        }
+    }
+
+    /// Handles one input line, returning false on `quit`
+    fn handle(&mut self, line: &str) -> bool {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = tokens.split_first() else {
+            return true;
+        };
+
+        match command {
+            "uci" => {
+                println!("id name {}", ENGINE_NAME);
+                println!("id author the chess_engine authors");
+                println!("option name Hash type spin default {} min 1 max {}", DEFAULT_HASH_MB, MAX_HASH_MB);
+                println!("option name Threads type spin default 1 min 1 max {}", MAX_THREADS);
+                println!("option name MultiPV type spin default 1 min 1 max {}", MAX_MULTI_PV);
+                println!("option name Contempt type spin default 0 min -{} max {}", MAX_CONTEMPT, MAX_CONTEMPT);
+                println!("option name UCI_Chess960 type check default false");
+                let mut defaults = SearchConfig::default();
+                for (name, switch) in SEARCH_SWITCHES {
+                    println!("option name {} type check default {}", name, switch(&mut defaults));
+                }
+                println!("uciok");
+            }
+            "isready" => println!("readyok"),
+            "ucinewgame" => {
+                self.stop();
+                self.searcher_mut().new_game();
+                self.position = Position::default();
+                self.history.clear();
+            }
+            "position" => {
+                self.stop();
+                match parse_position(args, self.chess960) {
+                    Ok((pos, history)) => {
+                        self.position = pos;
+                        self.history = history;
+                    }
+                    Err(err) => println!("info string {}", err),
+                }
+            }
+            "setoption" => {
+                self.stop();
+                match parse_setoption(args) {
+                    Some((name, value)) => self.set_option(&name, &value),
+                    None => println!("info string invalid setoption command"),
+                }
+            }
+            "go" => {
+                self.stop();
+                self.go(GoParams::parse(args));
+            }
+            "stop" => self.stop(),
+            "quit" => {
+                self.stop();
+                return false;
+            }
+            _ => println!("info string unknown command '{}'", command),
+        }
+
+        true
+    }
+
+    fn set_option(&mut self, name: &str, value: &str) {
+        match name.to_ascii_lowercase().as_str() {
+            "hash" => match value.parse::<usize>() {
+                Ok(mb) => self.searcher_mut().set_hash_mb(mb.clamp(1, MAX_HASH_MB)),
+                Err(_) => println!("info string invalid Hash value '{}'", value),
+            },
+            "threads" => match value.parse::<usize>() {
+                Ok(threads) => self.threads = threads.clamp(1, MAX_THREADS),
+                Err(_) => println!("info string invalid Threads value '{}'", value),
+            },
+            "multipv" => match value.parse::<usize>() {
+                Ok(lines) => self.multi_pv = lines.clamp(1, MAX_MULTI_PV),
+                Err(_) => println!("info string invalid MultiPV value '{}'", value),
+            },
+            "contempt" => match value.parse::<i32>() {
+                Ok(contempt) => self.searcher_mut().config.contempt = contempt.clamp(-MAX_CONTEMPT, MAX_CONTEMPT),
+                Err(_) => println!("info string invalid Contempt value '{}'", value),
+            },
+            "uci_chess960" => {
+                self.chess960 = value.eq_ignore_ascii_case("true");
+                self.position.chess960 = self.chess960;
+            }
+            _ => match SEARCH_SWITCHES.iter().find(|(switch, _)| switch.eq_ignore_ascii_case(name)) {
+                Some((_, switch)) => *switch(&mut self.searcher_mut().config) = value.eq_ignore_ascii_case("true"),
+                None => println!("info string unknown option '{}'", name),
+            },
+        }
+    }
+
+    fn go(&mut self, params: GoParams) {
+        let stop = Arc::new(AtomicBool::new(false));
+        let pos = self.position.clone();
+        let limits = SearchLimits {
+            history: self.history.clone(),
+            ..params.limits(pos.side_to_move, self.threads, self.multi_pv, stop.clone())
+        };
+        let mut searcher = self.searcher.take().expect("no search is running");
+
+        let search_stop = stop.clone();
+        let handle = thread::spawn(move || {
+            let result = searcher.search(&pos, limits, &mut |info| {
+                println!("{}", info.to_uci(&pos));
+            });
+
+            // In infinite mode the move may only be sent after `stop`
+            while params.infinite && !search_stop.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(5));
+            }
+
+            match result.best_move {
+                Some(mv) => println!("bestmove {}", pos.move_to_uci(mv)),
+                None => println!("bestmove 0000"),
+            }
+            searcher
+        });
+
+        self.search = Some(RunningSearch { stop, handle });
+    }
+
+    /// Stops the running search, if any, and waits for its `bestmove`
+    fn stop(&mut self) {
+        if let Some(search) = self.search.take() {
+            search.stop.store(true, Ordering::Relaxed);
+            self.searcher = Some(search.handle.join().expect("search thread panicked"));
+        }
+    }
+
+    fn searcher_mut(&mut self) -> &mut Searcher {
+        self.searcher.as_mut().expect("no search is running")
+    }
+}
+
+fn main() {
+    let mut engine = Engine::new();
+
+    for line in std::io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if !engine.handle(&line) {
+            return;
+        }
+    }
+
+    // End of input: let a running search finish its output
+    engine.stop();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<&str> {
+        text.split_whitespace().collect()
+    }
+
+    #[test]
+    fn test_parse_go() {
+        let params = GoParams::parse(&words("wtime 60000 btime 55000 winc 1000 binc 1000 movestogo 20 nodes 5000"));
+        assert_eq!(params.wtime, Some(60000));
+        assert_eq!(params.btime, Some(55000));
+        assert_eq!(params.winc, Some(1000));
+        assert_eq!(params.movestogo, Some(20));
+        assert_eq!(params.nodes, Some(5000));
+        assert!(!params.infinite);
+
+        let params = GoParams::parse(&words("depth 7 movetime 500 infinite wtime -20"));
+        assert_eq!(params.depth, Some(7));
+        assert_eq!(params.movetime, Some(500));
+        assert_eq!(params.wtime, Some(0));
+        assert!(params.infinite);
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let params = GoParams::parse(&words("movetime 1000 wtime 60000"));
+        assert_eq!(params.time_limit_ms(), Some(1000 - MOVE_OVERHEAD_MS));
+        assert_eq!(params.time_manager(Color::White), None);
+
+        let params = GoParams::parse(&words("wtime 60000 btime 30000 winc 1000 movestogo 20"));
+        assert_eq!(params.time_limit_ms(), None);
+        assert_eq!(params.time_manager(Color::White), Some(TimeManager::new(60000, 1000, Some(20))));
+        assert_eq!(params.time_manager(Color::White).unwrap().soft_ms(), 3000 + 750);
+        assert_eq!(params.time_manager(Color::Black).unwrap().soft_ms(), 1500);
+
+        // Never more than is left on the clock
+        let params = GoParams::parse(&words("wtime 100 winc 5000"));
+        assert_eq!(params.time_manager(Color::White).unwrap().hard_ms(), 100 - MOVE_OVERHEAD_MS);
+
+        assert_eq!(GoParams::parse(&words("depth 5")).time_manager(Color::White), None);
+        assert_eq!(GoParams::parse(&words("infinite wtime 1000")).time_manager(Color::White), None);
+        assert_eq!(GoParams::parse(&words("infinite")).time_limit_ms(), None);
+    }
+
+    #[test]
+    fn test_parse_position() {
+        let (pos, history) = parse_position(&words("startpos moves e2e4 e7e5 g1f3"), false).unwrap();
+        assert_eq!(pos.to_fen(), "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0], Position::default().zobrist);
+
+        let (pos, _) = parse_position(&words("fen 4k3/8/8/8/8/8/8/4K2R w K - 0 1 moves e1g1"), false).unwrap();
+        assert_eq!(pos.to_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+
+        // Chess960 castling is written king-takes-rook
+        let (pos, _) = parse_position(&words("fen 4k3/8/8/8/8/8/8/4K2R w K - 0 1 moves e1h1"), true).unwrap();
+        assert!(pos.chess960);
+        assert_eq!(pos.to_fen().split(' ').next(), Some("4k3/8/8/8/8/8/8/5RK1"));
+
+        assert!(parse_position(&words("startpos moves e2e5"), false).is_err());
+        assert!(parse_position(&words("fen"), false).is_err());
+        assert!(parse_position(&words("somewhere"), false).is_err());
+    }
+
+    #[test]
+    fn test_parse_setoption() {
+        assert_eq!(
+            parse_setoption(&words("name Hash value 128")),
+            Some(("Hash".to_string(), "128".to_string()))
+        );
+        assert_eq!(
+            parse_setoption(&words("name Clear Hash")),
+            Some(("Clear Hash".to_string(), String::new()))
+        );
+        assert_eq!(parse_setoption(&words("Hash 128")), None);
+    }
+
+    #[test]
+    fn test_engine_search_and_options() {
+        let mut engine = Engine::new();
+        assert!(engine.handle("setoption name Hash value 8"));
+        assert!(engine.handle("setoption name Threads value 4"));
+        assert!(engine.handle("setoption name MultiPV value 3"));
+        assert!(engine.handle("setoption name Contempt value 25"));
+        assert_eq!(engine.searcher_mut().hash_mb(), 8);
+        assert_eq!(engine.threads, 4);
+        assert_eq!(engine.multi_pv, 3);
+        assert_eq!(engine.searcher_mut().config.contempt, 25);
+        assert!(engine.handle("setoption name LateMoveReductions value false"));
+        assert!(engine.handle("setoption name razoring value false"));
+        let config = engine.searcher_mut().config;
+        assert!(!config.late_move_reductions && !config.razoring);
+        assert!(config.futility_pruning && config.check_extensions);
+        assert!(!config.quiescence_checks);
+        assert!(engine.handle("setoption name QuiescenceChecks value true"));
+        assert!(engine.searcher_mut().config.quiescence_checks);
+
+        assert!(engine.handle("position startpos moves e2e4"));
+        assert!(engine.handle("go depth 2"));
+        assert!(engine.search.is_some());
+        assert!(!engine.handle("quit"));
+        assert!(engine.search.is_none());
+        assert!(engine.searcher.is_some());
+    }
+}
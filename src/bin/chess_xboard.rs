@@ -0,0 +1,595 @@
+//! Headless engine speaking the xboard/WinBoard (CECP) protocol version 2 on
+//! stdin/stdout.
+//!
+//! ```text
+//! chess-xboard
+//! ```
+//!
+//! Supports `xboard`, `protover` (with `feature` negotiation), `new`,
+//! `variant`, `setboard`, `force`, `go`, `usermove`, `?`, `level`, `st`,
+//! `sd`, `cores`, `time`, `otim`, `undo`, `remove`, `post`/`nopost`, `ping`,
+//! `result` and `quit`. Moves are read in coordinate notation (or SAN) and written in
+//! coordinate notation. The search runs on a worker thread so the GUI can
+//! interrupt it.
+
+use chess_engine::agent::ai::{
+    SearchInfo, SearchLimits, Searcher, TimeManager, MAX_SEARCH_DEPTH, MOVE_OVERHEAD_MS,
+};
+use chess_engine::game_repr::{Color, Move, MoveType, Position};
+use std::io::BufRead;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+const ENGINE_NAME: &str = concat!("chess_engine ", env!("CARGO_PKG_VERSION"));
+
+/// Per-move budget when the GUI has sent no time control at all
+const DEFAULT_MOVE_TIME_MS: u64 = 5000;
+
+/// Most search threads `cores` will set
+const MAX_CORES: usize = 256;
+
+/// xboard reports a mate in N as this plus N (or minus, when being mated)
+const XBOARD_MATE_SCORE: i32 = 100_000;
+
+/// Input lines and finished searches, handled in order by the main thread
+enum Event {
+    Line(String),
+    /// Result of the search with the given id
+    Searched { id: u64, best_move: Option<Move> },
+}
+
+/// Time control set by `level` or `st`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimeControl {
+    /// No `level` or `st` received
+    None,
+    /// `level MPS BASE INC`: MPS moves per BASE (0 = whole game), plus INC
+    /// per move
+    Conventional { moves_per_session: u32, increment_ms: u64 },
+    /// `st`: exactly this long per move
+    PerMove { ms: u64 },
+}
+
+/// Parses the arguments of `level MPS BASE INC`
+///
+/// BASE is minutes or `minutes:seconds`; INC is seconds and may be
+/// fractional. The base time itself is not needed since `time` reports the
+/// clock before every move.
+fn parse_level(args: &[&str]) -> Option<TimeControl> {
+    let [mps, base, inc] = args else {
+        return None;
+    };
+    let moves_per_session = mps.parse().ok()?;
+    let (minutes, seconds) = base.split_once(':').unwrap_or((base, "0"));
+    minutes.parse::<u64>().ok()?;
+    seconds.parse::<u64>().ok()?;
+    let increment_ms = (inc.parse::<f64>().ok()? * 1000.0) as u64;
+    Some(TimeControl::Conventional { moves_per_session, increment_ms })
+}
+
+/// Score in xboard's convention, where mates are reported as ±100000+N
+fn xboard_score(info: &SearchInfo) -> i32 {
+    match info.mate_in() {
+        Some(moves) if moves >= 0 => XBOARD_MATE_SCORE + moves,
+        Some(moves) => -XBOARD_MATE_SCORE + moves,
+        None => info.score,
+    }
+}
+
+/// A `post` thinking line: depth, score, time in centiseconds, nodes and the
+/// PV in SAN
+fn thinking_line(info: &SearchInfo, pos: &Position) -> String {
+    let mut line = pos.clone();
+    let pv: Vec<String> = info
+        .pv
+        .iter()
+        .map(|&mv| {
+            let san = line.move_to_san(mv);
+            line.mk_move(mv);
+            san
+        })
+        .collect();
+
+    format!("{} {} {} {} {}", info.depth, xboard_score(info), info.time_ms / 10, info.nodes, pv.join(" "))
+}
+
+/// A move as sent to xboard: coordinate notation, with Chess960 castling as
+/// `O-O`/`O-O-O`
+fn xboard_move(pos: &Position, mv: Move) -> String {
+    if pos.chess960 && mv.move_type() == MoveType::Castling {
+        if mv._to() > mv._from() { "O-O" } else { "O-O-O" }.to_string()
+    } else {
+        mv.to_uci()
+    }
+}
+
+/// Reads a move in coordinate notation, falling back to SAN
+fn parse_move(pos: &Position, text: &str) -> Option<Move> {
+    pos.parse_uci_move(text).ok().or_else(|| pos.parse_san(text).ok())
+}
+
+/// `RESULT {comment}` if the game is over in `pos`
+///
+/// `history` holds every earlier position of the game, for repetitions.
+fn game_over(pos: &Position, history: &[Position]) -> Option<&'static str> {
+    let side = pos.side_to_move;
+    if !pos.has_legal_moves(side) {
+        return Some(match (pos.is_in_check(side), side) {
+            (true, Color::White) => "0-1 {Black mates}",
+            (true, Color::Black) => "1-0 {White mates}",
+            (false, _) => "1/2-1/2 {Stalemate}",
+        });
+    }
+    if pos.halfmove_clock >= 100 {
+        return Some("1/2-1/2 {Fifty move rule}");
+    }
+    let repetitions = history.iter().filter(|earlier| earlier.zobrist == pos.zobrist).count();
+    if repetitions >= 2 {
+        return Some("1/2-1/2 {Draw by repetition}");
+    }
+    None
+}
+
+/// A search running on the worker thread
+struct RunningSearch {
+    id: u64,
+    stop: Arc<AtomicBool>,
+    /// Hands the searcher back when the search ends
+    handle: JoinHandle<Searcher>,
+}
+
+struct Engine {
+    position: Position,
+    /// Positions before each move played, for `undo` and repetitions
+    history: Vec<Position>,
+    chess960: bool,
+    /// In force mode the engine only records moves
+    force: bool,
+    /// Side the engine plays, set by `go`
+    engine_color: Color,
+    post: bool,
+    time_control: TimeControl,
+    max_depth: u8,
+    /// Search threads, from `cores`
+    cores: usize,
+    /// Engine's clock in milliseconds, from `time`
+    time_left_ms: Option<u64>,
+    /// `None` while a search owns it
+    searcher: Option<Searcher>,
+    search: Option<RunningSearch>,
+    next_search_id: u64,
+    events: Sender<Event>,
+}
+
+impl Engine {
+    fn new(events: Sender<Event>) -> Self {
+        Self {
+            position: Position::default(),
+            history: Vec::new(),
+            chess960: false,
+            force: false,
+            engine_color: Color::Black,
+            post: false,
+            time_control: TimeControl::None,
+            max_depth: MAX_SEARCH_DEPTH,
+            cores: 1,
+            time_left_ms: None,
+            searcher: Some(Searcher::new()),
+            search: None,
+            next_search_id: 0,
+            events,
+        }
+    }
+
+    /// Handles one event, returning false on `quit`
+    fn handle(&mut self, event: Event) -> bool {
+        match event {
+            Event::Line(line) => return self.command(&line),
+            Event::Searched { id, best_move } => self.searched(id, best_move),
+        }
+        true
+    }
+
+    fn command(&mut self, line: &str) -> bool {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = tokens.split_first() else {
+            return true;
+        };
+
+        match command {
+            "xboard" | "accepted" | "rejected" | "random" | "hard" | "easy" | "computer" | "name" | "rating"
+            | "otim" => {}
+            "protover" => println!(
+                "feature myname=\"{}\" setboard=1 usermove=1 ping=1 sigint=0 sigterm=0 san=0 colors=0 \
+                 analyze=0 smp=1 variants=\"normal,fischerandom\" done=1",
+                ENGINE_NAME
+            ),
+            "new" => {
+                self.abort_search();
+                self.set_position(Position::default());
+                self.chess960 = false;
+                self.force = false;
+                self.engine_color = Color::Black;
+                self.max_depth = MAX_SEARCH_DEPTH;
+                self.searcher_mut().new_game();
+            }
+            "variant" => {
+                self.chess960 = args.first() == Some(&"fischerandom");
+                self.position.chess960 = self.chess960;
+            }
+            "setboard" => {
+                self.abort_search();
+                match Position::try_from_fen(&args.join(" ")) {
+                    Ok(mut pos) => {
+                        pos.chess960 = self.chess960;
+                        self.set_position(pos);
+                    }
+                    Err(err) => println!("tellusererror Illegal position: {}", err),
+                }
+            }
+            "force" => {
+                self.abort_search();
+                self.force = true;
+            }
+            "go" => {
+                self.abort_search();
+                self.force = false;
+                self.engine_color = self.position.side_to_move;
+                self.think();
+            }
+            "usermove" => match args.first() {
+                Some(text) => self.usermove(text),
+                None => println!("Error (no move given): usermove"),
+            },
+            "?" => {
+                if let Some(search) = &self.search {
+                    search.stop.store(true, Ordering::Relaxed);
+                }
+            }
+            "level" => match parse_level(args) {
+                Some(time_control) => self.time_control = time_control,
+                None => println!("Error (bad level): {}", line),
+            },
+            "st" => match args.first().and_then(|v| v.parse::<f64>().ok()) {
+                Some(seconds) => self.time_control = TimeControl::PerMove { ms: (seconds * 1000.0) as u64 },
+                None => println!("Error (bad time): {}", line),
+            },
+            "sd" => match args.first().and_then(|v| v.parse::<u64>().ok()) {
+                Some(depth) => self.max_depth = depth.clamp(1, MAX_SEARCH_DEPTH as u64) as u8,
+                None => println!("Error (bad depth): {}", line),
+            },
+            "cores" => match args.first().and_then(|v| v.parse::<usize>().ok()) {
+                Some(cores) => self.cores = cores.clamp(1, MAX_CORES),
+                None => println!("Error (bad cores): {}", line),
+            },
+            // Centiseconds on the engine's clock
+            "time" => self.time_left_ms = args.first().and_then(|v| v.parse::<i64>().ok()).map(|cs| cs.max(0) as u64 * 10),
+            "undo" => self.take_back(1),
+            "remove" => self.take_back(2),
+            "post" => self.post = true,
+            "nopost" => self.post = false,
+            "ping" => println!("pong {}", args.first().unwrap_or(&"")),
+            "result" => {
+                self.abort_search();
+                self.force = true;
+            }
+            "quit" => {
+                self.abort_search();
+                return false;
+            }
+            _ => println!("Error (unknown command): {}", command),
+        }
+
+        true
+    }
+
+    fn usermove(&mut self, text: &str) {
+        self.abort_search();
+        let Some(mv) = parse_move(&self.position, text) else {
+            println!("Illegal move: {}", text);
+            return;
+        };
+
+        self.play(mv);
+        if !self.force && self.position.side_to_move == self.engine_color {
+            self.think();
+        }
+    }
+
+    fn set_position(&mut self, pos: Position) {
+        self.position = pos;
+        self.history.clear();
+    }
+
+    fn play(&mut self, mv: Move) {
+        self.history.push(self.position.clone());
+        self.position.mk_move(mv);
+    }
+
+    fn take_back(&mut self, plies: usize) {
+        self.abort_search();
+        for _ in 0..plies {
+            if let Some(pos) = self.history.pop() {
+                self.position = pos;
+            }
+        }
+    }
+
+    /// Fixed time for the next move, when not playing on a clock
+    fn time_limit_ms(&self) -> Option<u64> {
+        match (self.time_control, self.time_left_ms) {
+            (TimeControl::PerMove { ms }, _) => Some(ms.saturating_sub(MOVE_OVERHEAD_MS).max(1)),
+            (_, None) => Some(DEFAULT_MOVE_TIME_MS),
+            _ => None,
+        }
+    }
+
+    /// Time limits from the engine's clock
+    fn time_manager(&self) -> Option<TimeManager> {
+        match (self.time_control, self.time_left_ms) {
+            (TimeControl::PerMove { .. }, _) | (_, None) => None,
+            (TimeControl::Conventional { moves_per_session, increment_ms }, Some(time_left)) => {
+                let moves_to_go = (moves_per_session > 0).then(|| {
+                    let played = (self.position.fullmove_number.saturating_sub(1)) % moves_per_session;
+                    (moves_per_session - played) as u64
+                });
+                Some(TimeManager::new(time_left, increment_ms, moves_to_go))
+            }
+            (TimeControl::None, Some(time_left)) => Some(TimeManager::new(time_left, 0, None)),
+        }
+    }
+
+    /// Starts searching for the side to move, or reports the result if the
+    /// game is already over
+    fn think(&mut self) {
+        if let Some(result) = game_over(&self.position, &self.history) {
+            println!("{}", result);
+            return;
+        }
+
+        let id = self.next_search_id;
+        self.next_search_id += 1;
+        let stop = Arc::new(AtomicBool::new(false));
+        let limits = SearchLimits {
+            max_depth: self.max_depth,
+            time_limit_ms: self.time_limit_ms(),
+            time_manager: self.time_manager(),
+            stop: Some(stop.clone()),
+            threads: self.cores,
+            history: self.history.iter().map(|earlier| earlier.zobrist).collect(),
+            ..SearchLimits::default()
+        };
+
+        let pos = self.position.clone();
+        let post = self.post;
+        let mut searcher = self.searcher.take().expect("a search is already running");
+        let events = self.events.clone();
+        let handle = thread::spawn(move || {
+            let result = searcher.search(&pos, limits, &mut |info| {
+                if post {
+                    println!("{}", thinking_line(info, &pos));
+                }
+            });
+            let _ = events.send(Event::Searched { id, best_move: result.best_move });
+            searcher
+        });
+
+        self.search = Some(RunningSearch { id, stop, handle });
+    }
+
+    fn searched(&mut self, id: u64, best_move: Option<Move>) {
+        if self.search.as_ref().map(|search| search.id) != Some(id) {
+            // Aborted by the GUI; the move is no longer wanted
+            return;
+        }
+        if let Some(search) = self.search.take() {
+            self.searcher = Some(search.handle.join().expect("search thread panicked"));
+        }
+
+        let Some(mv) = best_move else {
+            return;
+        };
+        println!("move {}", xboard_move(&self.position, mv));
+        self.play(mv);
+        if let Some(result) = game_over(&self.position, &self.history) {
+            println!("{}", result);
+        }
+    }
+
+    /// Stops the running search and waits for the searcher, without playing
+    /// its move
+    fn abort_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            search.stop.store(true, Ordering::Relaxed);
+            self.searcher = Some(search.handle.join().expect("search thread panicked"));
+        }
+    }
+
+    fn searcher_mut(&mut self) -> &mut Searcher {
+        self.searcher.as_mut().expect("no search is running")
+    }
+}
+
+fn main() {
+    let (sender, receiver): (Sender<Event>, Receiver<Event>) = mpsc::channel();
+
+    let input = sender.clone();
+    thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if input.send(Event::Line(line)).is_err() {
+                break;
+            }
+        }
+        let _ = input.send(Event::Line("quit".to_string()));
+    });
+
+    let mut engine = Engine::new(sender);
+    for event in receiver {
+        if !engine.handle(event) {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<&str> {
+        text.split_whitespace().collect()
+    }
+
+    fn engine() -> (Engine, Receiver<Event>) {
+        let (sender, receiver) = mpsc::channel();
+        (Engine::new(sender), receiver)
+    }
+
+    /// Waits for the running search and handles its result
+    fn finish_search(engine: &mut Engine, events: &Receiver<Event>) {
+        let event = events.recv().unwrap();
+        assert!(matches!(event, Event::Searched { .. }));
+        engine.handle(event);
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(
+            parse_level(&words("40 5 0")),
+            Some(TimeControl::Conventional { moves_per_session: 40, increment_ms: 0 })
+        );
+        assert_eq!(
+            parse_level(&words("0 2:30 1.5")),
+            Some(TimeControl::Conventional { moves_per_session: 0, increment_ms: 1500 })
+        );
+        assert_eq!(parse_level(&words("40 5")), None);
+        assert_eq!(parse_level(&words("40 x 0")), None);
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let (mut engine, _events) = engine();
+        assert_eq!(engine.time_limit_ms(), Some(DEFAULT_MOVE_TIME_MS));
+        assert_eq!(engine.time_manager(), None);
+
+        engine.command("st 2");
+        assert_eq!(engine.time_limit_ms(), Some(2000 - MOVE_OVERHEAD_MS));
+
+        // 40 moves in 5 minutes at move 1: 40 moves to go on a 300 s clock
+        engine.command("level 40 5 0");
+        engine.command("time 30000");
+        assert_eq!(engine.time_limit_ms(), None);
+        assert_eq!(engine.time_manager(), Some(TimeManager::new(300_000, 0, Some(40))));
+        assert_eq!(engine.time_manager().unwrap().soft_ms(), 300_000 / 40);
+    }
+
+    #[test]
+    fn test_usermove_force_and_undo() {
+        let (mut engine, _events) = engine();
+        engine.command("new");
+        engine.command("force");
+        engine.command("usermove e2e4");
+        engine.command("usermove e7e5");
+        engine.command("usermove Nf3");
+        assert!(engine.search.is_none());
+        assert_eq!(engine.position.to_fen(), "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+
+        engine.command("undo");
+        assert_eq!(engine.position.to_fen(), "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2");
+        engine.command("remove");
+        assert_eq!(engine.position.to_fen(), Position::default().to_fen());
+
+        // Illegal moves are rejected without changing the position
+        engine.command("usermove e2e5");
+        assert_eq!(engine.position.to_fen(), Position::default().to_fen());
+    }
+
+    #[test]
+    fn test_engine_replies_to_usermove() {
+        let (mut engine, events) = engine();
+        engine.command("new");
+        engine.command("sd 2");
+        engine.command("usermove e2e4");
+        assert!(engine.search.is_some());
+
+        finish_search(&mut engine, &events);
+        assert!(engine.search.is_none());
+        assert_eq!(engine.position.side_to_move, Color::White);
+        assert_eq!(engine.history.len(), 2);
+    }
+
+    #[test]
+    fn test_go_plays_side_to_move() {
+        let (mut engine, events) = engine();
+        engine.command("setboard 6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1");
+        engine.command("sd 3");
+        engine.command("go");
+        assert_eq!(engine.engine_color, Color::White);
+
+        finish_search(&mut engine, &events);
+        assert!(engine.position.is_checkmate(Color::Black));
+    }
+
+    #[test]
+    fn test_cores() {
+        let (mut engine, events) = engine();
+        engine.command("cores 4");
+        assert_eq!(engine.cores, 4);
+        engine.command("cores 0");
+        assert_eq!(engine.cores, 1);
+
+        // Same mate with helper threads
+        engine.command("cores 3");
+        engine.command("setboard 6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1");
+        engine.command("sd 3");
+        engine.command("go");
+        finish_search(&mut engine, &events);
+        assert!(engine.position.is_checkmate(Color::Black));
+    }
+
+    #[test]
+    fn test_aborted_search_is_ignored() {
+        let (mut engine, events) = engine();
+        engine.command("new");
+        engine.command("usermove e2e4");
+        engine.command("force");
+        assert!(engine.search.is_none());
+        // The searcher is back as soon as the search is aborted
+        assert!(engine.searcher.is_some());
+
+        // The stopped search still reports back, but its move is not played
+        finish_search(&mut engine, &events);
+        assert_eq!(engine.history.len(), 1);
+        assert!(engine.searcher.is_some());
+    }
+
+    #[test]
+    fn test_game_over() {
+        let mated = Position::from_fen("R5k1/5ppp/8/8/8/8/5PPP/6K1 b - - 1 1");
+        assert_eq!(game_over(&mated, &[]), Some("1-0 {White mates}"));
+
+        let stalemate = Position::from_fen("k7/8/1Q6/8/8/8/8/K7 b - - 0 1");
+        assert_eq!(game_over(&stalemate, &[]), Some("1/2-1/2 {Stalemate}"));
+
+        let start = Position::default();
+        assert_eq!(game_over(&start, &[]), None);
+        assert_eq!(
+            game_over(&start, &[start.clone(), start.clone()]),
+            Some("1/2-1/2 {Draw by repetition}")
+        );
+    }
+
+    #[test]
+    fn test_thinking_output() {
+        let pos = Position::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1");
+        let mate = SearchInfo { depth: 2, multipv: 1, score: 29999, nodes: 272, time_ms: 70, hashfull: 0, pv: vec![pos.parse_san("Ra8").unwrap()] };
+        assert_eq!(thinking_line(&mate, &pos), "2 100001 7 272 Ra8#");
+
+        let quiet = SearchInfo { depth: 1, multipv: 1, score: -40, nodes: 20, time_ms: 0, hashfull: 0, pv: Vec::new() };
+        assert_eq!(xboard_score(&quiet), -40);
+    }
+}
@@ -0,0 +1,95 @@
+//! Runs an EPD test suite or perft suite from the command line.
+//!
+//! ```text
+//! epd-suite <file.epd> [--depth N] [--time MS] [--perft-depth N]
+//! ```
+//!
+//! Records with `bm`/`am` operations are searched to `--depth` plies
+//! (default 6), optionally capped at `--time` milliseconds per position.
+//! Records with `D<n>` operations are perft-checked up to `--perft-depth`
+//! (default: every depth listed).
+
+use chess_engine::epd::{parse_epd_file, runner};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: epd-suite <file.epd> [--depth N] [--time MS] [--perft-depth N]";
+
+struct Options {
+    path: String,
+    depth: u8,
+    time_ms: Option<u64>,
+    perft_depth: Option<u32>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut path = None;
+    let mut options = Options { path: String::new(), depth: 6, time_ms: None, perft_depth: None };
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .and_then(|v| v.parse::<u64>().ok())
+                .ok_or_else(|| format!("{} expects a number", name))
+        };
+        match arg.as_str() {
+            "--depth" => options.depth = value("--depth")?.min(u8::MAX as u64) as u8,
+            "--time" => options.time_ms = Some(value("--time")?),
+            "--perft-depth" => options.perft_depth = Some(value("--perft-depth")? as u32),
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    options.path = path.ok_or("missing EPD file")?;
+    Ok(options)
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let text = match std::fs::read_to_string(&options.path) {
+        Ok(text) => text,
+        Err(err) => {
+            eprintln!("cannot read {}: {}", options.path, err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let suite = match parse_epd_file(&text) {
+        Ok(suite) => suite,
+        Err(err) => {
+            eprintln!("{}: {}", options.path, err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut ok = true;
+
+    let perft = runner::run_perft_suite(&suite, options.perft_depth);
+    if !perft.results.is_empty() {
+        println!("{}", perft);
+        ok &= perft.passed() == perft.results.len();
+    }
+
+    let search = runner::run_test_suite(&suite, options.depth, options.time_ms);
+    if !search.results.is_empty() {
+        println!("{}", search);
+    }
+
+    if perft.results.is_empty() && search.results.is_empty() {
+        eprintln!("{}: no bm, am or D<n> operations found", options.path);
+        return ExitCode::FAILURE;
+    }
+
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -1,5 +1,6 @@
 use crate::agent::player::GameResult;
-use crate::game_repr::{Color, Move, Piece, Position, UndoInfo};
+use crate::game_repr::{Color, FenError, Move, Piece, Position, UndoInfo};
+use crate::pgn::PgnGame;
 use crate::renderer::{ControlAction, Renderer};
 use winit::dpi::PhysicalPosition;
 use smallvec::SmallVec;
@@ -46,7 +47,7 @@ use smallvec::SmallVec;
 /// let board = Board::new(Box::new(WgpuRenderer::new(window)));
 ///
 /// // Or from FEN string
-/// let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR", renderer);
+/// let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR", renderer)?;
 ///
 /// // Query game state
 /// let piece = board.piece_at(0);
@@ -70,6 +71,10 @@ pub struct Board {
     /// The current chess position (game state)
     position: Position,
 
+    /// Position the game started from (the moves played since are in
+    /// `position.prev_moves`)
+    start_position: Position,
+
     /// Renderer responsible for drawing the board
     renderer: Box<dyn Renderer>,
 
@@ -94,6 +99,10 @@ pub struct Board {
     /// When at the end of history, this equals move_history.len()
     /// After undo, this can be less than move_history.len()
     history_index: usize,
+
+    /// Zobrist keys of every position reached in the current line, starting with
+    /// the initial position (used for repetition detection)
+    position_keys: Vec<u64>,
 }
 
 impl Board {
@@ -114,8 +123,11 @@ impl Board {
     /// let board = Board::new(renderer);
     /// ```
     pub fn new(renderer: Box<dyn Renderer>) -> Self {
+        let position = Position::default();
         Self {
-            position: Position::default(),
+            position_keys: vec![position.zobrist],
+            start_position: position.clone(),
+            position,
             renderer,
             selected_tile: None,
             legal_moves_cache: SmallVec::new(),
@@ -135,7 +147,8 @@ impl Board {
     ///
     /// # Returns
     ///
-    /// A new Board with the position from the FEN string.
+    /// A new Board with the position from the FEN string, or a [`FenError`]
+    /// if the FEN is malformed or describes an illegal position.
     ///
     /// # Example
     ///
@@ -143,11 +156,14 @@ impl Board {
     /// let board = Board::from_fen(
     ///     "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
     ///     renderer
-    /// );
+    /// )?;
     /// ```
-    pub fn from_fen(fen: &str, renderer: Box<dyn Renderer>) -> Self {
-        Self {
-            position: Position::from_fen(fen),
+    pub fn from_fen(fen: &str, renderer: Box<dyn Renderer>) -> Result<Self, FenError> {
+        let position = Position::try_from_fen(fen)?;
+        Ok(Self {
+            position_keys: vec![position.zobrist],
+            start_position: position.clone(),
+            position,
             renderer,
             selected_tile: None,
             legal_moves_cache: SmallVec::new(),
@@ -155,7 +171,7 @@ impl Board {
             mouse_pos: PhysicalPosition::new(0.0, 0.0),
             move_history: Vec::new(),
             history_index: 0,
-        }
+        })
     }
 
     // ===========================
@@ -174,6 +190,31 @@ impl Board {
         &self.position
     }
 
+    /// Get the position the game started from.
+    pub fn start_position(&self) -> &Position {
+        &self.start_position
+    }
+
+    /// Get the moves played from the start position to the current position.
+    ///
+    /// Undone moves are not included.
+    pub fn moves(&self) -> &[Move] {
+        &self.position.prev_moves
+    }
+
+    /// Export the game played so far as PGN.
+    ///
+    /// Headers other than the result are left as `?`. The result is filled in
+    /// when the game has ended (see [`get_game_result`](Self::get_game_result));
+    /// a claimed fifty-move draw has to be set on the returned game.
+    pub fn to_pgn_game(&self) -> PgnGame {
+        let mut game = PgnGame::new(self.start_position.clone(), self.moves().to_vec());
+        if let Some(result) = self.get_game_result() {
+            game.result = result.into();
+        }
+        game
+    }
+
     /// Get a mutable reference to the renderer.
     ///
     /// This provides direct access to the renderer for drawing operations that
@@ -299,6 +340,7 @@ impl Board {
     /// ```
     pub fn execute_move(&mut self, mv: Move) {
         self.position.mk_move(mv);
+        self.push_position_key();
         // Clear selection state after move
         self.selected_tile = None;
         self.legal_moves_cache.clear();
@@ -336,6 +378,7 @@ impl Board {
         let undo_info = self.position.make_move_undoable(mv);
         self.move_history.push((mv, undo_info));
         self.history_index += 1;
+        self.push_position_key();
 
         // Clear selection state
         self.selected_tile = None;
@@ -364,6 +407,7 @@ impl Board {
         self.history_index -= 1;
         let (mv, undo_info) = self.move_history[self.history_index];
         self.position.unmake_move(mv, undo_info);
+        self.position_keys.pop();
 
         // Clear selection state
         self.selected_tile = None;
@@ -392,6 +436,7 @@ impl Board {
         let (mv, _) = self.move_history[self.history_index];
         self.position.mk_move(mv);
         self.history_index += 1;
+        self.push_position_key();
 
         // Clear selection state
         self.selected_tile = None;
@@ -400,6 +445,38 @@ impl Board {
         Some(mv)
     }
 
+    /// Record the key of the current position for repetition detection.
+    fn push_position_key(&mut self) {
+        self.position_keys.push(self.position.zobrist);
+    }
+
+    /// Check if the current position has occurred at least three times.
+    ///
+    /// Only positions since the last capture or pawn move are considered, since
+    /// those moves are irreversible and no earlier position can recur.
+    pub fn is_threefold_repetition(&self) -> bool {
+        let Some(&current) = self.position_keys.last() else {
+            return false;
+        };
+
+        // Positions with the same side to move are two plies apart
+        let reversible = (self.position.halfmove_clock as usize).min(self.position_keys.len() - 1);
+        let occurrences = self.position_keys[self.position_keys.len() - 1 - reversible..]
+            .iter()
+            .rev()
+            .step_by(2)
+            .filter(|&&key| key == current)
+            .count();
+
+        occurrences >= 3
+    }
+
+    /// Zobrist keys of the positions before the current one, oldest first,
+    /// for the AI's repetition detection.
+    pub fn history_keys(&self) -> &[u64] {
+        &self.position_keys[..self.position_keys.len().saturating_sub(1)]
+    }
+
     /// Check if there are moves to undo.
     pub fn can_undo(&self) -> bool {
         self.history_index > 0
@@ -428,15 +505,9 @@ impl Board {
         };
     }
 
-    /// Get whose turn it is based on the number of moves played.
-    ///
-    /// White moves on even move counts (0, 2, 4, ...), Black on odd (1, 3, 5, ...).
+    /// Get whose turn it is in the current position.
     pub fn current_turn(&self) -> Color {
-        if self.history_index.is_multiple_of(2) {
-            Color::White
-        } else {
-            Color::Black
-        }
+        self.position.side_to_move
     }
 
     /// Check if the game is over (checkmate or stalemate).
@@ -448,14 +519,16 @@ impl Board {
         self.is_checkmate(turn) || self.is_stalemate(turn)
     }
 
-    /// Get the game result if the game is over (checkmate or stalemate).
+    /// Get the game result if the game is over.
     ///
-    /// Note: This only checks checkmate and stalemate. For full draw detection
-    /// including insufficient material, use Orchestrator's check_for_game_end.
+    /// Covers every ending the rules apply without a claim: checkmate,
+    /// stalemate, insufficient material, threefold repetition and the
+    /// seventy-five-move rule. A claimed fifty-move draw is not visible on
+    /// the board.
     ///
     /// # Returns
     ///
-    /// * `Some(GameResult)` - The result if checkmate or stalemate
+    /// * `Some(GameResult)` - The result if the game has ended
     /// * `None` - If the game is still in progress
     pub fn get_game_result(&self) -> Option<GameResult> {
         let turn = self.current_turn();
@@ -468,11 +541,121 @@ impl Board {
             })
         } else if self.is_stalemate(turn) {
             Some(GameResult::Stalemate)
+        } else if self.is_insufficient_material() {
+            Some(GameResult::Draw)
+        } else if self.is_threefold_repetition() {
+            Some(GameResult::ThreefoldRepetition)
+        } else if self.position.is_seventy_five_move_draw() {
+            Some(GameResult::SeventyFiveMoveRule)
         } else {
             None
         }
     }
 
+    /// Check if the current position has insufficient material for checkmate.
+    ///
+    /// This detects draw by insufficient material according to chess rules.
+    /// A position is drawn if neither side can possibly deliver checkmate.
+    ///
+    /// # Insufficient Material Cases
+    ///
+    /// - King vs King (only two kings on the board)
+    /// - King and Bishop vs King
+    /// - King and Knight vs King
+    /// - King and Bishop vs King and Bishop (same colored bishops)
+    ///
+    /// # Returns
+    ///
+    /// `true` if the position has insufficient material for checkmate
+    ///
+    /// # Note
+    ///
+    /// This lives on the board (not in game_repr) because it's a game rule
+    /// interpretation, not a move legality check.
+    pub fn is_insufficient_material(&self) -> bool {
+        use crate::game_repr::{Type, Color};
+
+        let mut white_pieces = Vec::new();
+        let mut black_pieces = Vec::new();
+
+        // Count all pieces on the board
+        for idx in 0..64 {
+            let piece = self.piece_at(idx);
+            if piece.piece_type != Type::None {
+                match piece.color {
+                    Color::White => white_pieces.push(piece.piece_type),
+                    Color::Black => black_pieces.push(piece.piece_type),
+                }
+            }
+        }
+
+        // Helper to count specific piece types
+        let count_type = |pieces: &[Type], piece_type: Type| -> usize {
+            pieces.iter().filter(|&&p| p == piece_type).count()
+        };
+
+        // King vs King (only two kings)
+        if white_pieces.len() == 1 && black_pieces.len() == 1 {
+            return true;
+        }
+
+        // King and Bishop vs King
+        if white_pieces.len() == 2 && black_pieces.len() == 1
+            && count_type(&white_pieces, Type::Bishop) == 1 {
+                return true;
+            }
+        if white_pieces.len() == 1 && black_pieces.len() == 2
+            && count_type(&black_pieces, Type::Bishop) == 1 {
+                return true;
+            }
+
+        // King and Knight vs King
+        if white_pieces.len() == 2 && black_pieces.len() == 1
+            && count_type(&white_pieces, Type::Knight) == 1 {
+                return true;
+            }
+        if white_pieces.len() == 1 && black_pieces.len() == 2
+            && count_type(&black_pieces, Type::Knight) == 1 {
+                return true;
+            }
+
+        // King and Bishop vs King and Bishop (same colored bishops)
+        if white_pieces.len() == 2 && black_pieces.len() == 2 {
+            let white_has_bishop = count_type(&white_pieces, Type::Bishop) == 1;
+            let black_has_bishop = count_type(&black_pieces, Type::Bishop) == 1;
+
+            if white_has_bishop && black_has_bishop {
+                // Find bishop squares to check if they're on same color
+                let mut white_bishop_square = None;
+                let mut black_bishop_square = None;
+
+                for idx in 0..64 {
+                    let piece = self.piece_at(idx);
+                    if piece.piece_type == Type::Bishop {
+                        if piece.color == Color::White {
+                            white_bishop_square = Some(idx);
+                        } else {
+                            black_bishop_square = Some(idx);
+                        }
+                    }
+                }
+
+                // Same color squares = insufficient material
+                // Square color: (rank + file) % 2
+                // idx / 8 = rank, idx % 8 = file
+                if let (Some(w_sq), Some(b_sq)) = (white_bishop_square, black_bishop_square) {
+                    let w_color = (w_sq / 8 + w_sq % 8) % 2;
+                    let b_color = (b_sq / 8 + b_sq % 8) % 2;
+                    if w_color == b_color {
+                        return true;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
     /// Check if the given color is in checkmate.
     ///
     /// # Arguments
@@ -518,21 +701,40 @@ impl Board {
     ///
     /// * `fen` - FEN string for the position, or empty string for default starting position
     ///
+    /// # Errors
+    ///
+    /// Returns a [`FenError`] if the FEN is malformed or illegal; the board is
+    /// left untouched in that case.
+    ///
     /// # Side Effects
     ///
     /// - Clears the selected tile
     /// - Clears the legal moves cache
     /// - Resets the position
-    pub fn reset_position(&mut self, fen: &str) {
-        self.position = if fen.is_empty() {
+    pub fn reset_position(&mut self, fen: &str) -> Result<(), FenError> {
+        let position = if fen.is_empty() {
             Position::default()
         } else {
-            Position::from_fen(fen)
+            Position::try_from_fen(fen)?
         };
+        self.set_start_position(position);
+        Ok(())
+    }
+
+    /// Reset the board to start a new game from `position`.
+    ///
+    /// Unlike [`reset_position`](Self::reset_position) this keeps every
+    /// property of the position, such as the Chess960 flag a FEN cannot
+    /// always convey.
+    pub fn set_start_position(&mut self, position: Position) {
+        self.position = position;
+        self.start_position = self.position.clone();
         self.selected_tile = None;
         self.legal_moves_cache.clear();
         self.move_history.clear();
         self.history_index = 0;
+        self.position_keys.clear();
+        self.push_position_key();
     }
 
     // ===========================
@@ -957,7 +1159,7 @@ mod tests {
             false
         }
 
-        fn draw_menu_state(&mut self, _state: &crate::menu::MenuState) {
+        fn draw_menu_state(&mut self, _state: &crate::menu::MenuState, _chess960: bool) {
             // No-op for tests
         }
 
@@ -987,7 +1189,7 @@ mod tests {
         let board = Board::from_fen(
             "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR",
             Box::new(MockRenderer)
-        );
+        ).unwrap();
 
         // Verify starting position
         assert_eq!(board.piece_at(0).piece_type, Type::Rook);
@@ -995,6 +1197,37 @@ mod tests {
         assert_eq!(board.piece_at(56).color, Color::Black);
     }
 
+    #[test]
+    fn test_board_from_fen_rejects_invalid_fen() {
+        assert!(Board::from_fen("not a fen", Box::new(MockRenderer)).is_err());
+        assert!(Board::from_fen("8/8/8/8/8/8/8/8 w - - 0 1", Box::new(MockRenderer)).is_err());
+    }
+
+    #[test]
+    fn test_reset_position_keeps_board_on_invalid_fen() {
+        let mut board = Board::new(Box::new(MockRenderer));
+        assert!(board.reset_position("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 extra").is_err());
+        assert_eq!(board.position().to_fen(), Position::default().to_fen());
+    }
+
+    #[test]
+    fn test_set_start_position_keeps_chess960_flag() {
+        // Chess960 setup 518 is the standard one, which a FEN reads as standard chess
+        let mut board = Board::new(Box::new(MockRenderer));
+        board.set_start_position(Position::chess960(518).unwrap());
+        assert!(board.position().chess960);
+        assert!(board.to_pgn_game().to_pgn().contains("[Variant \"Chess960\"]"));
+    }
+
+    #[test]
+    fn test_current_turn_follows_fen() {
+        let board = Board::from_fen(
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            Box::new(MockRenderer)
+        ).unwrap();
+        assert_eq!(board.current_turn(), Color::Black);
+    }
+
     #[test]
     fn test_piece_at() {
         let board = Board::new(Box::new(MockRenderer));
@@ -1023,7 +1256,7 @@ mod tests {
 
         // Should have cached legal moves for the pawn
         let moves = board.legal_moves_for_selection();
-        assert!(moves.len() > 0);
+        assert!(!moves.is_empty());
 
         // Clear selection
         board.set_selected_tile(None);
@@ -1048,7 +1281,7 @@ mod tests {
 
         // Select pawn at e2 (index 12)
         board.set_selected_tile(Some(12));
-        assert!(board.legal_moves_for_selection().len() > 0);
+        assert!(!board.legal_moves_for_selection().is_empty());
 
         // Create move e2-e4
         let mv = Move::new(12, 28, MoveType::Normal);
@@ -1065,6 +1298,54 @@ mod tests {
         assert_eq!(board.legal_moves_for_selection().len(), 0);
     }
 
+    #[test]
+    fn test_to_pgn_game() {
+        let mut board = Board::from_fen(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            Box::new(MockRenderer)
+        ).unwrap();
+
+        // Fool's mate; the undone move is not part of the exported line
+        for san in ["f3", "e5", "g4", "Qh4"] {
+            let mv = board.position().parse_san(san).unwrap();
+            board.execute_move_undoable(mv);
+        }
+        assert_eq!(board.moves().len(), 4);
+        assert_eq!(board.start_position().to_fen(), Position::default().to_fen());
+
+        let game = board.to_pgn_game();
+        assert_eq!(game.result, crate::pgn::PgnResult::BlackWins);
+        assert!(game.to_pgn().ends_with("\n1. f3 e5 2. g4 Qh4# 0-1\n"));
+
+        board.undo_move();
+        let game = board.to_pgn_game();
+        assert_eq!(game.moves.len(), 3);
+        assert_eq!(game.result, crate::pgn::PgnResult::Unknown);
+    }
+
+    #[test]
+    fn test_to_pgn_game_draws() {
+        // Threefold repetition by shuffling the knights
+        let mut board = Board::new(Box::new(MockRenderer));
+        for san in ["Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1", "Ng8"] {
+            let mv = board.position().parse_san(san).unwrap();
+            board.execute_move_undoable(mv);
+        }
+        assert_eq!(board.get_game_result(), Some(GameResult::ThreefoldRepetition));
+        assert_eq!(board.to_pgn_game().result, crate::pgn::PgnResult::Draw);
+
+        // Bare kings after the last capture
+        let mut board = Board::from_fen("8/8/8/8/3p4/4K3/8/7k w - - 0 1", Box::new(MockRenderer)).unwrap();
+        board.execute_move_undoable(board.position().parse_san("Kxd4").unwrap());
+        assert!(board.is_insufficient_material());
+        assert_eq!(board.to_pgn_game().to_pgn().lines().last(), Some("1. Kxd4 1/2-1/2"));
+
+        // Seventy-five moves without a capture or pawn move
+        let board = Board::from_fen("8/8/8/3k4/8/4K3/8/R7 w - - 150 100", Box::new(MockRenderer)).unwrap();
+        assert_eq!(board.get_game_result(), Some(GameResult::SeventyFiveMoveRule));
+        assert_eq!(board.to_pgn_game().result, crate::pgn::PgnResult::Draw);
+    }
+
     #[test]
     fn test_pov() {
         let mut board = Board::new(Box::new(MockRenderer));
@@ -1104,6 +1385,83 @@ mod tests {
         assert!(!board.is_stalemate(Color::Black));
     }
 
+    #[test]
+    fn test_threefold_repetition() {
+        let mut board = Board::new(Box::new(MockRenderer));
+        // Ng1-f3, Ng8-f6, Nf3-g1, Nf6-g8 returns to the starting position
+        let shuffle = [
+            Move::new(6, 21, MoveType::Normal),
+            Move::new(62, 45, MoveType::Normal),
+            Move::new(21, 6, MoveType::Normal),
+            Move::new(45, 62, MoveType::Normal),
+        ];
+
+        for mv in shuffle {
+            assert!(board.execute_move_undoable(mv));
+        }
+        assert!(!board.is_threefold_repetition(), "Start position has only occurred twice");
+
+        for mv in shuffle {
+            assert!(board.execute_move_undoable(mv));
+        }
+        assert!(board.is_threefold_repetition());
+
+        // Stepping back out of the repeated position clears the claim
+        board.undo_move();
+        assert!(!board.is_threefold_repetition());
+        board.redo_move();
+        assert!(board.is_threefold_repetition());
+    }
+
+    #[test]
+    fn test_repetition_after_rook_moves_with_king_moved() {
+        // Once the king has moved, a rook going h1-g1-h1 repeats the position
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1", Box::new(MockRenderer)).unwrap();
+        let king_walk = [
+            Move::new(4, 5, MoveType::Normal),
+            Move::new(60, 59, MoveType::Normal),
+            Move::new(5, 4, MoveType::Normal),
+            Move::new(59, 60, MoveType::Normal),
+        ];
+        let rook_walk = [
+            Move::new(7, 6, MoveType::Normal),
+            Move::new(60, 59, MoveType::Normal),
+            Move::new(6, 7, MoveType::Normal),
+            Move::new(59, 60, MoveType::Normal),
+        ];
+
+        for mv in king_walk.into_iter().chain(rook_walk) {
+            board.execute_move(mv);
+        }
+        assert!(!board.is_threefold_repetition());
+        for mv in king_walk {
+            board.execute_move(mv);
+        }
+        assert!(board.is_threefold_repetition());
+    }
+
+    #[test]
+    fn test_repetition_resets_after_irreversible_move() {
+        let mut board = Board::new(Box::new(MockRenderer));
+        let shuffle = [
+            Move::new(6, 21, MoveType::Normal),
+            Move::new(62, 45, MoveType::Normal),
+            Move::new(21, 6, MoveType::Normal),
+            Move::new(45, 62, MoveType::Normal),
+        ];
+
+        for mv in shuffle {
+            board.execute_move(mv);
+        }
+        // A pawn move makes every earlier position unreachable
+        board.execute_move(Move::new(12, 28, MoveType::Normal));
+        board.execute_move(Move::new(52, 36, MoveType::Normal));
+        for mv in shuffle {
+            board.execute_move(mv);
+        }
+        assert!(!board.is_threefold_repetition());
+    }
+
     #[test]
     fn test_handle_click() {
         let mut board = Board::new(Box::new(MockRenderer));
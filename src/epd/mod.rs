@@ -0,0 +1,343 @@
+//! EPD (Extended Position Description) parsing.
+//!
+//! An EPD record is the first four FEN fields followed by operations, each an
+//! opcode with operands and a terminating semicolon:
+//!
+//! ```text
+//! 2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001";
+//! rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - ;D1 20 ;D2 400
+//! ```
+//!
+//! The opcodes used by test suites are decoded into [`Epd`] fields (`bm`,
+//! `am`, `id`, `c0`, `acd`, `ce` and the perft-suite `D1`, `D2`, ...), with
+//! moves resolved from SAN. Every operation is also kept verbatim. The
+//! [`runner`] module searches or perfts a parsed suite.
+
+pub mod runner;
+
+use crate::game_repr::{FenError, Move, Position, SanError};
+use std::fmt;
+
+/// What went wrong while parsing an EPD record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpdErrorKind {
+    /// Fewer than the four position fields
+    MissingFields,
+    /// The position fields do not describe a valid position
+    InvalidFen(FenError),
+    /// A quoted operand that is never closed
+    UnterminatedString,
+    /// A move operand of `bm` or `am` that is not a legal move
+    InvalidMove { opcode: String, san: String, error: SanError },
+    /// A numeric operand that is missing or not a number
+    InvalidNumber { opcode: String },
+}
+
+impl fmt::Display for EpdErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EpdErrorKind::MissingFields => write!(f, "expected four position fields"),
+            EpdErrorKind::InvalidFen(err) => write!(f, "{}", err),
+            EpdErrorKind::UnterminatedString => write!(f, "string operand is never closed"),
+            EpdErrorKind::InvalidMove { opcode, san, error } => {
+                write!(f, "{} move '{}': {}", opcode, san, error)
+            }
+            EpdErrorKind::InvalidNumber { opcode } => write!(f, "{} expects a number", opcode),
+        }
+    }
+}
+
+/// Error returned by [`Epd::parse`] and [`parse_epd_file`]
+///
+/// `line` is 1-based; it is 1 for records parsed on their own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpdError {
+    pub line: usize,
+    pub kind: EpdErrorKind,
+}
+
+impl fmt::Display for EpdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid EPD on line {}: {}", self.line, self.kind)
+    }
+}
+
+impl std::error::Error for EpdError {}
+
+/// A parsed EPD record
+#[derive(Clone)]
+pub struct Epd {
+    /// The position, with move counters from `hmvc`/`fmvn` if given
+    pub position: Position,
+    /// Every operation in record order, with quotes removed from operands
+    pub operations: Vec<(String, Vec<String>)>,
+    /// `bm`: moves that solve the position
+    pub best_moves: Vec<Move>,
+    /// `am`: moves that fail the position
+    pub avoid_moves: Vec<Move>,
+    /// `id`: position identifier
+    pub id: Option<String>,
+    /// `c0`: primary comment
+    pub comment: Option<String>,
+    /// `acd`: analysis depth in plies
+    pub analysis_depth: Option<u32>,
+    /// `ce`: evaluation in centipawns from the side to move's point of view
+    pub centipawns: Option<i32>,
+    /// `D1`, `D2`, ...: expected perft node counts as `(depth, nodes)`
+    pub perft: Vec<(u32, u64)>,
+}
+
+impl Epd {
+    /// Parses a single EPD record
+    pub fn parse(record: &str) -> Result<Epd, EpdError> {
+        parse_record(record).map_err(|kind| EpdError { line: 1, kind })
+    }
+
+    /// Operands of the first operation with this opcode
+    pub fn operation(&self, opcode: &str) -> Option<&[String]> {
+        self.operations
+            .iter()
+            .find(|(op, _)| op == opcode)
+            .map(|(_, operands)| operands.as_slice())
+    }
+}
+
+/// Parses every record of an EPD file, skipping blank lines and `#` comments
+pub fn parse_epd_file(text: &str) -> Result<Vec<Epd>, EpdError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(index, line)| {
+            parse_record(line).map_err(|kind| EpdError { line: index + 1, kind })
+        })
+        .collect()
+}
+
+fn parse_record(record: &str) -> Result<Epd, EpdErrorKind> {
+    let (fields, mut rest) = split_fields(record, 4).ok_or(EpdErrorKind::MissingFields)?;
+
+    // Some files carry full FENs; otherwise the move counters may come from
+    // the hmvc and fmvn opcodes
+    let mut counters = None;
+    if let Some((numbers, after)) = split_fields(rest, 2) {
+        if numbers.split_whitespace().all(|n| n.bytes().all(|b| b.is_ascii_digit())) {
+            counters = Some(numbers.trim().to_string());
+            rest = after;
+        }
+    }
+
+    let operations = parse_operations(rest)?;
+    let counters = counters.unwrap_or_else(|| {
+        let operand = |opcode: &str, default: &str| {
+            operations
+                .iter()
+                .find(|(op, _)| op == opcode)
+                .and_then(|(_, operands)| operands.first().cloned())
+                .unwrap_or_else(|| default.to_string())
+        };
+        format!("{} {}", operand("hmvc", "0"), operand("fmvn", "1"))
+    });
+
+    let fen = format!("{} {}", fields.trim(), counters);
+    let position = Position::try_from_fen(&fen).map_err(EpdErrorKind::InvalidFen)?;
+
+    let mut epd = Epd {
+        position,
+        operations: Vec::new(),
+        best_moves: Vec::new(),
+        avoid_moves: Vec::new(),
+        id: None,
+        comment: None,
+        analysis_depth: None,
+        centipawns: None,
+        perft: Vec::new(),
+    };
+
+    for (opcode, operands) in &operations {
+        match opcode.as_str() {
+            "bm" => epd.best_moves = resolve_moves(&epd.position, opcode, operands)?,
+            "am" => epd.avoid_moves = resolve_moves(&epd.position, opcode, operands)?,
+            "id" => epd.id = operands.first().cloned(),
+            "c0" => epd.comment = operands.first().cloned(),
+            "acd" => epd.analysis_depth = Some(parse_number(opcode, operands)?),
+            "ce" => epd.centipawns = Some(parse_number(opcode, operands)?),
+            _ => {
+                if let Some(depth) = opcode.strip_prefix('D').and_then(|d| d.parse().ok()) {
+                    epd.perft.push((depth, parse_number(opcode, operands)?));
+                }
+            }
+        }
+    }
+
+    epd.operations = operations;
+    Ok(epd)
+}
+
+/// Splits off the first `count` whitespace-separated fields
+fn split_fields(text: &str, count: usize) -> Option<(&str, &str)> {
+    let mut end = 0;
+    for _ in 0..count {
+        let start = end + text[end..].find(|c: char| !c.is_whitespace())?;
+        end = start + text[start..].find(char::is_whitespace).unwrap_or(text.len() - start);
+    }
+    Some((&text[..end], &text[end..]))
+}
+
+/// Splits the operations part into `(opcode, operands)`, honouring quoted
+/// operands that contain spaces or semicolons
+fn parse_operations(text: &str) -> Result<Vec<(String, Vec<String>)>, EpdErrorKind> {
+    let mut operations = Vec::new();
+    let mut tokens: Vec<String> = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    let mut finish = |tokens: &mut Vec<String>| {
+        if !tokens.is_empty() {
+            let opcode = tokens.remove(0);
+            operations.push((opcode, std::mem::take(tokens)));
+        }
+    };
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            ';' => {
+                chars.next();
+                finish(&mut tokens);
+            }
+            '"' => {
+                chars.next();
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => string.push(c),
+                        None => return Err(EpdErrorKind::UnterminatedString),
+                    }
+                }
+                tokens.push(string);
+            }
+            _ if c.is_whitespace() => {
+                chars.next();
+            }
+            _ => {
+                let mut token = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == ';' || c == '"' {
+                        break;
+                    }
+                    token.push(c);
+                    chars.next();
+                }
+                tokens.push(token);
+            }
+        }
+    }
+    // The last operation may lack its semicolon
+    finish(&mut tokens);
+
+    Ok(operations)
+}
+
+fn resolve_moves(pos: &Position, opcode: &str, operands: &[String]) -> Result<Vec<Move>, EpdErrorKind> {
+    operands
+        .iter()
+        .map(|san| {
+            pos.parse_san(san).map_err(|error| EpdErrorKind::InvalidMove {
+                opcode: opcode.to_string(),
+                san: san.clone(),
+                error,
+            })
+        })
+        .collect()
+}
+
+fn parse_number<T: std::str::FromStr>(opcode: &str, operands: &[String]) -> Result<T, EpdErrorKind> {
+    operands
+        .first()
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| EpdErrorKind::InvalidNumber { opcode: opcode.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_repr::{Color, MoveType};
+
+    const WAC_001: &str =
+        r#"2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001";"#;
+
+    #[test]
+    fn test_parse_test_suite_record() {
+        let epd = Epd::parse(WAC_001).unwrap();
+
+        assert_eq!(epd.position.to_fen(), "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - 0 1");
+        assert_eq!(epd.best_moves, vec![Move::new(22, 46, MoveType::Normal)]);
+        assert_eq!(epd.id.as_deref(), Some("WAC.001"));
+        assert_eq!(epd.operation("bm"), Some(&["Qg6".to_string()][..]));
+        assert!(epd.avoid_moves.is_empty());
+    }
+
+    #[test]
+    fn test_parse_all_decoded_opcodes() {
+        let epd = Epd::parse(
+            r#"4k3/8/8/8/8/8/4P3/4K3 b - - am Kd7 Kf7; bm Ke7 Kd8; c0 "quiet; keep opposition"; acd 12; ce -35; id "custom";"#,
+        )
+        .unwrap();
+
+        assert_eq!(epd.position.side_to_move, Color::Black);
+        assert_eq!(epd.avoid_moves.len(), 2);
+        assert_eq!(epd.best_moves.len(), 2);
+        assert_eq!(epd.comment.as_deref(), Some("quiet; keep opposition"));
+        assert_eq!(epd.analysis_depth, Some(12));
+        assert_eq!(epd.centipawns, Some(-35));
+        assert_eq!(epd.operations.len(), 6);
+    }
+
+    #[test]
+    fn test_parse_perft_record() {
+        let epd = Epd::parse("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - ;D1 20 ;D2 400 ;D3 8902").unwrap();
+        assert_eq!(epd.perft, vec![(1, 20), (2, 400), (3, 8902)]);
+
+        // Full FEN counters before the operations, last semicolon missing
+        let epd = Epd::parse("4k3/8/8/8/8/8/8/4K2R w K - 3 40 ;D1 15 ;D2 66").unwrap();
+        assert_eq!(epd.position.halfmove_clock, 3);
+        assert_eq!(epd.position.fullmove_number, 40);
+        assert_eq!(epd.perft, vec![(1, 15), (2, 66)]);
+    }
+
+    #[test]
+    fn test_move_counter_opcodes() {
+        let epd = Epd::parse("4k3/8/8/8/8/8/8/4K3 w - - hmvc 12; fmvn 30;").unwrap();
+        assert_eq!(epd.position.halfmove_clock, 12);
+        assert_eq!(epd.position.fullmove_number, 30);
+    }
+
+    #[test]
+    fn test_parse_file_skips_blank_and_comment_lines() {
+        let text = format!("# WAC sample\n\n{}\n{}\n", WAC_001, "8/8/8/8/8/8/8/8 w - - bm Kd2;");
+        let err = parse_epd_file(&text).err().unwrap();
+        assert_eq!(err.line, 4);
+        assert!(matches!(err.kind, EpdErrorKind::InvalidFen(_)));
+
+        assert_eq!(parse_epd_file(&format!("# WAC sample\n\n{}\n", WAC_001)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let kind = |record: &str| Epd::parse(record).err().unwrap().kind;
+
+        assert_eq!(kind("4k3/8/8/8/8/8/8/4K3 w -"), EpdErrorKind::MissingFields);
+        assert_eq!(kind(r#"4k3/8/8/8/8/8/8/4K3 w - - id "open"#), EpdErrorKind::UnterminatedString);
+        assert_eq!(
+            kind("4k3/8/8/8/8/8/8/4K3 w - - acd deep;"),
+            EpdErrorKind::InvalidNumber { opcode: "acd".to_string() }
+        );
+        assert_eq!(
+            kind("4k3/8/8/8/8/8/8/4K3 w - - bm Ke3;"),
+            EpdErrorKind::InvalidMove {
+                opcode: "bm".to_string(),
+                san: "Ke3".to_string(),
+                error: SanError::IllegalMove,
+            }
+        );
+    }
+}
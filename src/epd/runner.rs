@@ -0,0 +1,241 @@
+//! Test-suite runner for parsed EPD records.
+//!
+//! [`run_test_suite`] searches every position that has a `bm` or `am`
+//! operation with [`iterative_deepening_search`] and checks the move it
+//! picks. [`run_perft_suite`] compares [`Position::perft`] against the `D<n>`
+//! node counts of perft-suite records.
+
+use super::Epd;
+use crate::agent::ai::iterative_deepening_search;
+use crate::game_repr::{Move, Position};
+use std::fmt;
+
+/// Outcome of searching one test-suite position
+#[derive(Debug, Clone)]
+pub struct PositionResult {
+    /// The record's `id`, or its position number in the suite
+    pub id: String,
+    /// Move the search picked, in SAN (`None` if it found no move)
+    pub found: Option<String>,
+    /// The `bm` and `am` moves in SAN, for reporting
+    pub best_moves: Vec<String>,
+    pub avoid_moves: Vec<String>,
+    pub passed: bool,
+    pub depth: u8,
+    pub nodes: u64,
+    pub time_ms: u64,
+}
+
+/// Per-position results and overall solve rate of a test suite
+#[derive(Debug, Clone, Default)]
+pub struct SuiteReport {
+    pub results: Vec<PositionResult>,
+}
+
+impl SuiteReport {
+    pub fn solved(&self) -> usize {
+        self.results.iter().filter(|result| result.passed).count()
+    }
+
+    /// Fraction of positions solved, between 0 and 1
+    pub fn solve_rate(&self) -> f64 {
+        if self.results.is_empty() {
+            0.0
+        } else {
+            self.solved() as f64 / self.results.len() as f64
+        }
+    }
+}
+
+impl fmt::Display for SuiteReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in &self.results {
+            let mut expected = Vec::new();
+            if !result.best_moves.is_empty() {
+                expected.push(format!("bm {}", result.best_moves.join(" ")));
+            }
+            if !result.avoid_moves.is_empty() {
+                expected.push(format!("am {}", result.avoid_moves.join(" ")));
+            }
+            writeln!(
+                f,
+                "{} {}: found {} ({}), depth {}, {} nodes, {} ms",
+                if result.passed { "PASS" } else { "FAIL" },
+                result.id,
+                result.found.as_deref().unwrap_or("none"),
+                expected.join("; "),
+                result.depth,
+                result.nodes,
+                result.time_ms,
+            )?;
+        }
+        write!(
+            f,
+            "Solved {}/{} ({:.1}%)",
+            self.solved(),
+            self.results.len(),
+            self.solve_rate() * 100.0
+        )
+    }
+}
+
+/// Searches every position with a `bm` or `am` operation
+///
+/// A position passes when the chosen move is one of its `bm` moves (if any)
+/// and none of its `am` moves. Records without either are skipped.
+pub fn run_test_suite(suite: &[Epd], max_depth: u8, time_limit_ms: Option<u64>) -> SuiteReport {
+    let mut report = SuiteReport::default();
+
+    for (index, epd) in suite.iter().enumerate() {
+        if epd.best_moves.is_empty() && epd.avoid_moves.is_empty() {
+            continue;
+        }
+
+        let pos = &epd.position;
+        let search = iterative_deepening_search(pos, pos.side_to_move, max_depth, time_limit_ms, &mut |_| {});
+        let passed = search.best_move.is_some_and(|mv| {
+            (epd.best_moves.is_empty() || epd.best_moves.contains(&mv))
+                && !epd.avoid_moves.contains(&mv)
+        });
+
+        report.results.push(PositionResult {
+            id: epd.id.clone().unwrap_or_else(|| format!("#{}", index + 1)),
+            found: search.best_move.map(|mv| pos.move_to_san(mv)),
+            best_moves: to_san(pos, &epd.best_moves),
+            avoid_moves: to_san(pos, &epd.avoid_moves),
+            passed,
+            depth: search.depth,
+            nodes: search.nodes_searched,
+            time_ms: search.time_ms,
+        });
+    }
+
+    report
+}
+
+fn to_san(pos: &Position, moves: &[Move]) -> Vec<String> {
+    moves.iter().map(|&mv| pos.move_to_san(mv)).collect()
+}
+
+/// Outcome of checking one perft-suite position
+#[derive(Debug, Clone)]
+pub struct PerftResult {
+    pub id: String,
+    pub fen: String,
+    /// `(depth, expected, actual)` for every depth checked
+    pub counts: Vec<(u32, u64, u64)>,
+}
+
+impl PerftResult {
+    pub fn passed(&self) -> bool {
+        self.counts.iter().all(|&(_, expected, actual)| expected == actual)
+    }
+}
+
+/// Per-position results of a perft suite
+#[derive(Debug, Clone, Default)]
+pub struct PerftReport {
+    pub results: Vec<PerftResult>,
+}
+
+impl PerftReport {
+    pub fn passed(&self) -> usize {
+        self.results.iter().filter(|result| result.passed()).count()
+    }
+}
+
+impl fmt::Display for PerftReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in &self.results {
+            writeln!(f, "{} {}: {}", if result.passed() { "PASS" } else { "FAIL" }, result.id, result.fen)?;
+            for &(depth, expected, actual) in &result.counts {
+                if expected != actual {
+                    writeln!(f, "    D{}: expected {}, got {}", depth, expected, actual)?;
+                }
+            }
+        }
+        write!(f, "Passed {}/{}", self.passed(), self.results.len())
+    }
+}
+
+/// Checks the `D<n>` node counts of every record, skipping depths above
+/// `max_depth` (deep perft counts take a long time)
+pub fn run_perft_suite(suite: &[Epd], max_depth: Option<u32>) -> PerftReport {
+    let mut report = PerftReport::default();
+
+    for (index, epd) in suite.iter().enumerate() {
+        let counts: Vec<(u32, u64, u64)> = epd
+            .perft
+            .iter()
+            .filter(|&&(depth, _)| max_depth.is_none_or(|max| depth <= max))
+            .map(|&(depth, expected)| (depth, expected, epd.position.perft(depth)))
+            .collect();
+
+        if counts.is_empty() {
+            continue;
+        }
+
+        report.results.push(PerftResult {
+            id: epd.id.clone().unwrap_or_else(|| format!("#{}", index + 1)),
+            fen: epd.position.to_fen(),
+            counts,
+        });
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::epd::parse_epd_file;
+
+    #[test]
+    fn test_run_test_suite() {
+        let suite = parse_epd_file(concat!(
+            "6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - bm Ra8#; id \"back rank\";\n",
+            "4k3/8/4p3/3p4/8/8/3Q4/4K3 w - - am Qxd5; id \"defended pawn\";\n",
+            "6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - bm Ra7;\n",
+            "4k3/8/8/8/8/8/8/4K2R w K - ;D1 15\n",
+        ))
+        .unwrap();
+
+        let report = run_test_suite(&suite, 3, None);
+
+        // The perft-only record is not searched
+        assert_eq!(report.results.len(), 3);
+        assert!(report.results[0].passed);
+        assert_eq!(report.results[0].found.as_deref(), Some("Ra8#"));
+        assert!(report.results[1].passed);
+        assert!(!report.results[2].passed);
+        assert_eq!(report.results[2].id, "#3");
+        assert_eq!(report.solved(), 2);
+
+        let text = report.to_string();
+        assert!(text.contains("PASS back rank: found Ra8# (bm Ra8#)"));
+        assert!(text.contains("FAIL #3: found Ra8# (bm Ra7)"));
+        assert!(text.ends_with("Solved 2/3 (66.7%)"));
+    }
+
+    #[test]
+    fn test_run_perft_suite() {
+        let suite = parse_epd_file(concat!(
+            "4k3/8/8/8/8/8/8/4K2R w K - ;D1 15 ;D2 66 ;D3 1197\n",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - ;D1 20 ;D2 401\n",
+            "6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - bm Ra8#;\n",
+        ))
+        .unwrap();
+
+        let report = run_perft_suite(&suite, None);
+        assert_eq!(report.results.len(), 2);
+        assert!(report.results[0].passed());
+        assert!(!report.results[1].passed());
+        assert_eq!(report.results[1].counts[1], (2, 401, 400));
+        assert!(report.to_string().contains("    D2: expected 401, got 400\n"));
+
+        // Depths above the limit are not run
+        let report = run_perft_suite(&suite, Some(1));
+        assert_eq!(report.passed(), 2);
+        assert_eq!(report.results[0].counts.len(), 1);
+    }
+}
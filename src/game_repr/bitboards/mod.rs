@@ -64,7 +64,7 @@ impl Bitboards {
     }
 
     /// Convert bitboards to 64-element piece array
-    pub fn to_array(&self) -> [Piece; 64] {
+    pub fn to_array(self) -> [Piece; 64] {
         let mut pieces = [Piece::default(); 64];
 
         for color in [Color::White, Color::Black] {
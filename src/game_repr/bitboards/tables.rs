@@ -1,3 +1,5 @@
+use std::sync::LazyLock;
+
 
 /// Precomputed knight attack tables
 /// KNIGHT_ATTACKS[square] returns a bitboard of all squares a knight can attack from that square
@@ -230,6 +232,236 @@ const fn generate_rays() -> [[u64; 64]; 8] {
     rays
 }
 
+/// Squares strictly between two squares on a shared rank, file or diagonal
+/// BETWEEN[a][b] is empty if the squares are not aligned or are adjacent
+pub static BETWEEN: [[u64; 64]; 64] = generate_between();
+
+/// Generate the between table at compile time from the ray tables
+const fn generate_between() -> [[u64; 64]; 64] {
+    let rays = generate_rays();
+    let mut between = [[0u64; 64]; 64];
+    let mut from = 0;
+
+    while from < 64 {
+        let mut direction = 0;
+        while direction < 8 {
+            let ray = rays[direction][from];
+            let mut to = 0;
+            while to < 64 {
+                if ray & (1u64 << to) != 0 {
+                    // Squares on the ray before `to` are those not on the ray beyond it
+                    between[from][to] = ray & !rays[direction][to] & !(1u64 << to);
+                }
+                to += 1;
+            }
+            direction += 1;
+        }
+        from += 1;
+    }
+
+    between
+}
+
+// ==================== MAGIC BITBOARDS ====================
+//
+// Slider attacks are looked up by multiplying the relevant blockers by a
+// per-square magic number; the top bits of the product index a table of
+// precomputed attack sets. Edge squares are left out of the relevance masks
+// because a blocker there never changes the attack set.
+
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+const FILE_H: u64 = 0x8080_8080_8080_8080;
+const RANK_1: u64 = 0x0000_0000_0000_00FF;
+const RANK_8: u64 = 0xFF00_0000_0000_0000;
+
+/// Relevant occupancy masks for rooks (edges excluded along each ray)
+pub static ROOK_MASKS: [u64; 64] = generate_rook_masks();
+
+/// Relevant occupancy masks for bishops (board edges excluded)
+pub static BISHOP_MASKS: [u64; 64] = generate_bishop_masks();
+
+/// Rook magic numbers, one per square (index bits = popcount of the mask)
+const ROOK_MAGICS: [u64; 64] = [
+    0x1080004008801020, 0x0840092002C03000, 0x1900200010400900, 0x0880100008000480,
+    0x4200100420080200, 0x8100020100080400, 0x0200040110886200, 0x0200008040220411,
+    0x0404800084400220, 0x0000401000402000, 0x0086001081220440, 0x0408800800100280,
+    0x000A001201040820, 0x8848800200840080, 0x4001000100040200, 0x0442000102105084,
+    0x9080010020804100, 0x0040404000201009, 0x0000808010002009, 0x2200090021D00100,
+    0x0008008008040080, 0x0004004002010040, 0x0011040008015042, 0x00000A0001768104,
+    0x0000800080204009, 0x2010004140002001, 0x9800200280100080, 0x1000100080080080,
+    0x0442000A00049020, 0x2100040080020080, 0x0800120400900148, 0x0010040A00128541,
+    0x2800804000800030, 0x1010002000400041, 0x4000200011004100, 0x0610008410800800,
+    0x0400802402800800, 0xC100020080800400, 0x0002000802000401, 0x0182085882000401,
+    0x0220204000808000, 0x2860100040024022, 0x0001002004110040, 0x99101042000A0020,
+    0x0004080004008080, 0x0010040002008080, 0x2012004881020004, 0x8300842444820011,
+    0x0088403882010200, 0x0820400080210100, 0x0110910040A00300, 0x0801100280080480,
+    0x0242009008200600, 0x1002000489500200, 0x0040800200010080, 0x0091800041000080,
+    0x0000209300488001, 0x04C1002414824001, 0x020020000B001041, 0x7000100004200901,
+    0x8002002004100802, 0x30010002084C0007, 0x0888221800813004, 0x4000002840840112,
+];
+
+/// Bishop magic numbers, one per square (index bits = popcount of the mask)
+const BISHOP_MAGICS: [u64; 64] = [
+    0xA010041108003100, 0x006082020A002900, 0x6810010619200000, 0x08281A0520000408,
+    0x0001104001000400, 0x0018901008048400, 0x00040A0210245280, 0x000200210808A402,
+    0x9140048410821200, 0x0800091010820041, 0x20504804832202C0, 0x0100091401081000,
+    0x8021011140000012, 0x0810020804450400, 0x208B0542109008A2, 0x0080084A08040204,
+    0x0040E2A80811244C, 0x2505022008008108, 0x0430220100420040, 0x010A040420220040,
+    0x1105000290400000, 0x0093001200822120, 0x4000A62048043004, 0x280120048A015004,
+    0x006090002A020814, 0x44042000240800D0, 0x01102800040A4400, 0x1004080080220040,
+    0x0001001011004024, 0x0010044000805040, 0x0914041200820100, 0x0004821012821480,
+    0x0024040500C05021, 0x0088611002080200, 0x0116080A00040020, 0x4000020080080080,
+    0x2450450140840040, 0x0000880201484100, 0x0222020404020092, 0x8081110600002E00,
+    0x2842101105000801, 0x1100809008001025, 0x00020202221C0400, 0x0422014022009020,
+    0x0210046102100C00, 0xC004008082029102, 0x00AA461801101200, 0x0404080080201108,
+    0x020542108C205002, 0x0410544804100100, 0x0040910841100000, 0x0400200042021100,
+    0x00004204850400C0, 0x0200100410A42102, 0x1040020801210102, 0x0805040410420000,
+    0x2884804130100200, 0x800C262201242000, 0x1058000194108800, 0x0014221054420204,
+    0x0104000012A02200, 0x0200881003300100, 0x0140400202840100, 0x0402020801010201,
+];
+
+const fn generate_rook_masks() -> [u64; 64] {
+    let rays = generate_rays();
+    let mut masks = [0u64; 64];
+    let mut sq = 0;
+
+    while sq < 64 {
+        masks[sq] = (rays[NORTH][sq] & !RANK_8)
+            | (rays[SOUTH][sq] & !RANK_1)
+            | (rays[EAST][sq] & !FILE_H)
+            | (rays[WEST][sq] & !FILE_A);
+        sq += 1;
+    }
+
+    masks
+}
+
+const fn generate_bishop_masks() -> [u64; 64] {
+    let rays = generate_rays();
+    let edges = FILE_A | FILE_H | RANK_1 | RANK_8;
+    let mut masks = [0u64; 64];
+    let mut sq = 0;
+
+    while sq < 64 {
+        masks[sq] = (rays[NORTH_EAST][sq] | rays[NORTH_WEST][sq]
+            | rays[SOUTH_EAST][sq] | rays[SOUTH_WEST][sq]) & !edges;
+        sq += 1;
+    }
+
+    masks
+}
+
+/// Lookup parameters for one square
+#[derive(Clone, Copy)]
+struct Magic {
+    mask: u64,
+    magic: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl Magic {
+    #[inline(always)]
+    fn index(&self, occupied: u64) -> usize {
+        self.offset + ((occupied & self.mask).wrapping_mul(self.magic) >> self.shift) as usize
+    }
+}
+
+/// Magic lookup parameters plus the shared attack table for both slider types
+struct SliderAttacks {
+    rook: [Magic; 64],
+    bishop: [Magic; 64],
+    attacks: Vec<u64>,
+}
+
+/// Slider attack tables, built on first use (about 107k entries, ~840 KB)
+static SLIDER_ATTACKS: LazyLock<SliderAttacks> = LazyLock::new(SliderAttacks::generate);
+
+impl SliderAttacks {
+    fn generate() -> Self {
+        let mut attacks = Vec::new();
+        let rook = Self::fill(&ROOK_MASKS, &ROOK_MAGICS, &ORTHOGONAL_DIRECTIONS, &mut attacks);
+        let bishop = Self::fill(&BISHOP_MASKS, &BISHOP_MAGICS, &DIAGONAL_DIRECTIONS, &mut attacks);
+        Self { rook, bishop, attacks }
+    }
+
+    /// Appends the attack sets for every square and returns their lookup parameters
+    fn fill(masks: &[u64; 64], magics: &[u64; 64], directions: &[usize; 4], attacks: &mut Vec<u64>) -> [Magic; 64] {
+        let mut entries = [Magic { mask: 0, magic: 0, shift: 0, offset: 0 }; 64];
+
+        for sq in 0..64 {
+            let bits = masks[sq].count_ones();
+            let entry = Magic {
+                mask: masks[sq],
+                magic: magics[sq],
+                shift: 64 - bits,
+                offset: attacks.len(),
+            };
+            attacks.resize(attacks.len() + (1 << bits), 0);
+
+            // Enumerate every subset of the mask (carry-rippler trick)
+            let mut subset = 0u64;
+            loop {
+                attacks[entry.index(subset)] = slider_attacks_slow(sq, subset, directions);
+                subset = subset.wrapping_sub(entry.mask) & entry.mask;
+                if subset == 0 {
+                    break;
+                }
+            }
+
+            entries[sq] = entry;
+        }
+
+        entries
+    }
+}
+
+const ORTHOGONAL_DIRECTIONS: [usize; 4] = [NORTH, SOUTH, EAST, WEST];
+const DIAGONAL_DIRECTIONS: [usize; 4] = [NORTH_EAST, NORTH_WEST, SOUTH_EAST, SOUTH_WEST];
+
+/// Ray-walking slider attacks, used to fill the magic tables and as a test oracle
+fn slider_attacks_slow(square: usize, occupied: u64, directions: &[usize; 4]) -> u64 {
+    let mut attacks = 0u64;
+
+    for &direction in directions {
+        let ray = RAYS[direction][square];
+        let blockers = ray & occupied;
+        attacks |= ray;
+
+        if blockers != 0 {
+            // Find first blocker in this direction and cut the ray behind it
+            let blocker_sq = match direction {
+                NORTH | EAST | NORTH_EAST | NORTH_WEST => blockers.trailing_zeros() as usize,
+                _ => 63 - blockers.leading_zeros() as usize,
+            };
+            attacks &= !RAYS[direction][blocker_sq];
+        }
+    }
+
+    attacks
+}
+
+/// Squares attacked by a rook on `square` given the board occupancy
+/// (includes the first blocker in each direction, whatever its color)
+#[inline]
+pub fn rook_attacks(square: usize, occupied: u64) -> u64 {
+    let sliders = &*SLIDER_ATTACKS;
+    sliders.attacks[sliders.rook[square].index(occupied)]
+}
+
+/// Squares attacked by a bishop on `square` given the board occupancy
+#[inline]
+pub fn bishop_attacks(square: usize, occupied: u64) -> u64 {
+    let sliders = &*SLIDER_ATTACKS;
+    sliders.attacks[sliders.bishop[square].index(occupied)]
+}
+
+/// Squares attacked by a queen on `square` (union of rook and bishop attacks)
+#[inline]
+pub fn queen_attacks(square: usize, occupied: u64) -> u64 {
+    rook_attacks(square, occupied) | bishop_attacks(square, occupied)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -296,6 +528,57 @@ mod tests {
         assert_eq!(ray, expected);
     }
 
+    #[test]
+    fn test_between() {
+        // a1 to a8: a2..a7
+        let expected = (1u64 << 8) | (1u64 << 16) | (1u64 << 24) | (1u64 << 32) |
+                       (1u64 << 40) | (1u64 << 48);
+        assert_eq!(BETWEEN[0][56], expected);
+        assert_eq!(BETWEEN[56][0], expected);
+
+        // c1 to f4: d2, e3
+        assert_eq!(BETWEEN[2][29], (1u64 << 11) | (1u64 << 20));
+
+        // Adjacent or unaligned squares have nothing between them
+        assert_eq!(BETWEEN[0][1], 0);
+        assert_eq!(BETWEEN[0][17], 0);
+    }
+
+    #[test]
+    fn test_magic_attacks_match_ray_walk() {
+        // Deterministic xorshift so failures are reproducible
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for square in 0..64 {
+            for _ in 0..200 {
+                let occupied = next() & next();
+                assert_eq!(
+                    rook_attacks(square, occupied),
+                    slider_attacks_slow(square, occupied, &ORTHOGONAL_DIRECTIONS),
+                    "rook on {} with occupancy {:#x}", square, occupied
+                );
+                assert_eq!(
+                    bishop_attacks(square, occupied),
+                    slider_attacks_slow(square, occupied, &DIAGONAL_DIRECTIONS),
+                    "bishop on {} with occupancy {:#x}", square, occupied
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_queen_attacks_empty_board() {
+        // Queen on d4 sees 27 squares on an empty board
+        assert_eq!(queen_attacks(27, 0).count_ones(), 27);
+        assert_eq!(rook_attacks(27, 0), RAYS[NORTH][27] | RAYS[SOUTH][27] | RAYS[EAST][27] | RAYS[WEST][27]);
+    }
+
     #[test]
     fn test_rays_diagonal() {
         // Test north-east ray from a1 (square 0)
@@ -0,0 +1,128 @@
+//! Chess960 (Fischer Random) starting positions.
+//!
+//! Start positions are numbered 0-959 with Scharnagl's scheme, the numbering
+//! used by most GUIs and engines. Number 518 is the standard setup.
+
+use super::*;
+
+/// Number of distinct Chess960 starting positions
+pub const CHESS960_POSITIONS: u16 = 960;
+
+/// Start position number of the standard chess setup
+pub const STANDARD_CHESS960_INDEX: u16 = 518;
+
+/// Knight placements among the five squares left after bishops and queen
+const KNIGHT_TABLE: [(usize, usize); 10] = [
+    (0, 1), (0, 2), (0, 3), (0, 4), (1, 2),
+    (1, 3), (1, 4), (2, 3), (2, 4), (3, 4),
+];
+
+/// Back rank piece order (a-file to h-file) of a numbered start position
+///
+/// Returns `None` for numbers outside 0-959.
+pub fn chess960_back_rank(index: u16) -> Option<[Type; 8]> {
+    if index >= CHESS960_POSITIONS {
+        return None;
+    }
+
+    let mut rank = [Type::None; 8];
+    let mut n = index as usize;
+
+    // Light-squared bishop on b/d/f/h, dark-squared bishop on a/c/e/g
+    rank[n % 4 * 2 + 1] = Type::Bishop;
+    n /= 4;
+    rank[n % 4 * 2] = Type::Bishop;
+    n /= 4;
+
+    // Queen on the n-th free square
+    let free = |rank: &[Type; 8]| -> Vec<usize> {
+        (0..8).filter(|&file| rank[file] == Type::None).collect()
+    };
+    rank[free(&rank)[n % 6]] = Type::Queen;
+    n /= 6;
+
+    let (first, second) = KNIGHT_TABLE[n];
+    let empty = free(&rank);
+    rank[empty[first]] = Type::Knight;
+    rank[empty[second]] = Type::Knight;
+
+    // The last three squares take rook, king, rook in that order
+    let empty = free(&rank);
+    rank[empty[0]] = Type::Rook;
+    rank[empty[1]] = Type::King;
+    rank[empty[2]] = Type::Rook;
+
+    Some(rank)
+}
+
+/// FEN of a numbered start position, with X-FEN castling rights
+pub fn chess960_fen(index: u16) -> Option<String> {
+    let back_rank = chess960_back_rank(index)?;
+    let white: String = back_rank
+        .iter()
+        .map(|&piece_type| match piece_type {
+            Type::King => 'K',
+            Type::Queen => 'Q',
+            Type::Rook => 'R',
+            Type::Bishop => 'B',
+            _ => 'N',
+        })
+        .collect();
+    let black = white.to_ascii_lowercase();
+    Some(format!("{}/pppppppp/8/8/8/8/PPPPPPPP/{} w KQkq - 0 1", black, white))
+}
+
+impl Position {
+    /// Chess960 start position by number (0-959), flagged as a Chess960 game
+    pub fn chess960(index: u16) -> Option<Position> {
+        let mut pos = Position::from_fen(&chess960_fen(index)?);
+        pos.chess960 = true;
+        Some(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_index_is_standard_setup() {
+        assert_eq!(
+            chess960_fen(STANDARD_CHESS960_INDEX).unwrap(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+        );
+    }
+
+    #[test]
+    fn test_known_indices() {
+        // Reference numbering from Scharnagl's tables
+        assert_eq!(chess960_fen(0).unwrap().split('/').next_back().unwrap(), "BBQNNRKR w KQkq - 0 1");
+        assert_eq!(chess960_fen(959).unwrap().split('/').next_back().unwrap(), "RKRNNQBB w KQkq - 0 1");
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert!(chess960_back_rank(CHESS960_POSITIONS).is_none());
+        assert!(Position::chess960(1000).is_none());
+    }
+
+    #[test]
+    fn test_all_positions_valid_and_distinct() {
+        let mut seen = std::collections::HashSet::new();
+        for index in 0..CHESS960_POSITIONS {
+            let rank = chess960_back_rank(index).unwrap();
+            let king = rank.iter().position(|&t| t == Type::King).unwrap();
+            let rooks: Vec<usize> = (0..8).filter(|&f| rank[f] == Type::Rook).collect();
+            let bishops: Vec<usize> = (0..8).filter(|&f| rank[f] == Type::Bishop).collect();
+
+            assert!(rooks[0] < king && king < rooks[1], "king between rooks in {}", index);
+            assert_ne!(bishops[0] % 2, bishops[1] % 2, "bishops on opposite colours in {}", index);
+            assert!(seen.insert(rank), "duplicate setup for {}", index);
+
+            let fen = chess960_fen(index).unwrap();
+            let pos = Position::try_from_fen(&fen).unwrap();
+            assert_eq!(pos.to_fen(), fen);
+            assert!(Position::chess960(index).unwrap().chess960);
+        }
+    }
+}
@@ -0,0 +1,532 @@
+//! FEN (Forsyth-Edwards Notation) parsing with typed errors.
+//!
+//! [`Position::try_from_fen`] is the entry point for untrusted input (user text,
+//! files, protocol commands). It rejects malformed fields as well as positions
+//! that cannot arise in a legal game. [`Position::from_fen`] shares the same
+//! parser but skips the legality checks, which lets tests build artificial
+//! positions.
+//!
+//! The castling field accepts standard `KQkq`, X-FEN (`KQkq` meaning the
+//! outermost rook, with a file letter for an inner one) and Shredder-FEN
+//! (file letters only), so Chess960 positions round-trip.
+
+use super::*;
+use super::bitboards::Bitboards;
+use std::fmt;
+
+/// The FEN field an error was found in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FenField {
+    Placement,
+    SideToMove,
+    Castling,
+    EnPassant,
+    HalfmoveClock,
+    FullmoveNumber,
+}
+
+impl fmt::Display for FenField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FenField::Placement => "piece placement",
+            FenField::SideToMove => "side to move",
+            FenField::Castling => "castling rights",
+            FenField::EnPassant => "en passant square",
+            FenField::HalfmoveClock => "halfmove clock",
+            FenField::FullmoveNumber => "fullmove number",
+        };
+        f.write_str(name)
+    }
+}
+
+/// What went wrong while parsing a FEN string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenErrorKind {
+    /// The string has no piece placement field
+    Empty,
+    /// More than six whitespace-separated fields
+    TooManyFields,
+    /// A character that is neither a piece, a digit 1-8 nor '/'
+    InvalidPiece(char),
+    /// A rank describes more or fewer than eight squares
+    BadRankLength { rank: u8, squares: usize },
+    /// Placement does not describe exactly eight ranks
+    WrongRankCount(usize),
+    /// Side to move is not "w" or "b"
+    InvalidSideToMove,
+    /// A character in the castling field that is not one of KQkq, a rook file or '-'
+    InvalidCastlingChar(char),
+    /// Castling right without the king and rook on their home squares
+    ImpossibleCastling(char),
+    /// En passant field is not '-' or a square on the correct rank
+    InvalidEnPassant,
+    /// En passant square without a pawn that could just have double-pushed
+    ImpossibleEnPassant,
+    /// A move counter is not a non-negative integer (or the fullmove number is 0)
+    InvalidNumber,
+    /// The given side has no king
+    MissingKing(Color),
+    /// The given side has more than one king
+    TooManyKings(Color),
+    /// A pawn stands on the first or eighth rank
+    PawnOnBackRank,
+    /// The side that just moved has left its king in check
+    OpponentInCheck,
+}
+
+impl fmt::Display for FenErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FenErrorKind::Empty => write!(f, "empty FEN"),
+            FenErrorKind::TooManyFields => write!(f, "too many fields"),
+            FenErrorKind::InvalidPiece(c) => write!(f, "invalid piece character '{}'", c),
+            FenErrorKind::BadRankLength { rank, squares } => {
+                write!(f, "rank {} has {} squares, expected 8", rank, squares)
+            }
+            FenErrorKind::WrongRankCount(n) => write!(f, "{} ranks, expected 8", n),
+            FenErrorKind::InvalidSideToMove => write!(f, "expected 'w' or 'b'"),
+            FenErrorKind::InvalidCastlingChar(c) => write!(f, "invalid castling character '{}'", c),
+            FenErrorKind::ImpossibleCastling(c) => {
+                write!(f, "castling right '{}' without king and rook on their home squares", c)
+            }
+            FenErrorKind::InvalidEnPassant => write!(f, "expected '-' or a square on rank 3 or 6"),
+            FenErrorKind::ImpossibleEnPassant => {
+                write!(f, "no pawn could have just double-pushed past this square")
+            }
+            FenErrorKind::InvalidNumber => write!(f, "invalid number"),
+            FenErrorKind::MissingKing(color) => write!(f, "{:?} has no king", color),
+            FenErrorKind::TooManyKings(color) => write!(f, "{:?} has more than one king", color),
+            FenErrorKind::PawnOnBackRank => write!(f, "pawn on the first or eighth rank"),
+            FenErrorKind::OpponentInCheck => write!(f, "the side not to move is in check"),
+        }
+    }
+}
+
+/// Error returned by [`Position::try_from_fen`]
+///
+/// `offset` is the character offset into the original string where the
+/// offending field (or character) starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FenError {
+    pub field: FenField,
+    pub offset: usize,
+    pub kind: FenErrorKind,
+}
+
+impl FenError {
+    fn new(field: FenField, offset: usize, kind: FenErrorKind) -> Self {
+        Self { field, offset, kind }
+    }
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid FEN {} at offset {}: {}", self.field, self.offset, self.kind)
+    }
+}
+
+impl std::error::Error for FenError {}
+
+/// Offsets (in characters) of each field inside the FEN string
+pub(super) struct FieldOffsets([usize; 6]);
+
+impl FieldOffsets {
+    fn of(&self, field: FenField) -> usize {
+        self.0[field as usize]
+    }
+}
+
+/// Splits a FEN into its whitespace-separated fields, remembering where each starts
+fn split_fields(fen: &str) -> Vec<(usize, &str)> {
+    let mut fields = Vec::new();
+    let mut start = None;
+    let mut start_char = 0;
+
+    for (char_offset, (byte_idx, c)) in fen.char_indices().enumerate() {
+        if c.is_whitespace() {
+            if let Some(s) = start.take() {
+                fields.push((start_char, &fen[s..byte_idx]));
+            }
+        } else if start.is_none() {
+            start = Some(byte_idx);
+            start_char = char_offset;
+        }
+    }
+    if let Some(s) = start {
+        fields.push((start_char, &fen[s..]));
+    }
+    fields
+}
+
+impl Position {
+    /// Parses a FEN string, rejecting malformed input and illegal positions.
+    ///
+    /// Trailing fields may be omitted: side to move defaults to White, castling
+    /// rights to whatever the placement allows, en passant to none and the
+    /// counters to `0 1`.
+    pub fn try_from_fen(fen: &str) -> Result<Position, FenError> {
+        let (pos, offsets) = parse_fen(fen)?;
+        validate(&pos, &offsets)?;
+        Ok(pos)
+    }
+}
+
+/// Parses the syntax of all six fields without checking legality
+pub(super) fn parse_fen(fen: &str) -> Result<(Position, FieldOffsets), FenError> {
+    let fields = split_fields(fen);
+    let end = fen.chars().count();
+
+    if fields.is_empty() {
+        return Err(FenError::new(FenField::Placement, 0, FenErrorKind::Empty));
+    }
+    if fields.len() > 6 {
+        return Err(FenError::new(FenField::FullmoveNumber, fields[6].0, FenErrorKind::TooManyFields));
+    }
+
+    let mut offsets = [end; 6];
+    for (i, (offset, _)) in fields.iter().enumerate() {
+        offsets[i] = *offset;
+    }
+    let offsets = FieldOffsets(offsets);
+    let field = |i: usize| fields.get(i).map(|(_, s)| *s);
+
+    let board = parse_placement(fields[0].1, fields[0].0)?;
+
+    // Side to move
+    let side_to_move = match field(1) {
+        None | Some("w") => Color::White,
+        Some("b") => Color::Black,
+        Some(_) => {
+            return Err(FenError::new(
+                FenField::SideToMove,
+                offsets.of(FenField::SideToMove),
+                FenErrorKind::InvalidSideToMove,
+            ))
+        }
+    };
+
+    // Castling rights
+    // castling_cond: [white_kingside_rook, white_queenside_rook, white_king, black_kingside_rook, black_queenside_rook, black_king]
+    let mut castling_cond = [false; 6];
+    let mut castling_squares = STANDARD_CASTLING_SQUARES;
+    let mut chess960 = false;
+    match field(2) {
+        None => {
+            // Not specified: grant every right the placement still allows
+            for c in ['K', 'Q', 'k', 'q'] {
+                if castling_pieces_home(&board, c) {
+                    grant_castling(&board, &mut castling_cond, &mut castling_squares, c);
+                }
+            }
+        }
+        Some("-") => {}
+        Some(castling_str) => {
+            let base = offsets.of(FenField::Castling);
+            for (i, c) in castling_str.chars().enumerate() {
+                if !grant_castling(&board, &mut castling_cond, &mut castling_squares, c) {
+                    return Err(FenError::new(
+                        FenField::Castling,
+                        base + i,
+                        FenErrorKind::InvalidCastlingChar(c),
+                    ));
+                }
+                // File letters only appear in Shredder-FEN / X-FEN
+                chess960 |= !matches!(c, 'K' | 'Q' | 'k' | 'q');
+            }
+        }
+    }
+    // Castling pieces anywhere but the standard squares can only be Chess960
+    chess960 |= (0..6).any(|i| castling_cond[i] && castling_squares[i] != STANDARD_CASTLING_SQUARES[i]);
+
+    // En passant target square
+    let en_passant = match field(3) {
+        None | Some("-") => None,
+        Some(ep) => match algebraic_to_square(ep) {
+            Some(sq) if sq / 8 == 2 || sq / 8 == 5 => Some(sq as u8),
+            _ => {
+                return Err(FenError::new(
+                    FenField::EnPassant,
+                    offsets.of(FenField::EnPassant),
+                    FenErrorKind::InvalidEnPassant,
+                ))
+            }
+        },
+    };
+
+    // Move counters
+    let halfmove_clock = match field(4) {
+        None => 0,
+        Some(s) => s.parse::<u32>().map_err(|_| {
+            FenError::new(
+                FenField::HalfmoveClock,
+                offsets.of(FenField::HalfmoveClock),
+                FenErrorKind::InvalidNumber,
+            )
+        })?,
+    };
+    let fullmove_number = match field(5) {
+        None => 1,
+        Some(s) => match s.parse::<u32>() {
+            Ok(n) if n > 0 => n,
+            _ => {
+                return Err(FenError::new(
+                    FenField::FullmoveNumber,
+                    offsets.of(FenField::FullmoveNumber),
+                    FenErrorKind::InvalidNumber,
+                ))
+            }
+        },
+    };
+
+    let mut pos = Position {
+        bitboards: Bitboards::from_array(board),
+        position: board,
+        prev_moves: Vec::new(),
+        side_to_move,
+        castling_cond,
+        castling_squares,
+        chess960,
+        en_passant,
+        halfmove_clock,
+        fullmove_number,
+        zobrist: 0,
+    };
+    pos.zobrist = pos.compute_zobrist();
+    Ok((pos, offsets))
+}
+
+/// Parses the piece placement field into a mailbox board
+fn parse_placement(placement: &str, base: usize) -> Result<[Piece; 64], FenError> {
+    let mut board = [Piece::default(); 64];
+    let mut rank: usize = 0; // ranks are listed from 8 down to 1
+    let mut file: usize = 0;
+
+    let bad_rank = |rank: usize, squares: usize, offset: usize| {
+        FenError::new(
+            FenField::Placement,
+            offset,
+            FenErrorKind::BadRankLength { rank: 8 - rank as u8, squares },
+        )
+    };
+
+    for (i, c) in placement.chars().enumerate() {
+        match c {
+            '/' => {
+                if file != 8 {
+                    return Err(bad_rank(rank, file, base + i));
+                }
+                rank += 1;
+                file = 0;
+                if rank > 7 {
+                    return Err(FenError::new(
+                        FenField::Placement,
+                        base + i,
+                        FenErrorKind::WrongRankCount(rank + 1),
+                    ));
+                }
+            }
+            '1'..='8' => {
+                file += c.to_digit(10).unwrap() as usize;
+                if file > 8 {
+                    return Err(bad_rank(rank, file, base + i));
+                }
+            }
+            _ => {
+                let piece = Piece::try_from_char(c).ok_or_else(|| {
+                    FenError::new(FenField::Placement, base + i, FenErrorKind::InvalidPiece(c))
+                })?;
+                if file >= 8 {
+                    return Err(bad_rank(rank, file + 1, base + i));
+                }
+                board[(7 - rank) * 8 + file] = piece;
+                file += 1;
+            }
+        }
+    }
+
+    let end = base + placement.chars().count();
+    if rank != 7 {
+        return Err(FenError::new(
+            FenField::Placement,
+            end,
+            FenErrorKind::WrongRankCount(rank + 1),
+        ));
+    }
+    if file != 8 {
+        return Err(bad_rank(rank, file, end));
+    }
+    Ok(board)
+}
+
+/// Sets the castling flags and piece squares for one castling character
+///
+/// `KQkq` pick the outermost rook on that side of the king (X-FEN); a file
+/// letter names the rook directly (Shredder-FEN), upper case for White.
+/// Returns false for a character that is neither.
+fn grant_castling(board: &[Piece; 64], castling_cond: &mut [bool; 6], castling_squares: &mut [u8; 6], c: char) -> bool {
+    let (color, rank_base, cond_base) = if c.is_ascii_uppercase() {
+        (Color::White, 0, 0)
+    } else {
+        (Color::Black, 56, 3)
+    };
+    let own = |file: usize, piece_type: Type| {
+        let piece = board[rank_base + file];
+        piece.piece_type == piece_type && piece.color == color
+    };
+
+    // Without a king on the back rank, assume the standard e-file; validation
+    // rejects the right later
+    let king_file = (0..8).find(|&file| own(file, Type::King)).unwrap_or(4);
+
+    let rook_file = match c.to_ascii_uppercase() {
+        'K' => (king_file + 1..8).rev().find(|&file| own(file, Type::Rook)).unwrap_or(7),
+        'Q' => (0..king_file).find(|&file| own(file, Type::Rook)).unwrap_or(0),
+        f @ 'A'..='H' => (f as u8 - b'A') as usize,
+        _ => return false,
+    };
+    let side = if rook_file > king_file { 0 } else { 1 };
+
+    castling_cond[cond_base + side] = true;
+    castling_cond[cond_base + 2] = true;
+    castling_squares[cond_base + side] = (rank_base + rook_file) as u8;
+    castling_squares[cond_base + 2] = (rank_base + king_file) as u8;
+    true
+}
+
+/// The castling field character for one right (`flag` indexes `castling_cond`)
+///
+/// Shredder-FEN always names the rook's file. Otherwise X-FEN is used, which
+/// writes `KQkq` unless another rook stands further out on the same side.
+fn castling_char(pos: &Position, flag: usize, shredder: bool) -> char {
+    let rook_sq = pos.castling_squares[flag] as usize;
+    let color = if flag < 3 { Color::White } else { Color::Black };
+    let rank_base = rook_sq & !7;
+    let file = rook_sq % 8;
+    let kingside = flag == 0 || flag == 3;
+
+    let outer_files = if kingside { file + 1..8 } else { 0..file };
+    let outermost = !outer_files.into_iter().any(|f| {
+        let piece = pos.position[rank_base + f];
+        piece.piece_type == Type::Rook && piece.color == color
+    });
+
+    let c = if !shredder && outermost {
+        if kingside { 'K' } else { 'Q' }
+    } else {
+        (b'A' + file as u8) as char
+    };
+    match color {
+        Color::White => c,
+        Color::Black => c.to_ascii_lowercase(),
+    }
+}
+
+/// The castling field of a FEN, `-` when no rights remain
+pub(super) fn castling_field(pos: &Position, shredder: bool) -> String {
+    let mut castling = String::new();
+    for (rook_flag, king_flag) in [(0, 2), (1, 2), (3, 5), (4, 5)] {
+        if pos.castling_cond[king_flag] && pos.castling_cond[rook_flag] {
+            castling.push(castling_char(pos, rook_flag, shredder));
+        }
+    }
+    if castling.is_empty() {
+        castling.push('-');
+    }
+    castling
+}
+
+/// Whether the king and rook needed for a KQkq right are on their home squares
+fn castling_pieces_home(board: &[Piece; 64], c: char) -> bool {
+    let (color, king_sq, rook_sq) = match c {
+        'K' => (Color::White, 4, 7),
+        'Q' => (Color::White, 4, 0),
+        'k' => (Color::Black, 60, 63),
+        'q' => (Color::Black, 60, 56),
+        _ => return false,
+    };
+    let is = |sq: usize, piece_type: Type| {
+        board[sq].piece_type == piece_type && board[sq].color == color
+    };
+    is(king_sq, Type::King) && is(rook_sq, Type::Rook)
+}
+
+/// Rejects positions that cannot occur in a legal game
+fn validate(pos: &Position, offsets: &FieldOffsets) -> Result<(), FenError> {
+    let placement = offsets.of(FenField::Placement);
+
+    for color in [Color::White, Color::Black] {
+        match pos.bitboards.pieces_of_type(color, Type::King).count_ones() {
+            0 => {
+                return Err(FenError::new(FenField::Placement, placement, FenErrorKind::MissingKing(color)))
+            }
+            1 => {}
+            _ => {
+                return Err(FenError::new(FenField::Placement, placement, FenErrorKind::TooManyKings(color)))
+            }
+        }
+    }
+
+    const BACK_RANKS: u64 = 0xFF00_0000_0000_00FF;
+    let pawns = pos.bitboards.pieces_of_type(Color::White, Type::Pawn)
+        | pos.bitboards.pieces_of_type(Color::Black, Type::Pawn);
+    if pawns & BACK_RANKS != 0 {
+        return Err(FenError::new(FenField::Placement, placement, FenErrorKind::PawnOnBackRank));
+    }
+
+    // Each granted right needs its king and rook at home
+    for (rook_flag, king_flag) in [(0, 2), (1, 2), (3, 5), (4, 5)] {
+        if !pos.castling_cond[rook_flag] {
+            continue;
+        }
+        let color = if rook_flag < 3 { Color::White } else { Color::Black };
+        let is = |sq: u8, piece_type: Type| {
+            let piece = pos.position[sq as usize];
+            piece.piece_type == piece_type && piece.color == color
+        };
+        if !is(pos.castling_squares[king_flag], Type::King) || !is(pos.castling_squares[rook_flag], Type::Rook) {
+            // Name Chess960 rights by their rook file, as KQkq would be ambiguous
+            let by_file = pos.castling_squares[rook_flag] != STANDARD_CASTLING_SQUARES[rook_flag];
+            return Err(FenError::new(
+                FenField::Castling,
+                offsets.of(FenField::Castling),
+                FenErrorKind::ImpossibleCastling(castling_char(pos, rook_flag, by_file)),
+            ));
+        }
+    }
+
+    let side_to_move = pos.side_to_move;
+
+    // The en passant square must sit behind an enemy pawn that just double-pushed
+    if let Some(ep) = pos.en_passant {
+        let ep = ep as usize;
+        let (expected_rank, pawn_sq, origin_sq) = match side_to_move {
+            Color::White => (5, ep.wrapping_sub(8), ep + 8),
+            Color::Black => (2, ep + 8, ep.wrapping_sub(8)),
+        };
+        let mover = side_to_move.opposite();
+        let pawn_there = pawn_sq < 64
+            && pos.position[pawn_sq].piece_type == Type::Pawn
+            && pos.position[pawn_sq].color == mover;
+        if ep / 8 != expected_rank
+            || !pawn_there
+            || !pos.position[ep].is_none()
+            || !pos.position[origin_sq].is_none()
+        {
+            return Err(FenError::new(
+                FenField::EnPassant,
+                offsets.of(FenField::EnPassant),
+                FenErrorKind::ImpossibleEnPassant,
+            ));
+        }
+    }
+
+    if pos.is_in_check(side_to_move.opposite()) {
+        return Err(FenError::new(
+            FenField::SideToMove,
+            offsets.of(FenField::SideToMove),
+            FenErrorKind::OpponentInCheck,
+        ));
+    }
+
+    Ok(())
+}
@@ -1,15 +1,26 @@
+mod chess960;
+mod fen;
 mod moves;
 mod piece;
 mod position;
+mod san;
+mod see;
+mod uci_move;
 mod piece_moves;
+mod zobrist;
 pub mod bitboards;
 
 #[cfg(test)]
 mod tests;
 
+pub use chess960::{chess960_back_rank, chess960_fen, CHESS960_POSITIONS, STANDARD_CHESS960_INDEX};
+pub use fen::{FenError, FenErrorKind, FenField};
 pub use moves::*;
 pub use piece::*;
 pub use position::*;
+pub use san::SanError;
+pub use uci_move::UciMoveError;
 pub use piece_moves::*;
 pub use bitboards::*;
+pub use zobrist::{ZobristKeys, ZOBRIST};
 
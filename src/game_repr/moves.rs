@@ -3,6 +3,8 @@
 // This allows efficient storage and comparison of moves while encoding
 // all necessary information (source, destination, and move type).
 
+use super::{square_to_algebraic, Type};
+
 /*-------ARCHITECTURE--------*/
 
 // | 6 bits | 6 bits | 4 bits |
@@ -30,6 +32,17 @@ impl MoveType {
             MoveType::PromotionKnight
         )
     }
+
+    /// The piece a pawn promotes to, if this is a promotion
+    pub fn promotion_piece(&self) -> Option<Type> {
+        match self {
+            MoveType::PromotionQueen => Some(Type::Queen),
+            MoveType::PromotionRook => Some(Type::Rook),
+            MoveType::PromotionBishop => Some(Type::Bishop),
+            MoveType::PromotionKnight => Some(Type::Knight),
+            _ => None,
+        }
+    }
 }
 
 impl From<u16> for MoveType {
@@ -73,4 +86,41 @@ impl Move {
     pub fn _to(&self) -> usize {
         ((self.buf >> 4) & 0x3F) as usize
     }
+
+    /// Where the king and rook end up for a castling move, as `(king, rook)`
+    ///
+    /// Castling is encoded as the king capturing its own rook, so `_to()` is
+    /// the rook's starting square. The destinations are the g/f files for a
+    /// kingside castle and c/d for queenside, on every starting setup.
+    pub fn castling_destinations(&self) -> (usize, usize) {
+        let rank_base = self._to() & !7;
+        if self._to() > self._from() {
+            (rank_base + 6, rank_base + 5)
+        } else {
+            (rank_base + 2, rank_base + 3)
+        }
+    }
+
+    /// Long algebraic (UCI) form: `e2e4`, `e7e8q`, and `e1g1` for castling
+    ///
+    /// Castling is written as the king's two-square step of standard chess.
+    /// Chess960 notation, where the king takes its own rook, needs the
+    /// position and is produced by [`Position::move_to_uci`].
+    ///
+    /// [`Position::move_to_uci`]: super::Position::move_to_uci
+    pub fn to_uci(self) -> String {
+        let to = match self.move_type() {
+            MoveType::Castling => self.castling_destinations().0,
+            _ => self._to(),
+        };
+        let mut uci = format!("{}{}", square_to_algebraic(self._from()), square_to_algebraic(to));
+        match self.move_type().promotion_piece() {
+            Some(Type::Queen) => uci.push('q'),
+            Some(Type::Rook) => uci.push('r'),
+            Some(Type::Bishop) => uci.push('b'),
+            Some(Type::Knight) => uci.push('n'),
+            _ => {}
+        }
+        uci
+    }
 }
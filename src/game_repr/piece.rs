@@ -52,8 +52,9 @@ impl Piece {
         self.piece_type == Type::None
     }
 
-    pub fn from_char(c: char) -> Self {
-        match c {
+    /// Parses a FEN piece letter (or a digit, which yields an empty square)
+    pub fn try_from_char(c: char) -> Option<Self> {
+        let piece = match c {
             'p' => Self {
                 color: Color::Black,
                 piece_type: Type::Pawn,
@@ -106,8 +107,14 @@ impl Piece {
                 color: Color::White,
                 piece_type: Type::None,
             },
-            _ => panic!("Invalid character, unable to transfrom into piece"),
-        }
+            _ => return None,
+        };
+        Some(piece)
+    }
+
+    /// Like [`Piece::try_from_char`], but panics on an invalid character
+    pub fn from_char(c: char) -> Self {
+        Self::try_from_char(c).expect("Invalid character, unable to transfrom into piece")
     }
 
     // true = white, false = black
@@ -1,6 +1,6 @@
 use smallvec::SmallVec;
 use crate::game_repr::{Move, MoveType};
-use crate::game_repr::bitboards::{pop_lsb, bitscan_forward, tables::*};
+use crate::game_repr::bitboards::{pop_lsb, tables::bishop_attacks};
 
 use super::super::position::Position;
 
@@ -11,39 +11,18 @@ impl Position {
         let occupied = self.bitboards.all_occupied();
         let friendly_pieces = self.bitboards.occupied_by_color(moving_piece.color);
 
-        // Process each diagonal direction (NE, NW, SE, SW)
-        for &direction in &[NORTH_EAST, NORTH_WEST, SOUTH_EAST, SOUTH_WEST] {
-            let mut ray = RAYS[direction][idx];
+        // Magic lookup gives every reachable square, including the first blocker in each direction
+        let mut targets = bishop_attacks(idx, occupied);
 
-            // Find first blocker in this direction
-            let blockers = ray & occupied;
-            if blockers != 0 {
-                let blocker_sq = if direction == NORTH_EAST || direction == NORTH_WEST {
-                    bitscan_forward(blockers)  // First blocker going forward (toward higher rank)
-                } else {
-                    63 - blockers.leading_zeros() as usize  // First blocker going backward (toward lower rank)
-                };
-
-                // Mask out squares beyond the blocker
-                ray &= !(RAYS[direction][blocker_sq]);
-
-                // Include the blocker square if it's an enemy piece (capture) or if include_friendly
-                let blocker_piece = self.position[blocker_sq];
-                if blocker_piece.color != moving_piece.color || include_friendly {
-                    ray |= 1u64 << blocker_sq;
-                }
-            }
-
-            // Remove friendly pieces unless include_friendly is true
-            if !include_friendly {
-                ray &= !friendly_pieces;
-            }
+        // Remove friendly pieces unless include_friendly is true
+        if !include_friendly {
+            targets &= !friendly_pieces;
+        }
 
-            // Generate moves for each target square in this ray
-            while ray != 0 {
-                let target_sq = pop_lsb(&mut ray);
-                moves.push(Move::new(idx as u8, target_sq as u8, MoveType::Normal));
-            }
+        // Generate moves for each target square
+        while targets != 0 {
+            let target_sq = pop_lsb(&mut targets);
+            moves.push(Move::new(idx as u8, target_sq as u8, MoveType::Normal));
         }
     }
 
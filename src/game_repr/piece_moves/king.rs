@@ -1,6 +1,6 @@
 use smallvec::SmallVec;
 use crate::game_repr::{MoveType, Move, Color};
-use crate::game_repr::bitboards::{pop_lsb, tables::KING_ATTACKS};
+use crate::game_repr::bitboards::{pop_lsb, tables::{BETWEEN, KING_ATTACKS}};
 
 use super::super::{
     piece::Type,
@@ -28,13 +28,14 @@ impl Position {
         }
 
         // Add castling moves
-        // Check if king is in its starting position
-        let is_king_on_starting_square = match king_color {
-            Color::White => idx == 4,
-            Color::Black => idx == 60,
+        // Get castling condition indices for this color
+        let (kingside_rook_cond, queenside_rook_cond, king_cond) = match king_color {
+            Color::White => (0, 1, 2),
+            Color::Black => (3, 4, 5),
         };
 
-        if !is_king_on_starting_square {
+        // Check if king is in its starting position
+        if !self.castling_cond[king_cond] || idx != self.castling_squares[king_cond] as usize {
             return;
         }
 
@@ -43,61 +44,48 @@ impl Position {
             return;
         }
 
-        // Get castling condition indices for this color
-        let (kingside_rook_cond, queenside_rook_cond, king_cond) = match king_color {
-            Color::White => (0, 1, 2),
-            Color::Black => (3, 4, 5),
-        };
-
         let opponent_color = king_color.opposite();
+        let occupied = self.bitboards.all_occupied();
 
-        // Try kingside castling
-        if self.castling_cond[kingside_rook_cond] && self.castling_cond[king_cond] {
-            let (f_square, g_square, h_square) = match king_color {
-                Color::White => (5, 6, 7),   // f1, g1, h1
-                Color::Black => (61, 62, 63), // f8, g8, h8
-            };
-
-            // Check that squares between king and rook are empty
-            let squares_empty = self.position[f_square].piece_type == Type::None &&
-                               self.position[g_square].piece_type == Type::None;
+        for rook_cond in [kingside_rook_cond, queenside_rook_cond] {
+            if !self.castling_cond[rook_cond] {
+                continue;
+            }
 
-            // Check that king doesn't pass through or land in check
-            let king_safe = !self.is_square_attacked(f_square, opponent_color) &&
-                           !self.is_square_attacked(g_square, opponent_color);
+            // Verify rook is on its starting square
+            let rook_sq = self.castling_squares[rook_cond] as usize;
+            let rook_present = self.position[rook_sq].piece_type == Type::Rook &&
+                              self.position[rook_sq].color == king_color;
+            if !rook_present {
+                continue;
+            }
 
-            // Verify rook is on starting square
-            let rook_present = self.position[h_square].piece_type == Type::Rook &&
-                              self.position[h_square].color == king_color;
+            // Castling is encoded as the king taking its own rook
+            let castle = Move::new(idx as u8, rook_sq as u8, MoveType::Castling);
+            let (king_to, rook_to) = castle.castling_destinations();
+
+            // Every square either piece crosses or lands on must be empty,
+            // apart from the king and rook themselves
+            let king_path = BETWEEN[idx][king_to] | (1u64 << king_to);
+            let rook_path = BETWEEN[rook_sq][rook_to] | (1u64 << rook_to);
+            let movers = (1u64 << idx) | (1u64 << rook_sq);
+            if (king_path | rook_path) & occupied & !movers != 0 {
+                continue;
+            }
 
-            if squares_empty && king_safe && rook_present {
-                moves.push(Move::new(idx as u8, g_square as u8, MoveType::Castling));
+            // Check that king doesn't pass through check; the landing square
+            // is verified by the legality filter once the rook has moved
+            let mut passed = BETWEEN[idx][king_to];
+            let mut king_safe = true;
+            while passed != 0 {
+                if self.is_square_attacked(pop_lsb(&mut passed), opponent_color) {
+                    king_safe = false;
+                    break;
+                }
             }
-        }
 
-        // Try queenside castling
-        if self.castling_cond[queenside_rook_cond] && self.castling_cond[king_cond] {
-            let (a_square, b_square, c_square, d_square) = match king_color {
-                Color::White => (0, 1, 2, 3),     // a1, b1, c1, d1
-                Color::Black => (56, 57, 58, 59), // a8, b8, c8, d8
-            };
-
-            // Check that squares between king and rook are empty
-            let squares_empty = self.position[b_square].piece_type == Type::None &&
-                               self.position[c_square].piece_type == Type::None &&
-                               self.position[d_square].piece_type == Type::None;
-
-            // Check that king doesn't pass through or land in check
-            // Note: b_square doesn't need to be checked as king doesn't pass through it
-            let king_safe = !self.is_square_attacked(c_square, opponent_color) &&
-                           !self.is_square_attacked(d_square, opponent_color);
-
-            // Verify rook is on starting square
-            let rook_present = self.position[a_square].piece_type == Type::Rook &&
-                              self.position[a_square].color == king_color;
-
-            if squares_empty && king_safe && rook_present {
-                moves.push(Move::new(idx as u8, c_square as u8, MoveType::Castling));
+            if king_safe {
+                moves.push(castle);
             }
         }
     }
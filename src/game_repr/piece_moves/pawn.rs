@@ -53,33 +53,23 @@ impl Position {
             moves.push(Move::new(idx as u8, target_sq as u8, MoveType::Normal));
         }
 
-        // En passant - only check if there was a previous move
-        if !self.prev_moves.is_empty() {
-            let prev_move = self.prev_moves.last().unwrap();
-            let prev_to = prev_move._to();
-            let prev_from = prev_move._from();
-
-            // Check if last move was a two-square pawn advance
-            if self.position[prev_to].piece_type == Type::Pawn
-                && self.position[prev_to].color != piece.color
-                && (prev_from as i32 - prev_to as i32).abs() == 16
+        // En passant - only if the position has an en passant target square
+        if let Some(ep_target) = self.en_passant {
+            let ep_target = ep_target as usize;
+
+            // The target must be on the capturing side's en passant rank (6th for White, 3rd for Black)
+            // and the pawn that made the double push must sit directly behind it
+            let (ep_rank, captured_sq) = match piece.color {
+                Color::White => (5, ep_target.wrapping_sub(8)),
+                Color::Black => (2, ep_target + 8),
+            };
+
+            if ep_target / 8 == ep_rank
+                && PAWN_ATTACKS[color_idx][idx] & (1u64 << ep_target) != 0
+                && self.position[captured_sq].piece_type == Type::Pawn
+                && self.position[captured_sq].color != piece.color
             {
-                // Check if enemy pawn is adjacent on the same rank
-                let same_rank = idx / 8 == prev_to / 8;
-                let adjacent = (idx as i32 - prev_to as i32).abs() == 1;
-
-                if same_rank && adjacent {
-                    // En passant target square is behind the enemy pawn
-                    let ep_target = if piece.color == Color::White {
-                        prev_to + 8
-                    } else {
-                        prev_to.wrapping_sub(8)
-                    };
-
-                    if ep_target < 64 {
-                        moves.push(Move::new(idx as u8, ep_target as u8, MoveType::EnPassant));
-                    }
-                }
+                moves.push(Move::new(idx as u8, ep_target as u8, MoveType::EnPassant));
             }
         }
 
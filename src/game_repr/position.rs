@@ -15,106 +15,86 @@ pub struct Position {
     pub(crate) bitboards: Bitboards,
     /// Mailbox representation for fast piece lookup (kept in sync with bitboards)
     pub position: [Piece; 64],
+    /// Moves played since the position was set up (not used to derive game state)
     pub prev_moves: Vec<Move>,
+    /// Side whose turn it is
+    pub side_to_move: Color,
     /// 3 bits for each side
     /// TRUE - has not moved
     /// KingRook, QueenRook, King - white  |  R  |  K  |  Q  | R
     /// KingRook, QueenRook, King - black  |  R  |  Q  |  K  | R
     pub castling_cond: [bool; 6],
+    /// Starting squares of the castling pieces, laid out like `castling_cond`
+    /// (kingside rook, queenside rook, king per side). Standard chess uses
+    /// [`STANDARD_CASTLING_SQUARES`]; Chess960 setups differ.
+    pub castling_squares: [u8; 6],
+    /// Whether this is a Chess960 game (affects notation, not rules)
+    pub chess960: bool,
+    /// En passant target square (the square a capturing pawn lands on), if any
+    pub en_passant: Option<u8>,
+    /// Plies since the last capture or pawn move (FEN halfmove clock)
+    pub halfmove_clock: u32,
+    /// Full move number, starting at 1 and incremented after Black moves
+    pub fullmove_number: u32,
+    /// Zobrist key of the position, maintained incrementally by make/unmake
+    pub zobrist: u64,
 }
 
 #[derive(Clone, Copy)]
 pub struct UndoInfo {
     captured_piece: Piece,
     castling_cond: [bool; 6],
+    en_passant: Option<u8>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+    zobrist: u64,
 }
 
+/// Castling piece squares of the standard setup: h1, a1, e1, h8, a8, e8
+pub const STANDARD_CASTLING_SQUARES: [u8; 6] = [7, 0, 4, 63, 56, 60];
+
 impl Default for Position {
     fn default() -> Self {
         Self::from_fen(r"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR")
     }
 }
 
-impl Position {
-
-    pub fn from_fen(fen_str: &str) -> Position {
-        let parts: Vec<&str> = fen_str.split_whitespace().collect();
-
-        // Parse piece placement (always present)
-        let piece_placement = parts.first().unwrap_or(&"");
-        let mut idx: usize = 56;
-        let mut board = [Piece::default(); 64];
-
-        for c in piece_placement.chars() {
-            match c {
-                '/' => {
-                    idx -= 16;
-                }
-                '1'..='8' => {
-                    idx += c.to_digit(10).unwrap() as usize;
-                }
-                _ => {
-                    board[idx] = Piece::from_char(c);
-                    idx += 1;
-                }
-            }
-        }
-
-        // Parse side to move (second field in FEN)
-        // "w" = White to move, "b" = Black to move
-        let side_to_move = parts.get(1).unwrap_or(&"w");
-        let is_white_to_move = *side_to_move == "w";
-
-        // Parse castling rights (third field in FEN)
-        // castling_cond: [white_kingside_rook, white_queenside_rook, white_king, black_kingside_rook, black_queenside_rook, black_king]
-        let mut castling_cond = [false; 6];
-        if let Some(castling_str) = parts.get(2) {
-            if *castling_str != "-" {
-                for c in castling_str.chars() {
-                    match c {
-                        'K' => {
-                            castling_cond[0] = true;  // White kingside rook
-                            castling_cond[2] = true;  // White king
-                        }
-                        'Q' => {
-                            castling_cond[1] = true;  // White queenside rook
-                            castling_cond[2] = true;  // White king
-                        }
-                        'k' => {
-                            castling_cond[3] = true;  // Black kingside rook
-                            castling_cond[5] = true;  // Black king
-                        }
-                        'q' => {
-                            castling_cond[4] = true;  // Black queenside rook
-                            castling_cond[5] = true;  // Black king
-                        }
-                        _ => {}
-                    }
-                }
-            }
-        } else {
-            // No castling rights specified, default to all true (for backward compatibility)
-            castling_cond = [true; 6];
-        }
+/// Converts a square index (0-63) to algebraic notation ("a1".."h8")
+pub fn square_to_algebraic(square: usize) -> String {
+    let file = (b'a' + (square % 8) as u8) as char;
+    let rank = (b'1' + (square / 8) as u8) as char;
+    format!("{}{}", file, rank)
+}
 
-        let bitboards = Bitboards::from_array(board);
+/// Parses algebraic notation ("a1".."h8") into a square index (0-63)
+pub fn algebraic_to_square(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    if bytes.len() != 2 {
+        return None;
+    }
+    let file = bytes[0].wrapping_sub(b'a');
+    let rank = bytes[1].wrapping_sub(b'1');
+    if file < 8 && rank < 8 {
+        Some(rank as usize * 8 + file as usize)
+    } else {
+        None
+    }
+}
 
-        // Initialize prev_moves to reflect whose turn it is
-        // The Position struct uses prev_moves.len() % 2 to determine side to move
-        // - even length (0, 2, 4, ...) = White to move
-        // - odd length (1, 3, 5, ...) = Black to move
-        let mut prev_moves = Vec::new();
-        if !is_white_to_move {
-            // Black to move means we need odd length prev_moves
-            // Add a dummy move (this is a sentinel value that won't be used)
-            prev_moves.push(Move::new(0, 0, MoveType::Normal));
-        }
+impl Position {
 
-        Self {
-            bitboards,
-            position: board,
-            prev_moves,
-            castling_cond,
+    /// Builds a position from a FEN string without checking that it is legal.
+    ///
+    /// Meant for trusted input such as built-in positions and tests; use
+    /// [`Position::try_from_fen`] for anything that comes from a user.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the FEN is syntactically malformed.
+    pub fn from_fen(fen_str: &str) -> Position {
+        match super::fen::parse_fen(fen_str) {
+            Ok((pos, _)) => pos,
+            Err(e) => panic!("{}", e),
         }
     }
 
@@ -122,7 +102,28 @@ impl Position {
         let from = _move._from();
         let to = _move._to();
         let moving_piece = self.position[from];
-        let captured_piece = self.position[to];
+        let is_castling = _move.move_type() == MoveType::Castling;
+        // Castling "captures" the king's own rook, which is not a capture
+        let captured_piece = if is_castling { Piece::default() } else { self.position[to] };
+
+        // Take the old castling rights and en passant file out of the key;
+        // the new ones are hashed back in once the move is complete
+        self.zobrist ^= ZOBRIST.castling_rights(&self.castling_cond);
+        if let Some(file) = self.en_passant_zobrist_file() {
+            self.zobrist ^= ZOBRIST.en_passant[file];
+        }
+
+        // Update the key for the moving piece, any piece captured on the
+        // destination, and the piece that ends up there (promotions differ).
+        // Castling hashes both of its pieces in its own branch below.
+        if !is_castling {
+            let placed_type = _move.move_type().promotion_piece().unwrap_or(moving_piece.piece_type);
+            self.zobrist ^= ZOBRIST.piece(moving_piece, from);
+            self.zobrist ^= ZOBRIST.piece(Piece { color: moving_piece.color, piece_type: placed_type }, to);
+            if captured_piece.piece_type != Type::None {
+                self.zobrist ^= ZOBRIST.piece(captured_piece, to);
+            }
+        }
 
         // Update castling conditions BEFORE making the move
         // If king moves, disable castling for that color
@@ -133,27 +134,34 @@ impl Position {
             }
         }
 
-        // If rook moves from starting position, disable castling on that side
-        if moving_piece.piece_type == Type::Rook {
-            match from {
-                7 => self.castling_cond[0] = false,   // White kingside rook (h1)
-                0 => self.castling_cond[1] = false,   // White queenside rook (a1)
-                63 => self.castling_cond[3] = false,  // Black kingside rook (h8)
-                56 => self.castling_cond[4] = false,  // Black queenside rook (a8)
-                _ => {}
+        // A rook leaving its starting square, or captured on it, loses its castling right
+        let rook_moved = moving_piece.piece_type == Type::Rook;
+        let rook_captured = captured_piece.piece_type == Type::Rook;
+        if rook_moved || rook_captured {
+            for i in [0, 1, 3, 4] {
+                let sq = self.castling_squares[i] as usize;
+                if (rook_moved && from == sq) || (rook_captured && to == sq) {
+                    self.castling_cond[i] = false;
+                }
             }
         }
 
-        // If a rook is captured on its starting square, disable castling for that rook
-        if captured_piece.piece_type == Type::Rook {
-            match to {
-                7 => self.castling_cond[0] = false,   // White kingside rook (h1)
-                0 => self.castling_cond[1] = false,   // White queenside rook (a1)
-                63 => self.castling_cond[3] = false,  // Black kingside rook (h8)
-                56 => self.castling_cond[4] = false,  // Black queenside rook (a8)
-                _ => {}
-            }
+        // Update en passant square and move counters
+        let is_capture = captured_piece.piece_type != Type::None
+            || _move.move_type() == MoveType::EnPassant;
+        if moving_piece.piece_type == Type::Pawn || is_capture {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if self.side_to_move == Color::Black {
+            self.fullmove_number += 1;
         }
+        self.en_passant = if moving_piece.piece_type == Type::Pawn && to.abs_diff(from) == 16 {
+            Some(((from + to) / 2) as u8)
+        } else {
+            None
+        };
 
         match _move.move_type(){
             MoveType::Normal => {
@@ -242,6 +250,7 @@ impl Position {
                     Color::Black => to + 8,
                 };
                 let captured_pawn = self.position[captured_pawn_sq];
+                self.zobrist ^= ZOBRIST.piece(captured_pawn, captured_pawn_sq);
 
                 // Remove captured pawn from bitboards
                 self.bitboards.remove_piece(captured_pawn.color, Type::Pawn, captured_pawn_sq);
@@ -254,31 +263,71 @@ impl Position {
                 self.position[from] = Piece::default();
             },
             MoveType::Castling => {
-                // Determine if kingside or queenside castling
-                let is_kingside = to > from;
-
-                // Move the rook based on color and side
-                let (rook_from, rook_to) = match (moving_piece.color, is_kingside) {
-                    (Color::White, true) => (7, 5),   // Kingside: h1 -> f1
-                    (Color::White, false) => (0, 3),  // Queenside: a1 -> d1
-                    (Color::Black, true) => (63, 61), // Kingside: h8 -> f8
-                    (Color::Black, false) => (56, 59),// Queenside: a8 -> d8
-                };
-
-                // Move king in bitboards
-                self.bitboards.move_piece(moving_piece.color, Type::King, from, to);
-                // Move rook in bitboards
-                self.bitboards.move_piece(moving_piece.color, Type::Rook, rook_from, rook_to);
+                // The king "captures" its own rook on `to`; both then land on
+                // their fixed castled squares. Lift both pieces before placing
+                // either, as the squares can overlap in Chess960.
+                let (king_to, rook_to) = _move.castling_destinations();
+                let king = moving_piece;
+                let rook = self.position[to];
+                self.zobrist ^= ZOBRIST.piece(king, from) ^ ZOBRIST.piece(king, king_to);
+                self.zobrist ^= ZOBRIST.piece(rook, to) ^ ZOBRIST.piece(rook, rook_to);
+
+                self.bitboards.remove_piece(king.color, Type::King, from);
+                self.bitboards.remove_piece(rook.color, Type::Rook, to);
+                self.bitboards.add_piece(king.color, Type::King, king_to);
+                self.bitboards.add_piece(rook.color, Type::Rook, rook_to);
 
                 // Update mailbox
-                self.position[to] = self.position[from];
                 self.position[from] = Piece::default();
-                self.position[rook_to] = self.position[rook_from];
-                self.position[rook_from] = Piece::default();
+                self.position[to] = Piece::default();
+                self.position[king_to] = king;
+                self.position[rook_to] = rook;
             }
         }
 
         self.prev_moves.push(_move);
+        self.side_to_move = self.side_to_move.opposite();
+
+        // Hash in the new castling rights, en passant file and side to move
+        self.zobrist ^= ZOBRIST.castling_rights(&self.castling_cond);
+        if let Some(file) = self.en_passant_zobrist_file() {
+            self.zobrist ^= ZOBRIST.en_passant[file];
+        }
+        self.zobrist ^= ZOBRIST.side_to_move;
+    }
+
+    /// Passes the turn without moving a piece (used by null-move pruning).
+    ///
+    /// Clears the en passant square and advances the halfmove clock; undo with
+    /// [`Position::unmake_null_move`].
+    pub fn make_null_move(&mut self) -> UndoInfo {
+        let undo = UndoInfo {
+            captured_piece: Piece::default(),
+            castling_cond: self.castling_cond,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            zobrist: self.zobrist,
+        };
+
+        if let Some(file) = self.en_passant_zobrist_file() {
+            self.zobrist ^= ZOBRIST.en_passant[file];
+        }
+        self.zobrist ^= ZOBRIST.side_to_move;
+        self.en_passant = None;
+        self.halfmove_clock += 1;
+        self.side_to_move = self.side_to_move.opposite();
+        self.debug_assert_zobrist();
+
+        undo
+    }
+
+    /// Reverts a [`Position::make_null_move`]
+    pub fn unmake_null_move(&mut self, undo: UndoInfo) {
+        self.en_passant = undo.en_passant;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.zobrist = undo.zobrist;
+        self.side_to_move = self.side_to_move.opposite();
     }
 
     /// Detects which pieces are pinned to the king and returns pin information
@@ -298,109 +347,30 @@ impl Position {
 
         let occupied = self.bitboards.all_occupied();
         let enemy_color = king_color.opposite();
+        let own_pieces = self.bitboards.occupied_by_color(king_color);
+        let enemy_pieces = occupied & !own_pieces;
 
         // Get enemy sliding pieces for quick type checking
         let enemy_bishops = self.bitboards.pieces_of_type(enemy_color, Type::Bishop);
         let enemy_rooks = self.bitboards.pieces_of_type(enemy_color, Type::Rook);
         let enemy_queens = self.bitboards.pieces_of_type(enemy_color, Type::Queen);
 
-        // Check diagonal pins only if there are diagonal sliders
-        if enemy_bishops | enemy_queens != 0 {
-            for &direction in &[NORTH_EAST, NORTH_WEST, SOUTH_EAST, SOUTH_WEST] {
-                let ray = RAYS[direction][king_square];
-                let blockers_on_ray = ray & occupied;
-
-                if blockers_on_ray == 0 {
-                    continue;  // No pieces on this ray
-                }
-
-                // Find first blocker
-                let first_blocker_sq = if direction == NORTH_EAST || direction == NORTH_WEST {
-                    bitscan_forward(blockers_on_ray)
-                } else {
-                    63 - blockers_on_ray.leading_zeros() as usize
-                };
-
-                let first_piece = self.position[first_blocker_sq];
-
-                // Only interested if first blocker is our own piece
-                if first_piece.color != king_color {
-                    continue;
-                }
-
-                // Remove first blocker and check for second blocker
-                let remaining_blockers = blockers_on_ray & !(1u64 << first_blocker_sq);
-                if remaining_blockers == 0 {
-                    continue;  // No second piece
-                }
-
-                let second_blocker_sq = if direction == NORTH_EAST || direction == NORTH_WEST {
-                    bitscan_forward(remaining_blockers)
-                } else {
-                    63 - remaining_blockers.leading_zeros() as usize
-                };
-
-                // Quick check: is the second blocker an enemy diagonal slider?
-                let second_blocker_bit = 1u64 << second_blocker_sq;
-                if (second_blocker_bit & (enemy_bishops | enemy_queens)) != 0 {
-                    // Pin exists!
-                    pinned_pieces |= 1u64 << first_blocker_sq;
-
-                    // Calculate the pin ray
-                    let ray_from_king = RAYS[direction][king_square];
-                    let ray_from_pinner = RAYS[direction][second_blocker_sq];
-                    pin_rays[first_blocker_sq] = ray_from_king & !(ray_from_pinner);
-                }
-            }
-        }
-
-        // Check orthogonal pins only if there are orthogonal sliders
-        if enemy_rooks | enemy_queens != 0 {
-            for &direction in &[NORTH, SOUTH, EAST, WEST] {
-                let ray = RAYS[direction][king_square];
-                let blockers_on_ray = ray & occupied;
-
-                if blockers_on_ray == 0 {
-                    continue;  // No pieces on this ray
-                }
-
-                // Find first blocker
-                let first_blocker_sq = if direction == NORTH || direction == EAST {
-                    bitscan_forward(blockers_on_ray)
-                } else {
-                    63 - blockers_on_ray.leading_zeros() as usize
-                };
-
-                let first_piece = self.position[first_blocker_sq];
-
-                // Only interested if first blocker is our own piece
-                if first_piece.color != king_color {
-                    continue;
-                }
-
-                // Remove first blocker and check for second blocker
-                let remaining_blockers = blockers_on_ray & !(1u64 << first_blocker_sq);
-                if remaining_blockers == 0 {
-                    continue;  // No second piece
-                }
-
-                let second_blocker_sq = if direction == NORTH || direction == EAST {
-                    bitscan_forward(remaining_blockers)
-                } else {
-                    63 - remaining_blockers.leading_zeros() as usize
-                };
-
-                // Quick check: is the second blocker an enemy orthogonal slider?
-                let second_blocker_bit = 1u64 << second_blocker_sq;
-                if (second_blocker_bit & (enemy_rooks | enemy_queens)) != 0 {
-                    // Pin exists!
-                    pinned_pieces |= 1u64 << first_blocker_sq;
-
-                    // Calculate the pin ray
-                    let ray_from_king = RAYS[direction][king_square];
-                    let ray_from_pinner = RAYS[direction][second_blocker_sq];
-                    pin_rays[first_blocker_sq] = ray_from_king & !(ray_from_pinner);
-                }
+        // Potential pinners: enemy sliders that would see the king if our own
+        // pieces were transparent
+        let mut snipers = (rook_attacks(king_square, enemy_pieces) & (enemy_rooks | enemy_queens))
+            | (bishop_attacks(king_square, enemy_pieces) & (enemy_bishops | enemy_queens));
+
+        while snipers != 0 {
+            let sniper_sq = pop_lsb(&mut snipers);
+            let between = BETWEEN[king_square][sniper_sq] & occupied;
+
+            // Exactly one piece in between, and it must be ours (enemy pieces
+            // were already blockers in the lookup above)
+            if between != 0 && between & (between - 1) == 0 {
+                let pinned_sq = bitscan_forward(between);
+                pinned_pieces |= between;
+                // The pinned piece may move along the ray up to and including the pinner
+                pin_rays[pinned_sq] = BETWEEN[king_square][sniper_sq] | (1u64 << sniper_sq);
             }
         }
 
@@ -444,6 +414,18 @@ impl Position {
     /// Checks if a square is under attack by any piece of the given color
     #[inline]
     pub fn is_square_attacked(&self, square: usize, by_color: Color) -> bool {
+        self.is_square_attacked_with(square, by_color, self.bitboards.all_occupied(), 0)
+    }
+
+    /// Attack test against an arbitrary occupancy
+    ///
+    /// `removed` masks out attackers that no longer exist (a piece captured
+    /// by the move being tested), so legality can be checked without making
+    /// the move.
+    #[inline]
+    fn is_square_attacked_with(&self, square: usize, by_color: Color, occupied: u64, removed: u64) -> bool {
+        let keep = !removed;
+
         // Fast path: check non-sliding pieces first (cheaper)
         // Check for pawn attacks using reverse lookup
         let opposite_color_idx = match by_color {
@@ -451,14 +433,14 @@ impl Position {
             Color::Black => 0,  // Use white's attack pattern (from lower to higher)
         };
         let pawn_attacker_squares = PAWN_ATTACKS[opposite_color_idx][square];
-        let enemy_pawns = self.bitboards.pieces_of_type(by_color, Type::Pawn);
+        let enemy_pawns = self.bitboards.pieces_of_type(by_color, Type::Pawn) & keep;
         if (pawn_attacker_squares & enemy_pawns) != 0 {
             return true;
         }
 
         // Check for knight attacks
         let knight_attackers = KNIGHT_ATTACKS[square];
-        let enemy_knights = self.bitboards.pieces_of_type(by_color, Type::Knight);
+        let enemy_knights = self.bitboards.pieces_of_type(by_color, Type::Knight) & keep;
         if (knight_attackers & enemy_knights) != 0 {
             return true;
         }
@@ -471,63 +453,15 @@ impl Position {
         }
 
         // Check for sliding piece attacks (rook, bishop, queen)
-        // Compute occupied once
-        let occupied = self.bitboards.all_occupied();
-
-        // Get enemy sliding pieces
-        let enemy_bishops = self.bitboards.pieces_of_type(by_color, Type::Bishop);
-        let enemy_rooks = self.bitboards.pieces_of_type(by_color, Type::Rook);
         let enemy_queens = self.bitboards.pieces_of_type(by_color, Type::Queen);
-        let enemy_diagonal_sliders = enemy_bishops | enemy_queens;
-        let enemy_orthogonal_sliders = enemy_rooks | enemy_queens;
-
-        // Early exit if no sliding pieces
-        if enemy_diagonal_sliders == 0 && enemy_orthogonal_sliders == 0 {
-            return false;
-        }
-
-        // Check diagonal attacks (bishop and queen) - only if there are diagonal sliders
-        if enemy_diagonal_sliders != 0 {
-            for &direction in &[NORTH_EAST, NORTH_WEST, SOUTH_EAST, SOUTH_WEST] {
-                let ray = RAYS[direction][square];
-                let blockers = ray & occupied;
-
-                if blockers != 0 {
-                    // Find first blocker in this direction
-                    let blocker_sq = if direction == NORTH_EAST || direction == NORTH_WEST {
-                        bitscan_forward(blockers)
-                    } else {
-                        63 - blockers.leading_zeros() as usize
-                    };
-
-                    // Check if blocker is an enemy diagonal slider
-                    if (enemy_diagonal_sliders & (1u64 << blocker_sq)) != 0 {
-                        return true;
-                    }
-                }
-            }
+        let enemy_diagonal_sliders = (self.bitboards.pieces_of_type(by_color, Type::Bishop) | enemy_queens) & keep;
+        if enemy_diagonal_sliders != 0 && bishop_attacks(square, occupied) & enemy_diagonal_sliders != 0 {
+            return true;
         }
 
-        // Check rank/file attacks (rook and queen) - only if there are orthogonal sliders
-        if enemy_orthogonal_sliders != 0 {
-            for &direction in &[NORTH, SOUTH, EAST, WEST] {
-                let ray = RAYS[direction][square];
-                let blockers = ray & occupied;
-
-                if blockers != 0 {
-                    // Find first blocker in this direction
-                    let blocker_sq = if direction == NORTH || direction == EAST {
-                        bitscan_forward(blockers)
-                    } else {
-                        63 - blockers.leading_zeros() as usize
-                    };
-
-                    // Check if blocker is an enemy orthogonal slider
-                    if (enemy_orthogonal_sliders & (1u64 << blocker_sq)) != 0 {
-                        return true;
-                    }
-                }
-            }
+        let enemy_orthogonal_sliders = (self.bitboards.pieces_of_type(by_color, Type::Rook) | enemy_queens) & keep;
+        if enemy_orthogonal_sliders != 0 && rook_attacks(square, occupied) & enemy_orthogonal_sliders != 0 {
+            return true;
         }
 
         false
@@ -549,22 +483,105 @@ impl Position {
     /// Checks if a move is legal (doesn't leave/put the king in check)
     #[inline]
     pub fn is_move_legal(&self, mv: Move) -> bool {
-        // Get the color of the piece being moved
-        let moving_color = self.position[mv._from()].color;
-
-        // Create a minimal temporary position without cloning prev_moves
-        let mut temp_position = Position {
-            bitboards: self.bitboards,  // Copy bitboards (fast)
-            position: self.position,     // Copy array (stack-allocated, fast)
-            prev_moves: Vec::new(),      // Don't clone the move history
-            castling_cond: self.castling_cond,  // Copy array
+        let from = mv._from();
+        let to = mv._to();
+        let moving = self.position[from];
+
+        // Castling moves two pieces and must also check the squares the king
+        // passes, so play it out on a scratch copy
+        if mv.move_type() == MoveType::Castling {
+            let mut temp_position = self.scratch_copy();
+            temp_position.mk_move(mv);
+            return !temp_position.is_in_check(moving.color);
+        }
+
+        // Everything else only changes occupancy: the mover leaves `from`,
+        // lands on `to`, and removes whatever it captured
+        let captured_sq = match (mv.move_type(), moving.color) {
+            (MoveType::EnPassant, Color::White) => to - 8,
+            (MoveType::EnPassant, Color::Black) => to + 8,
+            _ => to,
+        };
+        let captured = 1u64 << captured_sq;
+        let occupied = (self.bitboards.all_occupied() & !(1u64 << from) & !captured) | (1u64 << to);
+
+        let king_square = if moving.piece_type == Type::King {
+            to
+        } else {
+            let king_bb = self.bitboards.pieces_of_type(moving.color, Type::King);
+            if king_bb == 0 {
+                return true;
+            }
+            bitscan_forward(king_bb)
+        };
+
+        !self.is_square_attacked_with(king_square, moving.color.opposite(), occupied, captured)
+    }
+
+    /// Checks if a move puts the opponent in check, without making it
+    ///
+    /// Covers the moved (or promoted) piece attacking the king directly and
+    /// sliders uncovered by the move, en passant included.
+    pub fn gives_check(&self, mv: Move) -> bool {
+        let from = mv._from();
+        let to = mv._to();
+        let moving = self.position[from];
+        let them = moving.color.opposite();
+
+        // The rook's check is awkward to work out: play castling out
+        if mv.move_type() == MoveType::Castling {
+            let mut temp_position = self.scratch_copy();
+            temp_position.mk_move(mv);
+            return temp_position.is_in_check(them);
+        }
+
+        let king_bb = self.bitboards.pieces_of_type(them, Type::King);
+        if king_bb == 0 {
+            return false;
+        }
+        let king_square = bitscan_forward(king_bb);
+
+        let captured_sq = match (mv.move_type(), moving.color) {
+            (MoveType::EnPassant, Color::White) => to - 8,
+            (MoveType::EnPassant, Color::Black) => to + 8,
+            _ => to,
+        };
+        let occupied = (self.bitboards.all_occupied() & !(1u64 << from) & !(1u64 << captured_sq)) | (1u64 << to);
+
+        // Direct check from the destination square
+        let piece_type = mv.move_type().promotion_piece().unwrap_or(moving.piece_type);
+        let attacks = match piece_type {
+            Type::Pawn => PAWN_ATTACKS[if moving.color == Color::White { 0 } else { 1 }][to],
+            Type::Knight => KNIGHT_ATTACKS[to],
+            Type::Bishop => bishop_attacks(to, occupied),
+            Type::Rook => rook_attacks(to, occupied),
+            Type::Queen => bishop_attacks(to, occupied) | rook_attacks(to, occupied),
+            Type::King | Type::None => 0,
         };
+        if attacks & king_bb != 0 {
+            return true;
+        }
 
-        // Execute the move on the temporary position
-        temp_position.mk_move(mv);
+        // Discovered check: the mover no longer attacks from `from`
+        self.is_square_attacked_with(king_square, moving.color, occupied, 1u64 << from)
+    }
 
-        // Check if the king is in check after the move
-        !temp_position.is_in_check(moving_color)
+    /// A copy of the position without the move history, for playing a move
+    /// out and looking at the result
+    fn scratch_copy(&self) -> Position {
+        Position {
+            bitboards: self.bitboards,
+            position: self.position,
+            prev_moves: Vec::new(),
+            side_to_move: self.side_to_move,
+            castling_cond: self.castling_cond,
+            castling_squares: self.castling_squares,
+            chess960: self.chess960,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            zobrist: self.zobrist,
+        }
     }
 
     /// Checks if the given color has ANY legal moves available
@@ -599,8 +616,32 @@ impl Position {
         !self.is_in_check(color) && !self.has_legal_moves(color)
     }
 
+    /// Whether a draw can be claimed under the fifty-move rule
+    /// (fifty moves by each side without a capture or pawn move)
+    pub fn can_claim_fifty_move_draw(&self) -> bool {
+        self.halfmove_clock >= 100
+    }
+
+    /// Whether the game is automatically drawn under the seventy-five-move rule
+    pub fn is_seventy_five_move_draw(&self) -> bool {
+        self.halfmove_clock >= 150
+    }
+
     /// Converts the current position to FEN notation
+    ///
+    /// Castling rights are written as X-FEN, which is plain `KQkq` for
+    /// standard chess and only uses rook files for ambiguous Chess960 rights.
     pub fn to_fen(&self) -> String {
+        self.write_fen(false)
+    }
+
+    /// Converts the current position to Shredder-FEN, which writes every
+    /// castling right as the file of its rook (`HAha` for the standard setup)
+    pub fn to_shredder_fen(&self) -> String {
+        self.write_fen(true)
+    }
+
+    fn write_fen(&self, shredder: bool) -> String {
         let mut fen = String::new();
 
         // Piece placement (starting from rank 8 down to rank 1)
@@ -646,63 +687,25 @@ impl Position {
             }
         }
 
-        // Side to move (even number of moves = white, odd = black)
-        let side_to_move = if self.prev_moves.len().is_multiple_of(2) { "w" } else { "b" };
+        // Side to move
+        let side_to_move = match self.side_to_move {
+            Color::White => "w",
+            Color::Black => "b",
+        };
         fen.push_str(&format!(" {}", side_to_move));
 
         // Castling availability
-        let mut castling = String::new();
-        if self.castling_cond[2] {  // White king hasn't moved
-            if self.castling_cond[0] {  // White kingside rook
-                castling.push('K');
-            }
-            if self.castling_cond[1] {  // White queenside rook
-                castling.push('Q');
-            }
-        }
-        if self.castling_cond[5] {  // Black king hasn't moved
-            if self.castling_cond[3] {  // Black kingside rook
-                castling.push('k');
-            }
-            if self.castling_cond[4] {  // Black queenside rook
-                castling.push('q');
-            }
-        }
-        if castling.is_empty() {
-            castling.push('-');
-        }
-        fen.push_str(&format!(" {}", castling));
+        fen.push_str(&format!(" {}", super::fen::castling_field(self, shredder)));
 
         // En passant square
-        let en_passant = if let Some(last_move) = self.prev_moves.last() {
-            let from = last_move._from();
-            let to = last_move._to();
-            let moved_piece = self.position[to];
-
-            // Check if it was a pawn double move
-            if moved_piece.piece_type == Type::Pawn {
-                let distance = to.abs_diff(from);
-                if distance == 16 {
-                    // En passant square is between from and to
-                    let ep_square = (from + to) / 2;
-                    let file = (ep_square % 8) as u8;
-                    let rank = (ep_square / 8) as u8;
-                    let file_char = (b'a' + file) as char;
-                    let rank_char = (b'1' + rank) as char;
-                    format!("{}{}", file_char, rank_char)
-                } else {
-                    "-".to_string()
-                }
-            } else {
-                "-".to_string()
-            }
-        } else {
-            "-".to_string()
+        let en_passant = match self.en_passant {
+            Some(sq) => square_to_algebraic(sq as usize),
+            None => "-".to_string(),
         };
         fen.push_str(&format!(" {}", en_passant));
 
-        // Halfmove clock and fullmove number (simplified)
-        fen.push_str(" 0 1");
+        // Halfmove clock and fullmove number
+        fen.push_str(&format!(" {} {}", self.halfmove_clock, self.fullmove_number));
 
         fen
     }
@@ -712,11 +715,7 @@ impl Position {
     pub fn all_legal_moves_into(&self, moves: &mut SmallVec<[Move; 64]>) {
         moves.clear();
 
-        let current_side = if self.prev_moves.len().is_multiple_of(2) {
-            Color::White
-        } else {
-            Color::Black
-        };
+        let current_side = self.side_to_move;
 
         // Detect pins once for the entire position
         let in_check = self.is_in_check(current_side);
@@ -797,14 +796,29 @@ impl Position {
         all_moves
     }
 
+    /// Whether a move captures an enemy piece (including en passant)
+    ///
+    /// Castling lands on the king's own rook, so checking the destination
+    /// square alone is not enough.
+    #[inline]
+    pub fn is_capture(&self, mv: Move) -> bool {
+        match mv.move_type() {
+            MoveType::EnPassant => true,
+            MoveType::Castling => false,
+            _ => self.position[mv._to()].piece_type != Type::None,
+        }
+    }
+
     /// Makes a move and returns undo information
     /// This is more efficient than cloning the position
     pub fn make_move_undoable(&mut self, mv: Move) -> UndoInfo {
         let to = mv._to();
         let from = mv._from();
 
-        // For en passant, the captured piece is not at the 'to' square
+        // For en passant, the captured piece is not at the 'to' square, and
+        // castling captures nothing even though its own rook sits there
         let captured_piece = match mv.move_type() {
+            MoveType::Castling => Piece::default(),
             MoveType::EnPassant => {
                 match self.position[from].color {
                     Color::White => self.position[to - 8],
@@ -817,9 +831,14 @@ impl Position {
         let undo = UndoInfo {
             captured_piece,
             castling_cond: self.castling_cond,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            zobrist: self.zobrist,
         };
 
         self.mk_move(mv);
+        self.debug_assert_zobrist();
 
         undo
     }
@@ -829,11 +848,16 @@ impl Position {
         let from = mv._from();
         let to = mv._to();
 
-        // Restore castling conditions
+        // Restore castling conditions, en passant square and move counters
         self.castling_cond = undo.castling_cond;
+        self.en_passant = undo.en_passant;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.fullmove_number = undo.fullmove_number;
+        self.zobrist = undo.zobrist;
 
-        // Remove the move from history
+        // Remove the move from history and hand the turn back
         self.prev_moves.pop();
+        self.side_to_move = self.side_to_move.opposite();
 
         // Reverse the move based on type
         match mv.move_type() {
@@ -873,25 +897,21 @@ impl Position {
                 self.position[captured_sq] = undo.captured_piece;
             },
             MoveType::Castling => {
-                let king = self.position[to];
-                let is_kingside = to > from;
-                let (rook_from, rook_to) = match (king.color, is_kingside) {
-                    (Color::White, true) => (7, 5),
-                    (Color::White, false) => (0, 3),
-                    (Color::Black, true) => (63, 61),
-                    (Color::Black, false) => (56, 59),
-                };
+                let (king_to, rook_to) = mv.castling_destinations();
+                let king = self.position[king_to];
+                let rook = self.position[rook_to];
 
-                // Reverse king move in bitboards
-                self.bitboards.move_piece(king.color, Type::King, to, from);
-                // Reverse rook move in bitboards
-                self.bitboards.move_piece(king.color, Type::Rook, rook_to, rook_from);
+                // Reverse both pieces in bitboards
+                self.bitboards.remove_piece(king.color, Type::King, king_to);
+                self.bitboards.remove_piece(rook.color, Type::Rook, rook_to);
+                self.bitboards.add_piece(king.color, Type::King, from);
+                self.bitboards.add_piece(rook.color, Type::Rook, to);
 
                 // Update mailbox
-                self.position[from] = self.position[to];
-                self.position[to] = Piece::default();
-                self.position[rook_from] = self.position[rook_to];
+                self.position[king_to] = Piece::default();
                 self.position[rook_to] = Piece::default();
+                self.position[from] = king;
+                self.position[to] = rook;
             },
             MoveType::PromotionQueen | MoveType::PromotionRook |
             MoveType::PromotionBishop | MoveType::PromotionKnight => {
@@ -967,20 +987,11 @@ impl Position {
         let mut pos = self.clone();  // Clone once
 
         for mv in moves {
-            let from = mv._from();
-            let to = mv._to();
-
-            // Convert indices to algebraic notation
-            let from_file = (b'a' + (from % 8) as u8) as char;
-            let from_rank = (b'1' + (from / 8) as u8) as char;
-            let to_file = (b'a' + (to % 8) as u8) as char;
-            let to_rank = (b'1' + (to / 8) as u8) as char;
-
             let undo = pos.make_move_undoable(mv);
             let count = pos.perft(depth - 1);
             pos.unmake_move(mv, undo);
 
-            println!("{}{}{}{}: {}", from_file, from_rank, to_file, to_rank, count);
+            println!("{}: {}", self.move_to_uci(mv), count);
             total += count;
         }
 
@@ -0,0 +1,270 @@
+//! Standard Algebraic Notation (SAN) for moves.
+//!
+//! [`Position::move_to_san`] writes the canonical form used in PGN and move
+//! lists (`Nbd7`, `exd5`, `e8=Q+`, `O-O-O#`). [`Position::parse_san`] reads it
+//! back and is deliberately lenient with hand-typed input: it accepts `0-0`
+//! for castling, a missing or superfluous `x`, `e8Q` for `e8=Q`, and ignores
+//! check marks and annotations such as `+`, `#`, `!` or `?`.
+
+use super::*;
+use std::fmt;
+
+/// Error returned by [`Position::parse_san`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SanError {
+    /// The string is empty (or only check marks and annotations)
+    Empty,
+    /// The string is not valid SAN
+    InvalidSyntax,
+    /// No legal move matches the notation
+    IllegalMove,
+    /// More than one legal move matches the notation
+    AmbiguousMove,
+}
+
+impl fmt::Display for SanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            SanError::Empty => "empty move",
+            SanError::InvalidSyntax => "not a valid SAN move",
+            SanError::IllegalMove => "no legal move matches",
+            SanError::AmbiguousMove => "more than one legal move matches",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl std::error::Error for SanError {}
+
+/// SAN letter of a piece type (pawns have none)
+fn piece_letter(piece_type: Type) -> Option<char> {
+    match piece_type {
+        Type::King => Some('K'),
+        Type::Queen => Some('Q'),
+        Type::Rook => Some('R'),
+        Type::Bishop => Some('B'),
+        Type::Knight => Some('N'),
+        Type::Pawn | Type::None => None,
+    }
+}
+
+fn piece_from_letter(c: char) -> Option<Type> {
+    match c {
+        'K' => Some(Type::King),
+        'Q' => Some(Type::Queen),
+        'R' => Some(Type::Rook),
+        'B' => Some(Type::Bishop),
+        'N' => Some(Type::Knight),
+        _ => None,
+    }
+}
+
+/// Promotion move type for a promotion letter (either case)
+fn promotion_from_letter(c: char) -> Option<MoveType> {
+    match c.to_ascii_uppercase() {
+        'Q' => Some(MoveType::PromotionQueen),
+        'R' => Some(MoveType::PromotionRook),
+        'B' => Some(MoveType::PromotionBishop),
+        'N' => Some(MoveType::PromotionKnight),
+        _ => None,
+    }
+}
+
+impl Position {
+    /// Writes a legal move in Standard Algebraic Notation
+    ///
+    /// The move must be legal in this position; the result is unspecified
+    /// otherwise. Includes the `+`/`#` suffix, so this plays the move on a
+    /// copy of the position.
+    pub fn move_to_san(&self, mv: Move) -> String {
+        let mut san = self.san_without_suffix(mv);
+
+        let mut after = self.clone();
+        after.make_move_undoable(mv);
+        let opponent = after.side_to_move;
+        if after.is_in_check(opponent) {
+            san.push(if after.has_legal_moves(opponent) { '+' } else { '#' });
+        }
+
+        san
+    }
+
+    /// Writes a line of legal moves starting from this position in SAN with
+    /// move numbers, e.g. `12... Nf6 13. e4 d5`
+    pub fn line_to_san(&self, line: &[Move]) -> String {
+        let mut pos = self.clone();
+        let mut text = String::new();
+        for (index, &mv) in line.iter().enumerate() {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            match pos.side_to_move {
+                Color::White => text.push_str(&format!("{}. ", pos.fullmove_number)),
+                Color::Black if index == 0 => text.push_str(&format!("{}... ", pos.fullmove_number)),
+                Color::Black => {}
+            }
+            text.push_str(&pos.move_to_san(mv));
+            pos.mk_move(mv);
+        }
+        text
+    }
+
+    fn san_without_suffix(&self, mv: Move) -> String {
+        if mv.move_type() == MoveType::Castling {
+            return if mv._to() > mv._from() { "O-O" } else { "O-O-O" }.to_string();
+        }
+
+        let from = mv._from();
+        let to = mv._to();
+        let piece_type = self.position[from].piece_type;
+        let mut san = String::new();
+
+        match piece_letter(piece_type) {
+            Some(letter) => {
+                san.push(letter);
+                san.push_str(&self.disambiguation(mv, piece_type));
+                if self.is_capture(mv) {
+                    san.push('x');
+                }
+            }
+            None => {
+                // Pawn captures always name the file the pawn came from
+                if self.is_capture(mv) {
+                    san.push((b'a' + (from % 8) as u8) as char);
+                    san.push('x');
+                }
+            }
+        }
+
+        san.push_str(&square_to_algebraic(to));
+
+        if let Some(promoted) = mv.move_type().promotion_piece() {
+            san.push('=');
+            san.extend(piece_letter(promoted));
+        }
+
+        san
+    }
+
+    /// Shortest origin hint (file, rank or full square) that tells `mv` apart
+    /// from other legal moves of the same piece type to the same square
+    fn disambiguation(&self, mv: Move, piece_type: Type) -> String {
+        let from = mv._from();
+        let rivals: Vec<usize> = self
+            .all_legal_moves()
+            .iter()
+            .filter(|other| {
+                other._to() == mv._to()
+                    && other._from() != from
+                    && other.move_type() != MoveType::Castling
+                    && self.position[other._from()].piece_type == piece_type
+            })
+            .map(|other| other._from())
+            .collect();
+
+        let square = square_to_algebraic(from);
+        if rivals.is_empty() {
+            String::new()
+        } else if rivals.iter().all(|&sq| sq % 8 != from % 8) {
+            square[..1].to_string()
+        } else if rivals.iter().all(|&sq| sq / 8 != from / 8) {
+            square[1..].to_string()
+        } else {
+            square
+        }
+    }
+
+    /// Parses a move in Standard Algebraic Notation against the legal moves
+    /// of this position
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+        let text = san
+            .trim()
+            .trim_end_matches(['+', '#', '!', '?']);
+        if text.is_empty() {
+            return Err(SanError::Empty);
+        }
+
+        let legal = self.all_legal_moves();
+
+        // Castling, with letter O or digit zero
+        let castling_side = match text {
+            "O-O" | "0-0" => Some(true),
+            "O-O-O" | "0-0-0" => Some(false),
+            _ => None,
+        };
+        if let Some(kingside) = castling_side {
+            return single_match(legal.iter().copied().filter(|mv| {
+                mv.move_type() == MoveType::Castling && (mv._to() > mv._from()) == kingside
+            }));
+        }
+
+        let mut chars: Vec<char> = text.chars().collect();
+
+        let piece_type = match chars.first().copied().and_then(piece_from_letter) {
+            Some(piece_type) => {
+                chars.remove(0);
+                piece_type
+            }
+            None => Type::Pawn,
+        };
+
+        // Promotion suffix: "=Q" or just "Q" after the destination rank
+        let mut promotion = None;
+        if piece_type == Type::Pawn && chars.len() >= 3 {
+            let last = chars[chars.len() - 1];
+            if let Some(promo) = promotion_from_letter(last) {
+                if chars[chars.len() - 2] == '=' {
+                    chars.truncate(chars.len() - 2);
+                    promotion = Some(promo);
+                } else if chars[chars.len() - 2].is_ascii_digit() {
+                    chars.truncate(chars.len() - 1);
+                    promotion = Some(promo);
+                }
+            }
+        }
+
+        if chars.len() < 2 {
+            return Err(SanError::InvalidSyntax);
+        }
+        let target: String = chars[chars.len() - 2..].iter().collect();
+        let to = algebraic_to_square(&target).ok_or(SanError::InvalidSyntax)?;
+        chars.truncate(chars.len() - 2);
+
+        // Whatever is left is an optional origin file and/or rank, and an
+        // optional capture mark (not checked against the board)
+        if chars.last() == Some(&'x') {
+            chars.pop();
+        }
+        let mut from_file = None;
+        let mut from_rank = None;
+        match chars.as_slice() {
+            [] => {}
+            [f @ 'a'..='h'] => from_file = Some(*f as usize - 'a' as usize),
+            [r @ '1'..='8'] => from_rank = Some(*r as usize - '1' as usize),
+            [f @ 'a'..='h', r @ '1'..='8'] => {
+                from_file = Some(*f as usize - 'a' as usize);
+                from_rank = Some(*r as usize - '1' as usize);
+            }
+            _ => return Err(SanError::InvalidSyntax),
+        }
+
+        single_match(legal.iter().copied().filter(|mv| {
+            let from = mv._from();
+            mv.move_type() != MoveType::Castling
+                && mv._to() == to
+                && self.position[from].piece_type == piece_type
+                && from_file.is_none_or(|file| from % 8 == file)
+                && from_rank.is_none_or(|rank| from / 8 == rank)
+                && mv.move_type().promotion_piece() == promotion.and_then(|p| p.promotion_piece())
+        }))
+    }
+}
+
+/// The only move yielded, or why there is not exactly one
+fn single_match(mut moves: impl Iterator<Item = Move>) -> Result<Move, SanError> {
+    match (moves.next(), moves.next()) {
+        (Some(mv), None) => Ok(mv),
+        (None, _) => Err(SanError::IllegalMove),
+        (Some(_), Some(_)) => Err(SanError::AmbiguousMove),
+    }
+}
@@ -0,0 +1,117 @@
+//! Static exchange evaluation (SEE).
+//!
+//! [`Position::see`] works out what a capture wins or loses once every
+//! recapture on its target square has been played, each side capturing with
+//! its least valuable attacker and free to stop when going on would lose
+//! material. Attackers come from the bitboard attack tables, recomputed
+//! against an occupancy that loses each piece as it captures, so rooks,
+//! bishops and queens lined up behind it (x-rays) join the exchange.
+
+use super::*;
+use super::bitboards::tables::*;
+
+/// Exchange value of a piece type in centipawns
+///
+/// The king never gets captured: it only takes part when the other side has
+/// no attacker left.
+fn see_value(piece_type: Type) -> i32 {
+    match piece_type {
+        Type::Pawn => 100,
+        Type::Knight => 300,
+        Type::Bishop => 320,
+        Type::Rook => 500,
+        Type::Queen => 900,
+        Type::King => 20_000,
+        Type::None => 0,
+    }
+}
+
+/// Piece types from least to most valuable
+const EXCHANGE_ORDER: [Type; 6] = [Type::Pawn, Type::Knight, Type::Bishop, Type::Rook, Type::Queen, Type::King];
+
+impl Position {
+    /// Material the side to move wins with `mv` after the exchange on its
+    /// target square, in centipawns; negative if the move loses material
+    ///
+    /// Quiet moves count what the moving piece risks on its new square, and
+    /// promotions count the promoted piece. Castling is always 0.
+    pub fn see(&self, mv: Move) -> i32 {
+        if mv.move_type() == MoveType::Castling {
+            return 0;
+        }
+
+        let from = mv._from();
+        let to = mv._to();
+        let mut side = self.position[from].color;
+        let mut occupied = self.bitboards.all_occupied() & !(1u64 << from);
+
+        // Balance after each capture, from the point of view of its side
+        let mut gain = [0i32; 32];
+        let mut piece_on_target = self.position[from].piece_type;
+        gain[0] = see_value(self.position[to].piece_type);
+        if mv.move_type() == MoveType::EnPassant {
+            let captured = if side == Color::White { to - 8 } else { to + 8 };
+            occupied &= !(1u64 << captured);
+            gain[0] = see_value(Type::Pawn);
+        }
+        if let Some(promoted) = mv.move_type().promotion_piece() {
+            gain[0] += see_value(promoted) - see_value(Type::Pawn);
+            piece_on_target = promoted;
+        }
+
+        let mut attackers = self.attackers_to(to, occupied);
+        let mut depth = 0;
+        loop {
+            side = side.opposite();
+            let own = attackers & self.bitboards.occupied_by_color(side);
+            let Some((square, piece_type)) = EXCHANGE_ORDER.iter().find_map(|&piece_type| {
+                let pieces = own & self.bitboards.pieces_of_type(side, piece_type);
+                (pieces != 0).then(|| (bitscan_forward(pieces), piece_type))
+            }) else {
+                break;
+            };
+
+            // The king cannot recapture into an attack
+            if piece_type == Type::King && attackers & !own != 0 {
+                break;
+            }
+
+            depth += 1;
+            gain[depth] = see_value(piece_on_target) - gain[depth - 1];
+            piece_on_target = piece_type;
+
+            // Whatever was lined up behind the capturing piece joins in
+            occupied &= !(1u64 << square);
+            attackers = self.attackers_to(to, occupied);
+        }
+
+        // Each side stops the exchange where that suits it best
+        while depth > 0 {
+            gain[depth - 1] = -(-gain[depth - 1]).max(gain[depth]);
+            depth -= 1;
+        }
+        gain[0]
+    }
+
+    /// Whether [`see`](Self::see) of `mv` is at least `threshold`
+    pub fn see_ge(&self, mv: Move, threshold: i32) -> bool {
+        self.see(mv) >= threshold
+    }
+
+    /// Pieces of both colors on `occupied` attacking `square`
+    fn attackers_to(&self, square: usize, occupied: u64) -> u64 {
+        let bb = &self.bitboards;
+        let queens = bb.pieces_of_type(Color::White, Type::Queen) | bb.pieces_of_type(Color::Black, Type::Queen);
+        let diagonal = bb.pieces_of_type(Color::White, Type::Bishop) | bb.pieces_of_type(Color::Black, Type::Bishop) | queens;
+        let orthogonal = bb.pieces_of_type(Color::White, Type::Rook) | bb.pieces_of_type(Color::Black, Type::Rook) | queens;
+
+        // A white pawn attacks `square` from where a black pawn on it would attack
+        let attackers = (PAWN_ATTACKS[1][square] & bb.pieces_of_type(Color::White, Type::Pawn))
+            | (PAWN_ATTACKS[0][square] & bb.pieces_of_type(Color::Black, Type::Pawn))
+            | (KNIGHT_ATTACKS[square] & (bb.pieces_of_type(Color::White, Type::Knight) | bb.pieces_of_type(Color::Black, Type::Knight)))
+            | (KING_ATTACKS[square] & (bb.pieces_of_type(Color::White, Type::King) | bb.pieces_of_type(Color::Black, Type::King)))
+            | (bishop_attacks(square, occupied) & diagonal)
+            | (rook_attacks(square, occupied) & orthogonal);
+        attackers & occupied
+    }
+}
@@ -127,3 +127,26 @@ fn test_pinned_piece_cannot_move() {
         assert!(!temp_pos.is_in_check(Color::White), "Pinned piece moves must not expose king");
     }
 }
+
+#[test]
+fn test_gives_check_matches_playing_the_move() {
+    // Direct and discovered checks, en passant uncovering a rook, checking
+    // promotions and castling into check
+    let fens = [
+        "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4",
+        "4k3/8/8/8/4N3/8/4R3/4K3 w - - 0 1",
+        "8/8/8/R2pP2k/8/8/8/4K3 w - d6 0 1",
+        "4k3/1P6/8/8/8/8/8/4K3 w - - 0 1",
+        "5k2/8/8/8/8/8/8/4K2R w K - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    ];
+    for fen in fens {
+        let pos = Position::from_fen(fen);
+        for mv in pos.all_legal_moves() {
+            let mut played = pos.clone();
+            played.mk_move(mv);
+            let expected = played.is_in_check(pos.side_to_move.opposite());
+            assert_eq!(pos.gives_check(mv), expected, "{:?} in {}", mv, fen);
+        }
+    }
+}
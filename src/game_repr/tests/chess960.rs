@@ -0,0 +1,137 @@
+// Chess960 Tests
+//
+// This module covers Chess960 castling (king takes own rook, overlapping
+// squares, hidden checks) and X-FEN/Shredder-FEN castling fields.
+
+use super::*;
+
+fn castles(pos: &Position) -> Vec<Move> {
+    pos.all_legal_moves()
+        .into_iter()
+        .filter(|mv| mv.move_type() == MoveType::Castling)
+        .collect()
+}
+
+// ==================== FEN CASTLING FIELDS ====================
+
+#[test]
+fn test_shredder_fen_castling_squares() {
+    let pos = Position::try_from_fen("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9").unwrap();
+
+    assert!(pos.chess960);
+    assert_eq!(pos.castling_cond, [true; 6]);
+    // h1, f1, g1, h8, f8, g8
+    assert_eq!(pos.castling_squares, [7, 5, 6, 63, 61, 62]);
+}
+
+#[test]
+fn test_xfen_and_shredder_fen_round_trip() {
+    let shredder = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+    let xfen = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9";
+
+    let from_shredder = Position::try_from_fen(shredder).unwrap();
+    let from_xfen = Position::try_from_fen(xfen).unwrap();
+
+    assert_eq!(from_shredder.castling_squares, from_xfen.castling_squares);
+    assert_eq!(from_shredder.to_fen(), xfen);
+    assert_eq!(from_xfen.to_shredder_fen(), shredder);
+}
+
+#[test]
+fn test_xfen_names_inner_rook_by_file() {
+    // Two white rooks on the kingside: the right belongs to the inner one on f1
+    let pos = Position::try_from_fen("4k3/8/8/8/8/8/8/1K3R1R w F - 0 1").unwrap();
+
+    assert_eq!(pos.castling_squares[0], 5);
+    assert_eq!(pos.to_fen(), "4k3/8/8/8/8/8/8/1K3R1R w F - 0 1");
+
+    // KQkq always picks the outermost rook
+    let pos = Position::try_from_fen("4k3/8/8/8/8/8/8/1K3R1R w K - 0 1").unwrap();
+    assert_eq!(pos.castling_squares[0], 7);
+}
+
+#[test]
+fn test_standard_position_shredder_fen() {
+    let pos = Position::default();
+
+    assert!(!pos.chess960);
+    assert_eq!(pos.to_shredder_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1");
+    assert_eq!(Position::try_from_fen(&pos.to_shredder_fen()).unwrap().castling_squares, STANDARD_CASTLING_SQUARES);
+}
+
+#[test]
+fn test_castling_right_without_rook_rejected() {
+    let result = Position::try_from_fen("4k3/8/8/8/8/8/8/1K3R2 w G - 0 1");
+    assert_eq!(result.err().map(|e| e.kind), Some(FenErrorKind::ImpossibleCastling('G')));
+}
+
+// ==================== CASTLING MOVES ====================
+
+#[test]
+fn test_king_already_on_destination() {
+    // King on g1 castles kingside by only moving the h1 rook to f1
+    let mut pos = Position::from_fen("6k1/8/8/8/8/8/8/6KR w H - 0 1");
+    let moves = castles(&pos);
+    assert_eq!(moves, vec![Move::new(6, 7, MoveType::Castling)]);
+
+    let undo = pos.make_move_undoable(moves[0]);
+    assert_eq!(pos.position[6].piece_type, Type::King);
+    assert_eq!(pos.position[5].piece_type, Type::Rook);
+    assert!(pos.position[7].is_none());
+    assert_eq!(pos.zobrist, pos.compute_zobrist());
+
+    pos.unmake_move(moves[0], undo);
+    assert_eq!(pos.to_fen(), "6k1/8/8/8/8/8/8/6KR w K - 0 1");
+}
+
+#[test]
+fn test_king_and_rook_swap_squares() {
+    // King f1 and rook g1: after kingside castling they trade places
+    let mut pos = Position::from_fen("k7/8/8/8/8/8/8/5KR1 w G - 0 1");
+    let castle = Move::new(5, 6, MoveType::Castling);
+    assert!(castles(&pos).contains(&castle));
+
+    let undo = pos.make_move_undoable(castle);
+    assert_eq!(pos.position[6].piece_type, Type::King);
+    assert_eq!(pos.position[5].piece_type, Type::Rook);
+    pos.unmake_move(castle, undo);
+    assert_eq!(pos.position[5].piece_type, Type::King);
+    assert_eq!(pos.position[6].piece_type, Type::Rook);
+}
+
+#[test]
+fn test_rook_destination_must_be_empty() {
+    // Queenside: king b1 -> c1, rook a1 -> d1, but d1 is occupied
+    let pos = Position::from_fen("6k1/8/8/8/8/8/8/RK1N4 w A - 0 1");
+    assert!(castles(&pos).is_empty());
+}
+
+#[test]
+fn test_castling_rook_shielding_king_is_illegal() {
+    // The b1 rook blocks the a1 queen; castling queenside moves it to d1 and
+    // leaves the king on c1 in check, while kingside castling stays legal
+    let pos = Position::from_fen("k7/8/8/8/8/8/8/qRK4R w HB - 0 1");
+    let moves = castles(&pos);
+
+    assert!(!moves.contains(&Move::new(2, 1, MoveType::Castling)));
+    assert!(moves.contains(&Move::new(2, 7, MoveType::Castling)));
+}
+
+#[test]
+fn test_castling_is_not_a_capture() {
+    let mut pos = Position::from_fen("6k1/8/8/8/8/8/8/6KR w H - 5 20");
+    let castle = Move::new(6, 7, MoveType::Castling);
+
+    assert!(!pos.is_capture(castle));
+    pos.mk_move(castle);
+    assert_eq!(pos.halfmove_clock, 6);
+}
+
+#[test]
+fn test_other_rook_blocks_rook_destination() {
+    // Setup 0 is BBQNNRKR: the king already stands on g1, but kingside
+    // castling would put the h1 rook on f1, where the other rook is
+    let pos = Position::chess960(0).unwrap();
+    assert_eq!(pos.castling_squares, [7, 5, 6, 63, 61, 62]);
+    assert!(castles(&pos).is_empty());
+}
@@ -0,0 +1,54 @@
+// Draw Rule Tests
+//
+// This module contains tests for the fifty-move and seventy-five-move rules,
+// which depend on the halfmove clock maintained by make/unmake.
+
+use super::*;
+
+// ==================== FIFTY-MOVE RULE TESTS ====================
+
+#[test]
+fn test_fifty_move_claim_threshold() {
+    let pos = Position::from_fen("r3k3/8/8/8/8/8/8/R3K3 w - - 99 80");
+    assert!(!pos.can_claim_fifty_move_draw());
+
+    let pos = Position::from_fen("r3k3/8/8/8/8/8/8/R3K3 w - - 100 80");
+    assert!(pos.can_claim_fifty_move_draw());
+    assert!(!pos.is_seventy_five_move_draw());
+}
+
+#[test]
+fn test_quiet_move_reaches_fifty_move_claim() {
+    let mut pos = Position::from_fen("r3k3/8/8/8/8/8/8/R3K3 w - - 99 80");
+
+    let mv = Move::new(0, 1, MoveType::Normal);
+    let undo = pos.make_move_undoable(mv);
+    assert_eq!(pos.halfmove_clock, 100);
+    assert!(pos.can_claim_fifty_move_draw());
+
+    pos.unmake_move(mv, undo);
+    assert_eq!(pos.halfmove_clock, 99);
+    assert!(!pos.can_claim_fifty_move_draw());
+}
+
+#[test]
+fn test_capture_resets_halfmove_clock() {
+    let mut pos = Position::from_fen("r3k3/8/8/8/8/8/8/R3K3 w - - 99 80");
+
+    // Rook takes rook on a8
+    pos.mk_move(Move::new(0, 56, MoveType::Normal));
+    assert_eq!(pos.halfmove_clock, 0);
+    assert!(!pos.can_claim_fifty_move_draw());
+}
+
+// ==================== SEVENTY-FIVE-MOVE RULE TESTS ====================
+
+#[test]
+fn test_seventy_five_move_threshold() {
+    let pos = Position::from_fen("r3k3/8/8/8/8/8/8/R3K3 b - - 149 120");
+    assert!(pos.can_claim_fifty_move_draw());
+    assert!(!pos.is_seventy_five_move_draw());
+
+    let pos = Position::from_fen("r3k3/8/8/8/8/8/8/R3K3 b - - 150 120");
+    assert!(pos.is_seventy_five_move_draw());
+}
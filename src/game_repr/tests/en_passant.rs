@@ -114,52 +114,52 @@ fn test_en_passant_execution() {
 
 #[test]
 fn test_en_passant_direction_white() {
-    // White pawn at d4, black pawn moves c7->c5 (double move next to white pawn)
-    // White pawn at d4 should capture to c6 (toward enemy), not somewhere else
+    // White pawn at d5, black pawn moves c7->c5 (double move next to white pawn)
+    // White pawn at d5 should capture to c6 (toward enemy), not somewhere else
     let mut pos = empty_board();
 
-    // Place white pawn at d4 (index 27 = row 3, col 3)
-    place_piece(&mut pos, 27, Piece { color: Color::White, piece_type: Type::Pawn });
+    // Place white pawn at d5 (index 35 = row 4, col 3)
+    place_piece(&mut pos, 35, Piece { color: Color::White, piece_type: Type::Pawn });
 
-    // Place black pawn at c4 (index 26 = row 3, col 2) - adjacent to white pawn
-    place_piece(&mut pos, 26, Piece { color: Color::Black, piece_type: Type::Pawn });
+    // Place black pawn at c5 (index 34 = row 4, col 2) - adjacent to white pawn
+    place_piece(&mut pos, 34, Piece { color: Color::Black, piece_type: Type::Pawn });
 
-    // Simulate the previous move: c6->c4 (index 42->26, distance = 16)
-    pos.prev_moves = vec![Move::new(42, 26, MoveType::Normal)];
+    // Simulate the previous move c7->c5: en passant target is c6 (index 42)
+    pos.en_passant = Some(42);
 
     // Place kings
     place_piece(&mut pos, 4, Piece { color: Color::White, piece_type: Type::King });
     place_piece(&mut pos, 60, Piece { color: Color::Black, piece_type: Type::King });
 
-    let moves = pos.legal_moves(27);
+    let moves = pos.legal_moves(35);
 
-    // Should be able to capture via en passant to c5 (index 34)
-    // White pawn at d4 (27) captures left-forward with offset 7: 27 + 7 = 34 (c5)
-    assert!(has_move(&moves, 27, 34), "White pawn at d4 should capture LEFT to c5 via en passant");
+    // Should be able to capture via en passant to c6 (index 42)
+    // White pawn at d5 (35) captures left-forward with offset 7: 35 + 7 = 42 (c6)
+    assert!(has_move(&moves, 35, 42), "White pawn at d5 should capture LEFT to c6 via en passant");
 }
 
 #[test]
 fn test_en_passant_direction_black() {
-    // Black pawn at d5, white pawn moves e3->e5 (double move next to black pawn)
-    // Black pawn at d5 should capture to e4 (toward enemy), not somewhere else
+    // Black pawn at d4, white pawn moves e2->e4 (double move next to black pawn)
+    // Black pawn at d4 should capture to e3 (toward enemy), not somewhere else
     let mut pos = empty_board();
 
-    // Place black pawn at d5 (index 35 = row 4, col 3)
-    place_piece(&mut pos, 35, Piece { color: Color::Black, piece_type: Type::Pawn });
+    // Place black pawn at d4 (index 27 = row 3, col 3)
+    place_piece(&mut pos, 27, Piece { color: Color::Black, piece_type: Type::Pawn });
 
-    // Place white pawn at e5 (index 36 = row 4, col 4) - adjacent to black pawn
-    place_piece(&mut pos, 36, Piece { color: Color::White, piece_type: Type::Pawn });
+    // Place white pawn at e4 (index 28 = row 3, col 4) - adjacent to black pawn
+    place_piece(&mut pos, 28, Piece { color: Color::White, piece_type: Type::Pawn });
 
-    // Simulate the previous move: e3->e5 (index 20->36, distance = 16)
-    pos.prev_moves = vec![Move::new(20, 36, MoveType::Normal)];
+    // Simulate the previous move e2->e4: en passant target is e3 (index 20)
+    pos.en_passant = Some(20);
 
     // Place kings
     place_piece(&mut pos, 4, Piece { color: Color::White, piece_type: Type::King });
     place_piece(&mut pos, 60, Piece { color: Color::Black, piece_type: Type::King });
 
-    let moves = pos.legal_moves(35);
+    let moves = pos.legal_moves(27);
 
-    // Should be able to capture via en passant to e4 (index 28)
-    // Black pawn at d5 (35) captures right-forward with offset -7: 35 + (-7) = 28 (e4)
-    assert!(has_move(&moves, 35, 28), "Black pawn at d5 should capture RIGHT to e4 via en passant");
+    // Should be able to capture via en passant to e3 (index 20)
+    // Black pawn at d4 (27) captures right-forward with offset -7: 27 + (-7) = 20 (e3)
+    assert!(has_move(&moves, 27, 20), "Black pawn at d4 should capture RIGHT to e3 via en passant");
 }
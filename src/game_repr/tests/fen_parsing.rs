@@ -3,7 +3,7 @@
 // This module contains tests for FEN (Forsyth-Edwards Notation) string parsing.
 // FEN is a standard notation for describing chess positions.
 
-use crate::game_repr::{Position, Color, Type};
+use crate::game_repr::{Position, Color, Type, Move, MoveType, FenErrorKind, FenField};
 
 // ==================== FEN PARSING TEST ====================
 
@@ -51,3 +51,203 @@ fn test_custom_fen_position() {
         }
     }
 }
+
+// ==================== FULL SIX-FIELD FEN TESTS ====================
+
+#[test]
+fn test_fen_round_trip() {
+    let fens = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 12 47",
+        "rnbqkb1r/pp1p1ppp/5n2/2pPp3/8/8/PPP1PPPP/RNBQKBNR w Kq c6 0 4",
+    ];
+
+    for fen in fens {
+        assert_eq!(Position::from_fen(fen).to_fen(), fen, "FEN should round-trip");
+    }
+}
+
+#[test]
+fn test_fen_en_passant_field_enables_capture() {
+    // Black to move after 1. e4 ... d4?? setup: black pawn on d4 can take e3 en passant
+    let pos = Position::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 3");
+
+    assert_eq!(pos.en_passant, Some(20));
+    let moves = pos.all_legal_moves();
+    assert!(
+        moves.iter().any(|m| m._from() == 27 && m._to() == 20 && m.move_type() == MoveType::EnPassant),
+        "dxe3 en passant should be available from the FEN en passant field"
+    );
+}
+
+#[test]
+fn test_fen_move_counters() {
+    let mut pos = Position::from_fen("4k3/8/8/8/8/8/4P3/R3K3 w Q - 7 30");
+    assert_eq!(pos.halfmove_clock, 7);
+    assert_eq!(pos.fullmove_number, 30);
+
+    // Quiet rook move increments the halfmove clock; fullmove only advances after Black
+    pos.mk_move(Move::new(0, 1, MoveType::Normal));
+    assert_eq!(pos.to_fen(), "4k3/8/8/8/8/8/4P3/1R2K3 b - - 8 30");
+
+    // Black king move completes the full move
+    pos.mk_move(Move::new(60, 59, MoveType::Normal));
+    assert_eq!(pos.to_fen(), "3k4/8/8/8/8/8/4P3/1R2K3 w - - 9 31");
+
+    // Pawn double push resets the clock and sets the en passant square
+    pos.mk_move(Move::new(12, 28, MoveType::Normal));
+    assert_eq!(pos.to_fen(), "3k4/8/8/8/4P3/8/8/1R2K3 b - e3 0 31");
+}
+
+#[test]
+fn test_unmake_restores_fen_fields() {
+    let mut pos = Position::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 3");
+    let before = pos.to_fen();
+
+    let mv = Move::new(27, 20, MoveType::EnPassant);
+    let undo = pos.make_move_undoable(mv);
+    assert_eq!(pos.en_passant, None);
+    assert_eq!(pos.fullmove_number, 4);
+
+    pos.unmake_move(mv, undo);
+    assert_eq!(pos.to_fen(), before);
+}
+
+// ==================== FALLIBLE FEN PARSING TESTS ====================
+
+/// Asserts that `fen` is rejected in `field` at `offset` with `kind`
+fn assert_fen_error(fen: &str, field: FenField, offset: usize, kind: FenErrorKind) {
+    match Position::try_from_fen(fen) {
+        Ok(_) => panic!("FEN should be rejected: {}", fen),
+        Err(e) => {
+            assert_eq!(e.kind, kind, "wrong error kind for {}", fen);
+            assert_eq!(e.field, field, "wrong field for {}", fen);
+            assert_eq!(e.offset, offset, "wrong offset for {}", fen);
+        }
+    }
+}
+
+#[test]
+fn test_try_from_fen_accepts_valid_positions() {
+    let fens = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 12 47",
+        "4k3/8/8/8/8/8/8/4K3",
+    ];
+
+    for fen in fens {
+        let pos = Position::try_from_fen(fen).unwrap_or_else(|e| panic!("{}: {}", fen, e));
+        assert_eq!(pos.to_fen(), Position::from_fen(fen).to_fen());
+    }
+}
+
+#[test]
+fn test_try_from_fen_infers_missing_castling_rights() {
+    let pos = Position::try_from_fen("r3k3/8/8/8/8/8/8/4K2R").unwrap();
+    assert_eq!(pos.castling_cond, [true, false, true, false, true, true]);
+}
+
+#[test]
+fn test_try_from_fen_syntax_errors() {
+    assert_fen_error("", FenField::Placement, 0, FenErrorKind::Empty);
+    assert_fen_error(
+        "rnbqkbnr/ppppxppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        FenField::Placement,
+        13,
+        FenErrorKind::InvalidPiece('x'),
+    );
+    assert_fen_error(
+        "rnbqkbnr/ppppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        FenField::Placement,
+        17,
+        FenErrorKind::BadRankLength { rank: 7, squares: 9 },
+    );
+    assert_fen_error(
+        "rnbqkbnr/pppppppp/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        FenField::Placement,
+        41,
+        FenErrorKind::WrongRankCount(7),
+    );
+    assert_fen_error(
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1",
+        FenField::SideToMove,
+        44,
+        FenErrorKind::InvalidSideToMove,
+    );
+    assert_fen_error(
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQzq - 0 1",
+        FenField::Castling,
+        48,
+        FenErrorKind::InvalidCastlingChar('z'),
+    );
+    assert_fen_error(
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e4 0 1",
+        FenField::EnPassant,
+        51,
+        FenErrorKind::InvalidEnPassant,
+    );
+    assert_fen_error(
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1",
+        FenField::HalfmoveClock,
+        53,
+        FenErrorKind::InvalidNumber,
+    );
+    assert_fen_error(
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 0",
+        FenField::FullmoveNumber,
+        55,
+        FenErrorKind::InvalidNumber,
+    );
+}
+
+#[test]
+fn test_try_from_fen_rejects_illegal_positions() {
+    assert_fen_error(
+        "rnbq1bnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQ - 0 1",
+        FenField::Placement,
+        0,
+        FenErrorKind::MissingKing(Color::Black),
+    );
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/3KK3 w - - 0 1",
+        FenField::Placement,
+        0,
+        FenErrorKind::TooManyKings(Color::White),
+    );
+    assert_fen_error(
+        "P3k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        FenField::Placement,
+        0,
+        FenErrorKind::PawnOnBackRank,
+    );
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/4K3 w K - 0 1",
+        FenField::Castling,
+        22,
+        FenErrorKind::ImpossibleCastling('K'),
+    );
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/4K3 b - e3 0 1",
+        FenField::EnPassant,
+        24,
+        FenErrorKind::ImpossibleEnPassant,
+    );
+    // White to move while Black's king is attacked by the rook
+    assert_fen_error(
+        "4k3/8/8/8/8/8/8/4R1K1 w - - 0 1",
+        FenField::SideToMove,
+        22,
+        FenErrorKind::OpponentInCheck,
+    );
+}
+
+#[test]
+fn test_from_fen_still_accepts_artificial_positions() {
+    // from_fen skips legality checks so tests can set up partial boards
+    let pos = Position::from_fen("8/8/8/8/8/8/8/4K3 w - - 0 1");
+    assert_eq!(pos.position[4].piece_type, Type::King);
+}
@@ -9,7 +9,14 @@ pub fn empty_board() -> Position {
         bitboards: Bitboards::empty(),
         position: [Piece::default(); 64],
         prev_moves: Vec::new(),
+        side_to_move: Color::White,
         castling_cond: [false; 6],
+        castling_squares: STANDARD_CASTLING_SQUARES,
+        chess960: false,
+        en_passant: None,
+        halfmove_clock: 0,
+        fullmove_number: 1,
+        zobrist: 0,
     }
 }
 
@@ -19,6 +26,7 @@ pub fn place_piece(pos: &mut Position, idx: usize, piece: Piece) {
     // Also update bitboards
     if piece.piece_type != Type::None {
         pos.bitboards.add_piece(piece.color, piece.piece_type, idx);
+        pos.zobrist ^= ZOBRIST.piece(piece, idx);
     }
 }
 
@@ -56,4 +64,10 @@ mod checkmate;
 mod stalemate;
 mod regression;
 mod fen_parsing;
+mod draw_rules;
+mod zobrist;
+mod chess960;
+mod san;
+mod see;
+mod uci_move;
 mod perft;
@@ -0,0 +1,54 @@
+use super::*;
+
+// ==================== CHESS960 PERFT TESTS ====================
+// Positions from the standard Chess960 perft suite (Shredder-FEN castling
+// fields). They cover castling with the king or rook already on its target
+// square, inner rooks and one-sided rights.
+
+const SUITE: [(&str, [u64; 4]); 10] = [
+    ("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9", [21, 528, 12189, 326672]),
+    ("2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9", [21, 807, 18002, 667366]),
+    ("b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9", [20, 479, 10471, 273318]),
+    ("qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9", [22, 593, 13440, 382958]),
+    ("1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9", [28, 1120, 31058, 1171749]),
+    ("qnbnr1kr/ppp1b1pp/4p3/3p1p2/8/2NPP3/PPP1BPPP/QNB1R1KR w HEhe - 1 9", [29, 899, 26578, 824055]),
+    ("q1bnrkr1/ppppp2p/2n2p2/4b1p1/2NP4/8/PPP1PPPP/QNB1RRKB w ge - 1 9", [30, 860, 24566, 732757]),
+    ("qbn1brkr/ppp1p1p1/2n4p/3p1p2/P7/6PP/QPPPPP2/1BNNBRKR w HFhf - 0 9", [25, 635, 17054, 465806]),
+    ("qnnbbrkr/1p2ppp1/2pp3p/p7/1P5P/2NP4/P1P1PPP1/Q1NBBRKR w HFhf - 0 9", [24, 572, 15243, 384260]),
+    ("qn1rbbkr/ppp2p1p/1n1pp1p1/8/3P4/P6P/1PP1PPPK/QNNRBB1R w hd - 2 9", [28, 811, 23175, 679699]),
+];
+
+fn check_suite(depth: usize) {
+    for (fen, counts) in SUITE {
+        let pos = Position::try_from_fen(fen).unwrap();
+        assert_eq!(pos.perft(depth as u32), counts[depth - 1], "perft({}) of {}", depth, fen);
+    }
+}
+
+#[test]
+fn test_perft_chess960_suite_depth_1() {
+    check_suite(1);
+}
+
+#[test]
+fn test_perft_chess960_suite_depth_2() {
+    check_suite(2);
+}
+
+#[test]
+fn test_perft_chess960_suite_depth_3() {
+    check_suite(3);
+}
+
+#[test]
+fn test_perft_chess960_suite_depth_4() {
+    check_suite(4);
+}
+
+#[test]
+fn test_perft_chess960_start_positions() {
+    // Setups 0 and 959 are mirror images, so their trees are the same size
+    assert_eq!(Position::chess960(0).unwrap().perft(4), 201143);
+    assert_eq!(Position::chess960(959).unwrap().perft(4), 201143);
+    assert_eq!(Position::chess960(STANDARD_CHESS960_INDEX).unwrap().perft(4), 197281);
+}
@@ -6,3 +6,4 @@ mod endgame;
 mod complex_promotions;
 mod middle_game;
 mod symmetrical;
+mod chess960;
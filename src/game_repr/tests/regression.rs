@@ -79,3 +79,42 @@ fn test_pawn_edge_square_right_edge() {
     assert!(has_move(&moves, 23, 31), "Pawn should be able to move from h3 to h4");
     assert!(!has_move(&moves, 23, 32), "BUG: Pawn should NOT wrap around board edge!");
 }
+
+// ==================== SIDE TO MOVE STATE TESTS ====================
+
+#[test]
+fn test_black_to_move_fen_has_no_dummy_history() {
+    // Black to move used to be faked by pushing a dummy move into prev_moves
+    let pos = Position::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+
+    assert_eq!(pos.side_to_move, Color::Black);
+    assert!(pos.prev_moves.is_empty());
+    assert!(pos.all_legal_moves().iter().all(|m| pos.position[m._from()].color == Color::Black));
+}
+
+#[test]
+fn test_make_unmake_toggles_side_to_move() {
+    let mut pos = Position::default();
+    let mv = Move::new(12, 28, MoveType::Normal);
+
+    let undo = pos.make_move_undoable(mv);
+    assert_eq!(pos.side_to_move, Color::Black);
+
+    pos.unmake_move(mv, undo);
+    assert_eq!(pos.side_to_move, Color::White);
+    assert_eq!(pos.to_fen(), Position::default().to_fen());
+}
+
+#[test]
+fn test_null_move_round_trip() {
+    let mut pos = Position::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+    let before = pos.to_fen();
+
+    let undo = pos.make_null_move();
+    assert_eq!(pos.side_to_move, Color::White);
+    assert_eq!(pos.en_passant, None, "Null move should clear the en passant square");
+    assert!(pos.prev_moves.is_empty(), "Null move should not touch the move history");
+
+    pos.unmake_null_move(undo);
+    assert_eq!(pos.to_fen(), before);
+}
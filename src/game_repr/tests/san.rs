@@ -0,0 +1,190 @@
+// SAN Tests
+//
+// This module covers writing moves in Standard Algebraic Notation and
+// parsing them back, including disambiguation, promotions, castling in
+// standard and Chess960 games, and lenient input.
+
+use super::*;
+
+fn san(fen: &str, from: &str, to: &str) -> String {
+    let pos = Position::from_fen(fen);
+    let from = algebraic_to_square(from).unwrap();
+    let to = algebraic_to_square(to).unwrap();
+    let mv = pos
+        .all_legal_moves()
+        .into_iter()
+        .find(|mv| mv._from() == from && mv._to() == to)
+        .expect("move should be legal");
+    pos.move_to_san(mv)
+}
+
+// ==================== WRITING SAN ====================
+
+#[test]
+fn test_san_pawn_and_piece_moves() {
+    let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    assert_eq!(san(start, "e2", "e4"), "e4");
+    assert_eq!(san(start, "g1", "f3"), "Nf3");
+}
+
+#[test]
+fn test_san_captures() {
+    let fen = "rnbqkbnr/ppp1pppp/8/3p4/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 0 2";
+    assert_eq!(san(fen, "e4", "d5"), "exd5");
+
+    let fen = "4k3/8/8/3p4/8/4N3/8/4K3 w - - 0 1";
+    assert_eq!(san(fen, "e3", "d5"), "Nxd5");
+
+    // En passant is written as a plain pawn capture
+    let fen = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2";
+    assert_eq!(san(fen, "e5", "d6"), "exd6");
+}
+
+#[test]
+fn test_san_disambiguation() {
+    // Knights on b1 and f3 can both reach d2: file is enough
+    let fen = "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1";
+    assert_eq!(san(fen, "b1", "d2"), "Nbd2");
+
+    // Rooks on a1 and a5 share a file: use the rank
+    let fen = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+    assert_eq!(san(fen, "a1", "a3"), "R1a3");
+    assert_eq!(san(fen, "a5", "a3"), "R5a3");
+
+    // Three queens where neither file nor rank alone is unique
+    let fen = "8/8/1k6/8/4Q2Q/8/8/K6Q w - - 0 1";
+    assert_eq!(san(fen, "h4", "e1"), "Qh4e1");
+}
+
+#[test]
+fn test_san_ignores_pinned_rival() {
+    // The e2 knight is pinned to the king, so Nc3 needs no disambiguation
+    let fen = "4r1k1/8/8/8/8/8/4N3/1N2K3 w - - 0 1";
+    assert_eq!(san(fen, "b1", "c3"), "Nc3");
+}
+
+#[test]
+fn test_san_promotions() {
+    let fen = "3r3k/4P3/8/8/8/8/8/K7 w - - 0 1";
+    assert_eq!(san(fen, "e7", "e8"), "e8=Q+");
+
+    let pos = Position::from_fen(fen);
+    let underpromotion = Move::new(52, 59, MoveType::PromotionKnight);
+    assert_eq!(pos.move_to_san(underpromotion), "exd8=N");
+}
+
+#[test]
+fn test_san_castling() {
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    assert_eq!(san(fen, "e1", "h1"), "O-O");
+    assert_eq!(san(fen, "e1", "a1"), "O-O-O");
+
+    // Chess960 castling is written the same way, even when the king stays put
+    assert_eq!(san("6k1/8/8/8/8/8/8/6KR w H - 0 1", "g1", "h1"), "O-O");
+}
+
+#[test]
+fn test_san_check_and_mate() {
+    let fen = "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1";
+    assert_eq!(san(fen, "a1", "a8"), "Ra8#");
+    assert_eq!(san(fen, "a1", "a7"), "Ra7");
+
+    let fen = "4k3/8/8/8/8/8/8/R3K3 w Q - 0 1";
+    assert_eq!(san(fen, "e1", "a1"), "O-O-O");
+    let fen = "3k4/8/8/8/8/8/8/R3K3 w Q - 0 1";
+    assert_eq!(san(fen, "e1", "a1"), "O-O-O+");
+}
+
+// ==================== PARSING SAN ====================
+
+#[test]
+fn test_parse_san_round_trips_every_legal_move() {
+    let fens = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+    ];
+
+    for fen in fens {
+        let pos = Position::try_from_fen(fen).unwrap();
+        for mv in pos.all_legal_moves() {
+            let text = pos.move_to_san(mv);
+            assert_eq!(pos.parse_san(&text), Ok(mv), "{} in {}", text, fen);
+        }
+    }
+}
+
+#[test]
+fn test_parse_san_lenient_input() {
+    let pos = Position::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    let kingside = Move::new(4, 7, MoveType::Castling);
+    let queenside = Move::new(4, 0, MoveType::Castling);
+
+    assert_eq!(pos.parse_san("O-O"), Ok(kingside));
+    assert_eq!(pos.parse_san("0-0"), Ok(kingside));
+    assert_eq!(pos.parse_san("0-0-0"), Ok(queenside));
+    assert_eq!(pos.parse_san("O-O-O+"), Ok(queenside));
+
+    // Missing check mark, missing capture mark and annotations
+    let pos = Position::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+    assert_eq!(pos.parse_san("Ra8"), Ok(Move::new(0, 56, MoveType::Normal)));
+    assert_eq!(pos.parse_san("Ra8#!!"), Ok(Move::new(0, 56, MoveType::Normal)));
+
+    let pos = Position::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2");
+    assert_eq!(pos.parse_san("ed5"), Ok(Move::new(28, 35, MoveType::Normal)));
+
+    // Promotion with or without '='
+    let pos = Position::from_fen("7k/4P3/8/8/8/8/8/K7 w - - 0 1");
+    assert_eq!(pos.parse_san("e8=Q"), Ok(Move::new(52, 60, MoveType::PromotionQueen)));
+    assert_eq!(pos.parse_san("e8N"), Ok(Move::new(52, 60, MoveType::PromotionKnight)));
+}
+
+#[test]
+fn test_parse_san_errors() {
+    let pos = Position::default();
+
+    assert_eq!(pos.parse_san(""), Err(SanError::Empty));
+    assert_eq!(pos.parse_san("+"), Err(SanError::Empty));
+    assert_eq!(pos.parse_san("Xe4"), Err(SanError::InvalidSyntax));
+    assert_eq!(pos.parse_san("e9"), Err(SanError::InvalidSyntax));
+    assert_eq!(pos.parse_san("e5"), Err(SanError::IllegalMove));
+    assert_eq!(pos.parse_san("O-O"), Err(SanError::IllegalMove));
+
+    // Both knights can reach d2 without disambiguation
+    let pos = Position::from_fen("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1");
+    assert_eq!(pos.parse_san("Nd2"), Err(SanError::AmbiguousMove));
+    assert_eq!(pos.parse_san("Nfd2"), Ok(Move::new(21, 11, MoveType::Normal)));
+
+    // A pawn reaching the last rank must say what it promotes to
+    let pos = Position::from_fen("7k/4P3/8/8/8/8/8/K7 w - - 0 1");
+    assert_eq!(pos.parse_san("e8"), Err(SanError::IllegalMove));
+}
+
+#[test]
+fn test_parse_san_does_not_confuse_bishop_and_b_file() {
+    // Bishop on a3 and b-pawn can both go to b4
+    let pos = Position::from_fen("4k3/8/8/8/8/B7/1P6/4K3 w - - 0 1");
+    assert_eq!(pos.parse_san("Bb4"), Ok(Move::new(16, 25, MoveType::Normal)));
+    assert_eq!(pos.parse_san("b4"), Ok(Move::new(9, 25, MoveType::Normal)));
+}
+
+#[test]
+fn test_line_to_san() {
+    let pos = Position::default();
+    let line: Vec<Move> = ["e4", "e5", "Nf3"]
+        .iter()
+        .scan(pos.clone(), |line_pos, san| {
+            let mv = line_pos.parse_san(san).unwrap();
+            line_pos.mk_move(mv);
+            Some(mv)
+        })
+        .collect();
+    assert_eq!(pos.line_to_san(&line), "1. e4 e5 2. Nf3");
+
+    // A line starting with Black's move
+    let pos = Position::from_fen("6k1/5ppp/8/8/8/8/r4PPP/6K1 b - - 0 30");
+    assert_eq!(pos.line_to_san(&[pos.parse_san("Ra1").unwrap()]), "30... Ra1#");
+    assert_eq!(pos.line_to_san(&[]), "");
+}
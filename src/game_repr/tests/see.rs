@@ -0,0 +1,76 @@
+// Static Exchange Evaluation Tests
+//
+// This module covers the material balance SEE assigns to captures: free
+// pieces, defended pieces, exchanges that stop halfway, x-ray attackers
+// behind the capturing piece, en passant, promotions and the king.
+
+use super::*;
+
+fn see(fen: &str, san: &str) -> i32 {
+    let pos = Position::from_fen(fen);
+    pos.see(pos.parse_san(san).expect("move should be legal"))
+}
+
+#[test]
+fn test_see_undefended_capture() {
+    assert_eq!(see("4k3/8/8/3p4/8/8/8/3QK3 w - -", "Qxd5"), 100);
+    assert_eq!(see("4k3/8/8/3r4/8/8/8/3RK3 w - -", "Rxd5"), 500);
+}
+
+#[test]
+fn test_see_defended_capture() {
+    // QxP defended by a pawn loses the queen for a pawn
+    assert_eq!(see("4k3/8/4p3/3p4/8/8/8/3QK3 w - -", "Qxd5"), 100 - 900);
+    // PxN defended by a pawn still wins a piece for a pawn
+    assert_eq!(see("4k3/8/4p3/3n4/4P3/8/8/4K3 w - -", "exd5"), 300 - 100);
+}
+
+#[test]
+fn test_see_side_stops_losing_exchange() {
+    // The bishop takes back, so the rook goes for the knight
+    assert_eq!(see("4k3/8/2b5/3n4/8/8/8/3RK3 w - -", "Rxd5"), 300 - 500);
+    // Black does not take back with the queen, which the rook would win
+    assert_eq!(see("3qk3/8/8/3p4/4P3/8/8/3RK3 w - -", "exd5"), 100);
+}
+
+#[test]
+fn test_see_xray_attackers() {
+    // The rook behind the first one takes back once it has gone
+    assert_eq!(see("3rk3/8/8/3p4/8/8/3R4/3RK3 w - -", "Rxd5"), 100);
+    assert_eq!(see("3rk3/8/8/3p4/8/8/3R4/4K3 w - -", "Rxd5"), 100 - 500);
+    assert_eq!(see("3rk3/8/8/3p4/8/8/3Q4/3RK3 w - -", "Qxd5"), 100 - 900 + 500);
+    // A bishop behind a pawn backs up the pawn's capture
+    assert_eq!(see("4k3/8/5p2/4n3/3P4/2B5/8/4K3 w - -", "dxe5"), 300 - 100 + 100);
+    assert_eq!(see("4k3/8/5p2/4n3/3P4/8/8/4K3 w - -", "dxe5"), 300 - 100);
+    // X-rays work for the defending side too: the queen has the last word
+    assert_eq!(see("4k3/3r4/8/3n4/8/8/3R4/3RK3 w - -", "Rxd5"), 300);
+    assert_eq!(see("3qk3/3r4/8/3n4/8/8/3R4/3RK3 w - -", "Rxd5"), 300 - 500 + 500 - 500);
+}
+
+#[test]
+fn test_see_en_passant_and_promotion() {
+    let mut pos = Position::from_fen("4k3/5p2/8/4P3/8/8/8/4K3 b - -");
+    pos.mk_move(pos.parse_san("f5").unwrap());
+    assert_eq!(pos.see(pos.parse_san("exf6").unwrap()), 100);
+
+    // Promoting on a guarded square gives the queen back
+    assert_eq!(see("3rk3/2P5/8/8/8/8/8/4K3 w - -", "c8=Q"), -100);
+    assert_eq!(see("4k3/2P5/8/8/8/8/8/4K3 w - -", "c8=Q"), 800);
+}
+
+#[test]
+fn test_see_king_recaptures_only_undefended() {
+    // The king takes back a lone attacker
+    assert_eq!(see("4k3/4p3/8/8/8/8/8/4RK2 w - -", "Rxe7"), 100 - 500);
+    // ...but not when the square is still covered
+    assert_eq!(see("4k3/4p3/8/8/8/8/4R3/4RK2 w - -", "Rxe7"), 100);
+}
+
+#[test]
+fn test_see_ge() {
+    let pos = Position::from_fen("4k3/8/4p3/3p4/8/8/8/3QK3 w - -");
+    let capture = pos.parse_san("Qxd5").unwrap();
+    assert!(pos.see_ge(capture, -800));
+    assert!(!pos.see_ge(capture, 0));
+    assert!(pos.see_ge(pos.parse_san("Qd2").unwrap(), 0));
+}
@@ -0,0 +1,94 @@
+// UCI Move Notation Tests
+//
+// This module covers long algebraic move text (e2e4, e7e8q, e1g1): writing
+// it from a Move and resolving it back to the right MoveType against the
+// legal moves of a position.
+
+use super::*;
+
+// ==================== FORMATTING ====================
+
+#[test]
+fn test_to_uci() {
+    assert_eq!(Move::new(12, 28, MoveType::Normal).to_uci(), "e2e4");
+    assert_eq!(Move::new(52, 60, MoveType::PromotionQueen).to_uci(), "e7e8q");
+    assert_eq!(Move::new(52, 59, MoveType::PromotionKnight).to_uci(), "e7d8n");
+    assert_eq!(Move::new(36, 43, MoveType::EnPassant).to_uci(), "e5d6");
+
+    // Castling is stored as king takes rook but written as the king's step
+    assert_eq!(Move::new(4, 7, MoveType::Castling).to_uci(), "e1g1");
+    assert_eq!(Move::new(60, 56, MoveType::Castling).to_uci(), "e8c8");
+}
+
+#[test]
+fn test_move_to_uci_in_chess960() {
+    let mut pos = Position::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    let castle = Move::new(4, 7, MoveType::Castling);
+    assert_eq!(pos.move_to_uci(castle), "e1g1");
+
+    pos.chess960 = true;
+    assert_eq!(pos.move_to_uci(castle), "e1h1");
+    assert_eq!(pos.move_to_uci(Move::new(12, 20, MoveType::Normal)), "e2e3");
+}
+
+// ==================== PARSING ====================
+
+#[test]
+fn test_parse_uci_resolves_move_types() {
+    let pos = Position::from_fen("6k1/4P3/8/3pP3/8/8/8/R3K2R w KQ d6 0 2");
+
+    assert_eq!(pos.parse_uci_move("e5d6"), Ok(Move::new(36, 43, MoveType::EnPassant)));
+    assert_eq!(pos.parse_uci_move("e1g1"), Ok(Move::new(4, 7, MoveType::Castling)));
+    assert_eq!(pos.parse_uci_move("e1c1"), Ok(Move::new(4, 0, MoveType::Castling)));
+    assert_eq!(pos.parse_uci_move("e1h1"), Ok(Move::new(4, 7, MoveType::Castling)));
+    assert_eq!(pos.parse_uci_move("e7e8r"), Ok(Move::new(52, 60, MoveType::PromotionRook)));
+    assert_eq!(pos.parse_uci_move("e7e8Q"), Ok(Move::new(52, 60, MoveType::PromotionQueen)));
+    assert_eq!(pos.parse_uci_move("a1a5"), Ok(Move::new(0, 32, MoveType::Normal)));
+}
+
+#[test]
+fn test_parse_uci_round_trips_every_legal_move() {
+    let fens = [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        "k7/8/8/8/8/8/8/5KR1 w G - 0 1",
+    ];
+
+    for fen in fens {
+        let pos = Position::try_from_fen(fen).unwrap();
+        for mv in pos.all_legal_moves() {
+            let text = pos.move_to_uci(mv);
+            assert_eq!(pos.parse_uci_move(&text), Ok(mv), "{} in {}", text, fen);
+        }
+    }
+}
+
+#[test]
+fn test_parse_uci_chess960_castling_is_king_takes_rook() {
+    // King f1, rook h1: f1g1 is a plain king step, castling is f1h1
+    let pos = Position::try_from_fen("k7/8/8/8/8/8/8/5K1R w H - 0 1").unwrap();
+    assert!(pos.chess960);
+
+    assert_eq!(pos.parse_uci_move("f1g1"), Ok(Move::new(5, 6, MoveType::Normal)));
+    assert_eq!(pos.parse_uci_move("f1h1"), Ok(Move::new(5, 7, MoveType::Castling)));
+}
+
+#[test]
+fn test_parse_uci_errors() {
+    let pos = Position::default();
+
+    assert_eq!(pos.parse_uci_move(""), Err(UciMoveError::InvalidSyntax));
+    assert_eq!(pos.parse_uci_move("e2"), Err(UciMoveError::InvalidSyntax));
+    assert_eq!(pos.parse_uci_move("e2e9"), Err(UciMoveError::InvalidSyntax));
+    assert_eq!(pos.parse_uci_move("e2e4k"), Err(UciMoveError::InvalidSyntax));
+    assert_eq!(pos.parse_uci_move("e2e4qq"), Err(UciMoveError::InvalidSyntax));
+    assert_eq!(pos.parse_uci_move("e3e4"), Err(UciMoveError::EmptySquare));
+    assert_eq!(pos.parse_uci_move("e7e5"), Err(UciMoveError::NotYourPiece));
+    assert_eq!(pos.parse_uci_move("e2e5"), Err(UciMoveError::IllegalMove));
+    assert_eq!(pos.parse_uci_move("e1g1"), Err(UciMoveError::IllegalMove));
+    assert_eq!(pos.parse_uci_move("e2e4q"), Err(UciMoveError::UnexpectedPromotion));
+
+    let pos = Position::from_fen("7k/4P3/8/8/8/8/8/K7 w - - 0 1");
+    assert_eq!(pos.parse_uci_move("e7e8"), Err(UciMoveError::MissingPromotion));
+}
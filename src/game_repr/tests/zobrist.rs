@@ -0,0 +1,119 @@
+// Zobrist Key Tests
+//
+// This module checks that the incrementally maintained Zobrist key matches a
+// full recompute through make/unmake, null moves and special moves.
+
+use super::*;
+
+/// Plays every legal move to `depth` and checks the key after each make and unmake
+fn check_keys_recursive(pos: &mut Position, depth: u32) {
+    if depth == 0 {
+        return;
+    }
+    for mv in pos.all_legal_moves() {
+        let before = pos.zobrist;
+        let undo = pos.make_move_undoable(mv);
+        assert_eq!(pos.zobrist, pos.compute_zobrist(), "after {:?} in {}", mv, pos.to_fen());
+        check_keys_recursive(pos, depth - 1);
+        pos.unmake_move(mv, undo);
+        assert_eq!(pos.zobrist, before);
+    }
+}
+
+// ==================== KEY CONTENT TESTS ====================
+
+#[test]
+fn test_zobrist_hash_starting_position() {
+    let pos = Position::default();
+
+    assert_eq!(pos.zobrist, pos.compute_zobrist());
+    assert_ne!(pos.zobrist, 0);
+}
+
+#[test]
+fn test_zobrist_hash_different_positions() {
+    let pos1 = Position::default();
+    let pos2 = Position::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+
+    assert_ne!(pos1.zobrist, pos2.zobrist);
+}
+
+#[test]
+fn test_zobrist_hash_side_to_move() {
+    // Same position, different side to move
+    let pos_white = Position::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    let pos_black = Position::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1");
+
+    // Should differ only by side-to-move bit
+    assert_eq!(pos_white.zobrist ^ pos_black.zobrist, ZOBRIST.side_to_move);
+}
+
+#[test]
+fn test_zobrist_en_passant_only_when_capturable() {
+    // No black pawn can take on e3, so the en passant field doesn't change the key
+    let with_ep = Position::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+    let without_ep = Position::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1");
+    assert_eq!(with_ep.zobrist, without_ep.zobrist);
+
+    // A black pawn on d4 can capture, so the file is hashed
+    let with_ep = Position::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 3");
+    let without_ep = Position::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 3");
+    assert_eq!(with_ep.zobrist ^ without_ep.zobrist, ZOBRIST.en_passant[4]);
+}
+
+#[test]
+fn test_zobrist_transposition_same_key() {
+    // 1. Nf3 Nf6 2. Nc3 and 1. Nc3 Nf6 2. Nf3 reach the same position
+    let mut a = Position::default();
+    for mv in [Move::new(6, 21, MoveType::Normal), Move::new(62, 45, MoveType::Normal), Move::new(1, 18, MoveType::Normal)] {
+        a.mk_move(mv);
+    }
+    let mut b = Position::default();
+    for mv in [Move::new(1, 18, MoveType::Normal), Move::new(62, 45, MoveType::Normal), Move::new(6, 21, MoveType::Normal)] {
+        b.mk_move(mv);
+    }
+
+    assert_eq!(a.zobrist, b.zobrist);
+}
+
+// ==================== INCREMENTAL UPDATE TESTS ====================
+
+#[test]
+fn test_incremental_hash_update() {
+    let mut pos = Position::default();
+    let initial_hash = pos.zobrist;
+
+    // Make a move (e2-e4)
+    pos.mk_move(Move::new(12, 28, MoveType::Normal));
+
+    assert_ne!(pos.zobrist, initial_hash);
+    assert_eq!(pos.zobrist, pos.compute_zobrist());
+}
+
+#[test]
+fn test_zobrist_incremental_starting_position() {
+    check_keys_recursive(&mut Position::default(), 3);
+}
+
+#[test]
+fn test_zobrist_incremental_special_moves() {
+    // Kiwipete: castling, en passant and promotions all show up within three plies
+    let mut pos = Position::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+    check_keys_recursive(&mut pos, 3);
+
+    let mut pos = Position::from_fen("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1");
+    check_keys_recursive(&mut pos, 3);
+}
+
+#[test]
+fn test_zobrist_null_move() {
+    let mut pos = Position::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 3");
+    let before = pos.zobrist;
+
+    let undo = pos.make_null_move();
+    assert_eq!(pos.zobrist, pos.compute_zobrist());
+    assert_ne!(pos.zobrist, before);
+
+    pos.unmake_null_move(undo);
+    assert_eq!(pos.zobrist, before);
+}
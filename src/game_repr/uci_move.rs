@@ -0,0 +1,113 @@
+//! Long algebraic move notation as used by the UCI protocol.
+//!
+//! Moves are written as origin and destination square plus an optional
+//! promotion letter (`e2e4`, `e7e8q`). Standard chess writes castling as the
+//! king's two-square step (`e1g1`); Chess960 writes it as the king taking its
+//! own rook (`e1h1`), which is the only unambiguous form when the king may
+//! start next to, or on, its destination square.
+
+use super::*;
+use std::fmt;
+
+/// Error returned by [`Position::parse_uci_move`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UciMoveError {
+    /// Not two squares followed by an optional promotion letter
+    InvalidSyntax,
+    /// There is no piece on the origin square
+    EmptySquare,
+    /// The piece on the origin square belongs to the side not to move
+    NotYourPiece,
+    /// A pawn move to the last rank without a promotion letter
+    MissingPromotion,
+    /// A promotion letter on a move that does not promote
+    UnexpectedPromotion,
+    /// The piece cannot make this move in the current position
+    IllegalMove,
+}
+
+impl fmt::Display for UciMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            UciMoveError::InvalidSyntax => "expected a move like e2e4 or e7e8q",
+            UciMoveError::EmptySquare => "no piece on the origin square",
+            UciMoveError::NotYourPiece => "the piece on the origin square belongs to the opponent",
+            UciMoveError::MissingPromotion => "pawn reaches the last rank without a promotion piece",
+            UciMoveError::UnexpectedPromotion => "promotion piece given for a move that does not promote",
+            UciMoveError::IllegalMove => "illegal move",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl std::error::Error for UciMoveError {}
+
+impl Position {
+    /// Long algebraic form of a move, with castling written the way this
+    /// game expects it: king takes rook in Chess960, `e1g1` otherwise
+    pub fn move_to_uci(&self, mv: Move) -> String {
+        if self.chess960 && mv.move_type() == MoveType::Castling {
+            format!("{}{}", square_to_algebraic(mv._from()), square_to_algebraic(mv._to()))
+        } else {
+            mv.to_uci()
+        }
+    }
+
+    /// Parses a long algebraic move and resolves it against the legal moves
+    /// of this position
+    ///
+    /// Castling is accepted as king takes rook in every game, and also as the
+    /// king's two-square step (`e1g1`) outside Chess960. The promotion letter
+    /// may be given in either case.
+    pub fn parse_uci_move(&self, text: &str) -> Result<Move, UciMoveError> {
+        let text = text.trim();
+        if !text.is_ascii() || !(4..=5).contains(&text.len()) {
+            return Err(UciMoveError::InvalidSyntax);
+        }
+
+        let from = algebraic_to_square(&text[0..2]).ok_or(UciMoveError::InvalidSyntax)?;
+        let to = algebraic_to_square(&text[2..4]).ok_or(UciMoveError::InvalidSyntax)?;
+        let promotion = match text[4..].chars().next().map(|c| c.to_ascii_lowercase()) {
+            None => None,
+            Some('q') => Some(Type::Queen),
+            Some('r') => Some(Type::Rook),
+            Some('b') => Some(Type::Bishop),
+            Some('n') => Some(Type::Knight),
+            Some(_) => return Err(UciMoveError::InvalidSyntax),
+        };
+
+        let piece = self.position[from];
+        if piece.is_none() {
+            return Err(UciMoveError::EmptySquare);
+        }
+        if piece.color != self.side_to_move {
+            return Err(UciMoveError::NotYourPiece);
+        }
+
+        let candidates: Vec<Move> = self
+            .all_legal_moves()
+            .into_iter()
+            .filter(|mv| mv._from() == from)
+            .filter(|mv| {
+                mv._to() == to
+                    || (!self.chess960
+                        && mv.move_type() == MoveType::Castling
+                        && mv.castling_destinations().0 == to)
+            })
+            .collect();
+
+        if candidates.is_empty() {
+            return Err(UciMoveError::IllegalMove);
+        }
+
+        let promotes = candidates.iter().any(|mv| mv.move_type().is_promotion());
+        match (promotes, promotion) {
+            (true, None) => Err(UciMoveError::MissingPromotion),
+            (false, Some(_)) => Err(UciMoveError::UnexpectedPromotion),
+            _ => candidates
+                .into_iter()
+                .find(|mv| mv.move_type().promotion_piece() == promotion)
+                .ok_or(UciMoveError::IllegalMove),
+        }
+    }
+}
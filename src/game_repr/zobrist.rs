@@ -0,0 +1,183 @@
+//! Zobrist hashing for positions.
+//!
+//! Every [`Position`] carries a `zobrist` key that `mk_move`, `unmake_move`
+//! and the null move keep up to date incrementally. The transposition table,
+//! repetition detection and anything else that needs a position identity
+//! should read that field rather than rehashing the board.
+
+use super::*;
+use super::bitboards::tables::PAWN_ATTACKS;
+use std::sync::LazyLock;
+
+/// Zobrist hashing constants for chess positions
+///
+/// Zobrist hashing uses random 64-bit numbers to represent each possible
+/// piece-square combination, along with castling rights, en passant, and
+/// side to move. This allows for efficient incremental hash updates.
+pub struct ZobristKeys {
+    /// [piece_type][color][square] - 6 piece types * 2 colors * 64 squares
+    pub pieces: [[[u64; 64]; 2]; 6],
+    /// [castling_index] - 6 castling conditions
+    pub castling: [u64; 6],
+    /// [file] - en passant file (0-7)
+    pub en_passant: [u64; 8],
+    /// Side to move (toggle this when it's black's turn)
+    pub side_to_move: u64,
+}
+
+impl ZobristKeys {
+    /// Generate Zobrist keys using a seeded random number generator
+    /// This ensures the keys are random but reproducible
+    fn generate() -> Self {
+        use rand::{Rng, SeedableRng};
+        use rand::rngs::StdRng;
+
+        // Use a fixed seed for reproducibility
+        let mut rng = StdRng::seed_from_u64(0x517cc1b727220a95);
+
+        let mut pieces = [[[0u64; 64]; 2]; 6];
+
+        // Generate random numbers for each piece-square combination
+        for piece_type in &mut pieces {
+            for color in piece_type {
+                for square in color {
+                    *square = rng.gen();
+                }
+            }
+        }
+
+        let mut castling = [0u64; 6];
+        for castle in &mut castling {
+            *castle = rng.gen();
+        }
+
+        let mut en_passant = [0u64; 8];
+        for ep in &mut en_passant {
+            *ep = rng.gen();
+        }
+
+        Self {
+            pieces,
+            castling,
+            en_passant,
+            side_to_move: rng.gen(),
+        }
+    }
+
+    /// Get piece type index for Zobrist hashing
+    #[inline]
+    fn piece_index(piece_type: Type) -> usize {
+        match piece_type {
+            Type::Pawn => 0,
+            Type::Knight => 1,
+            Type::Bishop => 2,
+            Type::Rook => 3,
+            Type::Queen => 4,
+            Type::King => 5,
+            Type::None => panic!("Cannot hash Type::None"),
+        }
+    }
+
+    /// Get color index for Zobrist hashing
+    #[inline]
+    fn color_index(color: Color) -> usize {
+        match color {
+            Color::White => 0,
+            Color::Black => 1,
+        }
+    }
+
+    /// Key for a piece standing on a square
+    #[inline]
+    pub fn piece(&self, piece: Piece, square: usize) -> u64 {
+        self.pieces[Self::piece_index(piece.piece_type)][Self::color_index(piece.color)][square]
+    }
+
+    /// Combined key for the castling rights a set of castling conditions
+    /// leaves
+    ///
+    /// A rook flag only counts while its king's flag is still set, so a king
+    /// that has moved hashes the same whatever its rooks did since.
+    #[inline]
+    pub fn castling_rights(&self, castling_cond: &[bool; 6]) -> u64 {
+        let mut key = 0;
+        for side in [0, 3] {
+            if !castling_cond[side + 2] {
+                continue;
+            }
+            for rook in [side, side + 1] {
+                if castling_cond[rook] {
+                    key ^= self.castling[rook];
+                }
+            }
+        }
+        key
+    }
+}
+
+/// Global Zobrist keys - initialized once using LazyLock
+pub static ZOBRIST: LazyLock<ZobristKeys> = LazyLock::new(ZobristKeys::generate);
+
+impl Position {
+    /// Computes the Zobrist key from scratch.
+    ///
+    /// Used to seed `zobrist` when a position is built and to verify the
+    /// incremental updates in debug builds.
+    pub fn compute_zobrist(&self) -> u64 {
+        let mut hash = 0u64;
+
+        // Hash all pieces on the board
+        for (square, &piece) in self.position.iter().enumerate() {
+            if piece.piece_type != Type::None {
+                hash ^= ZOBRIST.piece(piece, square);
+            }
+        }
+
+        // Hash castling rights
+        hash ^= ZOBRIST.castling_rights(&self.castling_cond);
+
+        // Hash en passant file if a capture is actually possible
+        if let Some(file) = self.en_passant_zobrist_file() {
+            hash ^= ZOBRIST.en_passant[file];
+        }
+
+        // Hash side to move (black to move toggles this bit)
+        if self.side_to_move == Color::Black {
+            hash ^= ZOBRIST.side_to_move;
+        }
+
+        hash
+    }
+
+    /// The en passant file that contributes to the key, if any.
+    ///
+    /// The file is only hashed when a pawn of the side to move can capture en
+    /// passant, so a position reached with and without a double push that
+    /// can't be answered en passant gets the same key.
+    #[inline]
+    pub(crate) fn en_passant_zobrist_file(&self) -> Option<usize> {
+        let ep = self.en_passant? as usize;
+        // Reverse lookup: our pawns that attack `ep` sit where an enemy pawn on `ep` would attack
+        let opposite_color_idx = match self.side_to_move {
+            Color::White => 1,
+            Color::Black => 0,
+        };
+        let capturers = self.bitboards.pieces_of_type(self.side_to_move, Type::Pawn);
+        if PAWN_ATTACKS[opposite_color_idx][ep] & capturers != 0 {
+            Some(ep % 8)
+        } else {
+            None
+        }
+    }
+
+    /// Debug check that the incremental key matches a full recompute
+    #[inline]
+    pub(crate) fn debug_assert_zobrist(&self) {
+        debug_assert_eq!(
+            self.zobrist,
+            self.compute_zobrist(),
+            "incremental Zobrist key diverged from full recompute ({})",
+            self.to_fen()
+        );
+    }
+}
@@ -1,9 +1,11 @@
 pub mod agent;
 pub mod assets;
 pub mod board;
+pub mod epd;
 pub mod game_repr;
 pub mod menu;
 pub mod orchestrator;
+pub mod pgn;
 pub mod renderer;
 
 #[cfg(target_arch = "wasm32")]
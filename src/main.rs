@@ -18,9 +18,11 @@ use winit::{
 mod agent;
 mod assets;
 mod board;
+mod epd;
 mod game_repr;
 mod menu;
 mod orchestrator;
+mod pgn;
 mod renderer;
 
 /// Native application handler for the chess engine.
@@ -78,12 +80,9 @@ impl ApplicationHandler for App {
         event: WindowEvent,
     ) {
         // Handle app-level events first
-        match &event {
-            WindowEvent::CloseRequested => {
-                event_loop.exit();
-                return;
-            }
-            _ => {}
+        if let WindowEvent::CloseRequested = event {
+            event_loop.exit();
+            return;
         }
 
         // Delegate to orchestrator
@@ -26,6 +26,8 @@ pub struct GameConfig {
     pub white_player: PlayerConfig,
     /// Configuration for the Black player
     pub black_player: PlayerConfig,
+    /// Start from a random Chess960 setup instead of the standard position
+    pub chess960: bool,
 }
 
 /// Game mode selection (separate from orchestrator's GameMode for clean separation).
@@ -46,6 +48,7 @@ impl GameConfig {
             mode: GameMode::PvP,
             white_player: PlayerConfig::Human,
             black_player: PlayerConfig::Human,
+            chess960: false,
         }
     }
 
@@ -70,6 +73,7 @@ impl GameConfig {
             mode: GameMode::PvAI,
             white_player,
             black_player,
+            chess960: false,
         }
     }
 
@@ -83,9 +87,16 @@ impl GameConfig {
             mode: GameMode::AIvAI,
             white_player: PlayerConfig::AI { difficulty: white_difficulty },
             black_player: PlayerConfig::AI { difficulty: black_difficulty },
+            chess960: false,
         }
     }
 
+    /// Play this configuration from a Chess960 starting position.
+    pub fn with_chess960(mut self, chess960: bool) -> Self {
+        self.chess960 = chess960;
+        self
+    }
+
     /// Get the human player's color in a PvAI game.
     /// Returns None for PvP or AIvAI games.
     pub fn human_color(&self) -> Option<Color> {
@@ -112,6 +123,14 @@ mod tests {
         assert_eq!(config.mode, GameMode::PvP);
         assert_eq!(config.white_player, PlayerConfig::Human);
         assert_eq!(config.black_player, PlayerConfig::Human);
+        assert!(!config.chess960);
+    }
+
+    #[test]
+    fn test_with_chess960() {
+        let config = GameConfig::aivai(Difficulty::Easy, Difficulty::Hard).with_chess960(true);
+        assert_eq!(config.mode, GameMode::AIvAI);
+        assert!(config.chess960);
     }
 
     #[test]
@@ -94,6 +94,9 @@ pub mod main_menu {
     pub const PVAI: ButtonRect = ButtonRect::new(-0.5, 0.1, 1.0, 0.2);
     pub const AIVAI: ButtonRect = ButtonRect::new(-0.5, -0.25, 1.0, 0.2);
 
+    /// Chess960 on/off toggle below the mode buttons
+    pub const CHESS960: ButtonRect = ButtonRect::new(-0.3, -0.55, 0.6, 0.12);
+
     /// Get all main menu buttons.
     pub fn buttons() -> [ButtonRect; 3] {
         [PVP, PVAI, AIVAI]
@@ -178,6 +181,10 @@ pub mod colors {
     /// AIvAI button color (purplish)
     pub const AIVAI: [f32; 4] = [0.6, 0.5, 0.7, 1.0];
 
+    /// Chess960 toggle when off / on
+    pub const CHESS960_OFF: [f32; 4] = [0.35, 0.35, 0.38, 1.0];
+    pub const CHESS960_ON: [f32; 4] = [0.8, 0.65, 0.35, 1.0];
+
     /// Play as White button (light)
     pub const SIDE_WHITE: [f32; 4] = [0.85, 0.85, 0.8, 1.0];
     /// Play as Black button (dark)
@@ -255,6 +262,13 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_chess960_toggle_below_mode_buttons() {
+        for button in main_menu::buttons() {
+            assert!(button.bottom() > main_menu::CHESS960.top);
+        }
+    }
+
     #[test]
     fn test_difficulty_buttons_dont_overlap() {
         let white = difficulty::white_buttons();
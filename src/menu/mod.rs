@@ -15,7 +15,7 @@
 //! # Example Flow
 //!
 //! ```text
-//! ModeSelection
+//! ModeSelection  (Chess960 toggle applies to whichever mode is picked)
 //!   ├─ PvP → Returns GameConfig::pvp() immediately
 //!   ├─ PvAI → SideSelection
 //!   │           ├─ White → DifficultySelection { White }
@@ -45,6 +45,8 @@ pub use state::{AIvAISetupState, MenuState};
 pub struct Menu {
     /// Current menu state
     state: MenuState,
+    /// Whether the next game starts from a Chess960 position
+    chess960: bool,
     /// Last known mouse position
     mouse_pos: PhysicalPosition<f64>,
     /// Window size for coordinate conversion
@@ -59,6 +61,7 @@ impl Menu {
     pub fn new() -> Self {
         Self {
             state: MenuState::default(),
+            chess960: false,
             mouse_pos: PhysicalPosition::new(0.0, 0.0),
             window_size: (800, 800),
             scale_factor: 1.0,
@@ -70,6 +73,11 @@ impl Menu {
         &self.state
     }
 
+    /// Whether the Chess960 toggle is on.
+    pub fn chess960(&self) -> bool {
+        self.chess960
+    }
+
     /// Update the mouse position.
     pub fn update_mouse_pos(&mut self, pos: PhysicalPosition<f64>) {
         self.mouse_pos = pos;
@@ -125,13 +133,16 @@ impl Menu {
                 let buttons = layout::main_menu::buttons();
                 if buttons[0].contains(pos, size) {
                     // PvP - start immediately
-                    return Some(GameConfig::pvp());
+                    return Some(GameConfig::pvp().with_chess960(self.chess960));
                 } else if buttons[1].contains(pos, size) {
                     // PvAI - go to side selection
                     self.state = MenuState::SideSelection;
                 } else if buttons[2].contains(pos, size) {
                     // AIvAI - go to AI setup
                     self.state = MenuState::AIvAISetup(AIvAISetupState::default());
+                } else if layout::main_menu::CHESS960.contains(pos, size) {
+                    // Chess960 - toggle for whichever mode is picked next
+                    self.chess960 = !self.chess960;
                 }
             }
 
@@ -159,7 +170,7 @@ impl Menu {
 
                 for (i, button) in buttons.iter().enumerate() {
                     if button.contains(pos, size) {
-                        return Some(GameConfig::pvai(*user_color, difficulties[i]));
+                        return Some(GameConfig::pvai(*user_color, difficulties[i]).with_chess960(self.chess960));
                     }
                 }
             }
@@ -195,7 +206,7 @@ impl Menu {
                     return Some(GameConfig::aivai(
                         setup.white_difficulty,
                         setup.black_difficulty,
-                    ));
+                    ).with_chess960(self.chess960));
                 }
             }
         }
@@ -259,6 +270,30 @@ mod tests {
         assert!(result.is_some());
         let config = result.unwrap();
         assert_eq!(config.mode, GameMode::PvP);
+        assert!(!config.chess960);
+    }
+
+    #[test]
+    fn test_menu_chess960_toggle() {
+        let mut menu = Menu::new();
+        menu.update_window_size((800, 800));
+
+        // Chess960 toggle is at NDC (-0.3, -0.55) to (0.3, -0.67)
+        // In 800x800 window: y 620-668
+        menu.update_mouse_pos(PhysicalPosition::new(400.0, 640.0));
+        assert!(menu.handle_click().is_none());
+        assert!(menu.chess960());
+
+        // The toggle carries over to the game that gets started
+        menu.update_mouse_pos(PhysicalPosition::new(400.0, 260.0));
+        let config = menu.handle_click().unwrap();
+        assert!(config.chess960);
+
+        // Clicking again turns it off
+        menu.reset();
+        menu.update_mouse_pos(PhysicalPosition::new(400.0, 640.0));
+        menu.handle_click();
+        assert!(!menu.chess960());
     }
 
     #[test]
@@ -27,7 +27,7 @@ use crate::agent::player::{GameResult, Player};
 use crate::agent::NegamaxPlayer;
 use crate::agent::ai::{AIType, AIConfig, Difficulty};
 use crate::board::Board;
-use crate::game_repr::{Color, Move};
+use crate::game_repr::{Color, FenError, Move, Position, CHESS960_POSITIONS};
 use crate::menu::{GameConfig, GameMode as MenuGameMode, Menu, PlayerConfig};
 use crate::renderer::wgpu_renderer::WgpuRenderer;
 use crate::renderer::ControlAction;
@@ -36,6 +36,7 @@ use std::sync::Arc;
 use winit::event::{Touch, TouchPhase, WindowEvent};
 use winit::keyboard::{Key, NamedKey};
 use winit::window::Window;
+use rand::Rng;
 
 /// Game mode enumeration representing the current application state.
 ///
@@ -101,7 +102,7 @@ pub enum AISetupButton {
 ///
 /// When a game is active (`game_active = true`), the orchestrator manages turns:
 /// 1. Calls [`request_move`](Orchestrator::request_move) for current player
-/// 2. Waits for player to provide move (blocks for human, computes for AI)
+/// 2. Polls the player for its move after input events and, while an AI searches in the background, every frame
 /// 3. Calls [`process_move`](Orchestrator::process_move) to execute and validate
 /// 4. Calls [`check_game_end`](Orchestrator::check_game_end) to detect checkmate/stalemate
 /// 5. Switches `current_turn` and repeats, or returns to menu if game ended
@@ -133,6 +134,10 @@ pub struct Orchestrator {
     /// Empty string means use default starting position
     starting_fen: String,
 
+    /// Chess960 start position number of the next game, which then starts
+    /// from that setup instead of `starting_fen`
+    chess960_index: Option<u16>,
+
     /// Result of the game if it has ended
     /// None if game is in progress or in menu
     game_result: Option<GameResult>,
@@ -200,6 +205,7 @@ impl Orchestrator {
             current_turn: Color::White,
             game_active: false,
             starting_fen: String::new(),
+            chess960_index: None,
             game_result: None,
             pending_promotion: None,
             menu,
@@ -231,7 +237,7 @@ impl Orchestrator {
             WindowEvent::RedrawRequested => {
                 if self.game_mode == GameMode::Menu {
                     // Draw menu using new menu system
-                    self.board.borrow_mut().renderer_mut().draw_menu_state(self.menu.state());
+                    self.board.borrow_mut().renderer_mut().draw_menu_state(self.menu.state(), self.menu.chess960());
                 } else if let Some(result) = self.game_result {
                     // Game has ended - draw board with game end overlay
                     self.board.borrow_mut().draw_game_end(result);
@@ -242,6 +248,19 @@ impl Orchestrator {
                     // Draw normal game board (includes controls panel)
                     self.board.borrow_mut().draw();
                 }
+
+                // AI players search in the background; poll them once per frame
+                // and keep frames coming until they have moved
+                if self.pending_promotion.is_none() {
+                    self.poll_current_player();
+                    // AIvAI moves are started by the forward button only
+                    if self.game_mode == GameMode::AIvAI && self.is_thinking() {
+                        self.poll_aivai_move();
+                    }
+                }
+                if self.is_thinking() {
+                    self.window.request_redraw();
+                }
             }
 
             WindowEvent::Resized(_new_size) => {
@@ -440,26 +459,41 @@ impl Orchestrator {
                                 }
                             }
                         }
-                        Key::Named(NamedKey::ArrowLeft) => {
+                        Key::Named(NamedKey::ArrowLeft)
+                            if self.game_active || self.game_result.is_some() =>
+                        {
                             // Undo/backward navigation
-                            if self.game_active || self.game_result.is_some() {
-                                self.handle_control_action(ControlAction::Undo);
-                                return;
-                            }
+                            self.handle_control_action(ControlAction::Undo);
+                            return;
                         }
-                        Key::Named(NamedKey::ArrowRight) => {
+                        Key::Named(NamedKey::ArrowRight)
+                            if self.game_active || self.game_result.is_some() =>
+                        {
                             // Redo/forward navigation
-                            if self.game_active || self.game_result.is_some() {
-                                self.handle_control_action(ControlAction::Redo);
-                                return;
-                            }
+                            self.handle_control_action(ControlAction::Redo);
+                            return;
                         }
-                        Key::Character(c) if c.to_lowercase() == "r" => {
+                        Key::Character(c)
+                            if c.to_lowercase() == "r" && (self.game_active || self.game_result.is_some()) =>
+                        {
                             // Flip board
-                            if self.game_active || self.game_result.is_some() {
-                                self.handle_control_action(ControlAction::FlipBoard);
-                                return;
-                            }
+                            self.handle_control_action(ControlAction::FlipBoard);
+                            return;
+                        }
+                        Key::Character(c)
+                            if c.to_lowercase() == "d"
+                                && self.game_mode != GameMode::AIvAI
+                                && !self.is_thinking()
+                                && self.board.borrow().position().can_claim_fifty_move_draw() =>
+                        {
+                            // Claim a fifty-move draw on a human player's turn
+                            self.claim_fifty_move_draw();
+                            return;
+                        }
+                        Key::Named(NamedKey::Space) if self.is_thinking() => {
+                            // Make the thinking AI move now
+                            self.stop_thinking();
+                            return;
                         }
                         _ => {}
                     }
@@ -502,15 +536,19 @@ impl Orchestrator {
 
     /// Poll current player for a move and process it if available.
     ///
-    /// This method is called after each event to check if the current player has
-    /// a move ready. If so, it processes the move and updates the game state.
+    /// This method is called after each event, and on every frame, to check if
+    /// the current player has a move ready. If so, it processes the move and
+    /// updates the game state.
     ///
     /// # Control Flow
     ///
     /// 1. Get the current player based on `current_turn`
-    /// 2. Call `player.get_move(current_turn)` (non-blocking for HumanPlayer)
+    /// 2. Call `player.get_move(current_turn)` (non-blocking; AI players search
+    ///    in the background and report [`is_thinking`](Player::is_thinking))
     /// 3. If move is returned, call `process_move()` to execute it
     /// 4. Request window redraw to show the new position
+    /// 5. If no move is returned and the player has resigned (an AI whose
+    ///    search failed), end the game as a loss for that player
     ///
     /// # Design Note
     ///
@@ -518,7 +556,7 @@ impl Orchestrator {
     /// - Each event potentially changes the player's state (clicks, cursor movement)
     /// - After handling the event, we check if a complete move is ready
     /// - If ready, we process it immediately
-    /// - If not ready, we continue processing events
+    /// - If not ready, we continue processing events (and frames, while an AI thinks)
     fn poll_current_player(&mut self) {
         if !self.game_active {
             return;
@@ -548,6 +586,55 @@ impl Orchestrator {
 
             self.process_move(mv);
             self.window.request_redraw();
+        } else if current_player.has_resigned() {
+            self.handle_game_end(GameResult::from_winner(self.current_turn.opposite()));
+            self.window.request_redraw();
+        }
+    }
+
+    /// Whether the player (or AIvAI configuration) to move is searching in
+    /// the background
+    fn is_thinking(&self) -> bool {
+        if !self.game_active {
+            return false;
+        }
+        if let Some((white, black)) = &self.players {
+            let current_player = match self.current_turn {
+                Color::White => white,
+                Color::Black => black,
+            };
+            return current_player.is_thinking();
+        }
+        let ai_config = match self.current_turn {
+            Color::White => self.white_ai_config.as_ref(),
+            Color::Black => self.black_ai_config.as_ref(),
+        };
+        self.game_mode == GameMode::AIvAI && ai_config.is_some_and(AIConfig::is_thinking)
+    }
+
+    /// Make the thinking AI play the best move it has found so far
+    fn stop_thinking(&mut self) {
+        if let Some((white, black)) = &mut self.players {
+            let current_player = match self.current_turn {
+                Color::White => white,
+                Color::Black => black,
+            };
+            current_player.stop_thinking();
+        } else {
+            let ai_config = match self.current_turn {
+                Color::White => self.white_ai_config.as_ref(),
+                Color::Black => self.black_ai_config.as_ref(),
+            };
+            if let Some(config) = ai_config {
+                config.stop_thinking();
+            }
+        }
+    }
+
+    /// Abandon the searches of both AIvAI configurations
+    fn cancel_aivai_searches(&mut self) {
+        for config in [&mut self.white_ai_config, &mut self.black_ai_config].into_iter().flatten() {
+            config.cancel();
         }
     }
 
@@ -602,14 +689,25 @@ impl Orchestrator {
     /// # Arguments
     ///
     /// * `fen` - FEN string describing the starting position, or empty for default
-    pub fn set_starting_fen(&mut self, fen: String) {
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FenError`] and keeps the previous FEN if `fen` is malformed
+    /// or describes an illegal position.
+    pub fn set_starting_fen(&mut self, fen: String) -> Result<(), FenError> {
+        if !fen.is_empty() {
+            Position::try_from_fen(&fen)?;
+        }
         self.starting_fen = fen;
+        self.chess960_index = None;
+        Ok(())
     }
 
     /// Get a mutable reference to the starting FEN string.
     ///
     /// Used for direct manipulation (e.g., text input).
     pub fn starting_fen_mut(&mut self) -> &mut String {
+        self.chess960_index = None;
         &mut self.starting_fen
     }
 
@@ -638,7 +736,7 @@ impl Orchestrator {
     /// # State Changes
     ///
     /// - Creates and stores player instances in `players`
-    /// - Sets `current_turn` to White (games always start with white)
+    /// - Sets `current_turn` to the side to move in the starting position
     /// - Sets `game_active` to true
     /// - Calls [`request_move`](Self::request_move) to begin game loop
     ///
@@ -700,13 +798,24 @@ impl Orchestrator {
         }
 
         // Initialize game state
-        self.current_turn = Color::White;
         self.game_active = true;
 
         // Reset board to starting position (using FEN if provided)
         {
             let mut board = self.board.borrow_mut();
-            board.reset_position(&self.starting_fen);
+            if let Some(index) = self.chess960_index {
+                // Built directly so the game counts as Chess960 even for the
+                // setups a FEN would read as standard chess
+                board.set_start_position(Position::chess960(index).expect("index is in range"));
+            } else if board.reset_position(&self.starting_fen).is_err() {
+                // The FEN may have been edited in place via starting_fen_mut(), so it
+                // is not guaranteed to be valid; fall back to the standard position
+                self.starting_fen.clear();
+                let _ = board.reset_position("");
+            }
+
+            // The starting FEN may have Black to move
+            self.current_turn = board.current_turn();
 
             // Set POV based on game mode
             match self.game_mode {
@@ -731,44 +840,19 @@ impl Orchestrator {
 
     /// Request the next move from the current player.
     ///
-    /// Polls the current player's [`get_move`](Player::get_move) method. This may block:
-    /// - For human players: blocks until user makes a move via UI
-    /// - For AI players: blocks during move computation
-    /// - For network players: blocks waiting for network response
-    ///
-    /// The event loop continues to process events while waiting, allowing
-    /// human players to interact with the board.
+    /// Polls the current player once, as
+    /// [`poll_current_player`](Self::poll_current_player) does after every
+    /// event. `None` from the player only means the move is not ready yet: a
+    /// human has not finished it, or an AI is still thinking.
     ///
     /// # Control Flow
     ///
     /// 1. Determines which player's turn it is
     /// 2. Calls `player.get_move(current_turn)`
     /// 3. If move is returned, calls [`process_move`](Self::process_move)
-    /// 4. If None is returned, player has resigned/cancelled
-    ///
-    /// # Implementation Notes
-    ///
-    /// For human players, this is called repeatedly from the event loop.
-    /// The player's `get_move` returns `None` until a valid move is made,
-    /// then returns `Some(move)` which triggers move processing.
+    /// 4. If None is returned, nothing happens; poll again later
     pub fn request_move(&mut self) {
-        if !self.game_active {
-            return;
-        }
-
-        if let Some((ref mut white, ref mut black)) = self.players {
-            let player = match self.current_turn {
-                Color::White => white,
-                Color::Black => black,
-            };
-
-            if let Some(mv) = player.get_move(self.current_turn) {
-                self.process_move(mv);
-            } else {
-                // Player resigned or cancelled
-                self.return_to_menu();
-            }
-        }
+        self.poll_current_player();
     }
 
     /// Process and execute a move received from a player.
@@ -845,7 +929,11 @@ impl Orchestrator {
     ///
     /// - **Checkmate**: Current player is in check with no legal moves
     /// - **Stalemate**: Current player is not in check but has no legal moves
-    /// - **Draw**: Insufficient material, threefold repetition, fifty-move rule (future)
+    /// - **Draw**: Insufficient material
+    /// - **Threefold repetition**: The same position has occurred three times
+    /// - **Seventy-five-move rule**: 150 plies without a capture or pawn move
+    /// - **Fifty-move rule**: not detected here; the draw has to be claimed
+    ///   through [`claim_fifty_move_draw`](Self::claim_fifty_move_draw)
     ///
     /// # State Changes
     ///
@@ -867,118 +955,36 @@ impl Orchestrator {
             // log::info!("Stalemate! Game is a draw");
             drop(board);
             self.handle_game_end(GameResult::Stalemate);
-        } else if self.is_insufficient_material(&board) {
+        } else if board.is_insufficient_material() {
             // Insufficient material to checkmate (e.g., only kings remaining)
             // TODO: Add logging once log crate is added to dependencies
             // log::info!("Draw by insufficient material");
             drop(board);
             self.handle_game_end(GameResult::Draw);
+        } else if board.is_threefold_repetition() {
+            drop(board);
+            self.handle_game_end(GameResult::ThreefoldRepetition);
+        } else if board.position().is_seventy_five_move_draw() {
+            drop(board);
+            self.handle_game_end(GameResult::SeventyFiveMoveRule);
         }
-        // Other draw conditions (threefold repetition, fifty-move rule) can be added here
+        // A fifty-move draw has to be claimed, see claim_fifty_move_draw()
     }
 
-    /// Check if the current position has insufficient material for checkmate.
+    /// Ends the game as a fifty-move draw if the side to move may claim one
     ///
-    /// This detects draw by insufficient material according to chess rules.
-    /// A position is drawn if neither side can possibly deliver checkmate.
-    ///
-    /// # Insufficient Material Cases
-    ///
-    /// - King vs King (only two kings on the board)
-    /// - King and Bishop vs King
-    /// - King and Knight vs King
-    /// - King and Bishop vs King and Bishop (same colored bishops)
+    /// Players claim with the D key; AI players never claim, so their games
+    /// run on to the automatic seventy-five-move draw.
     ///
     /// # Returns
     ///
-    /// `true` if the position has insufficient material for checkmate
-    ///
-    /// # Note
-    ///
-    /// This is called at the orchestrator level (not in game_repr) because
-    /// it's a game rule interpretation, not a move legality check.
-    fn is_insufficient_material(&self, board: &Board) -> bool {
-        use crate::game_repr::{Type, Color};
-
-        let mut white_pieces = Vec::new();
-        let mut black_pieces = Vec::new();
-
-        // Count all pieces on the board
-        for idx in 0..64 {
-            let piece = board.piece_at(idx);
-            if piece.piece_type != Type::None {
-                match piece.color {
-                    Color::White => white_pieces.push(piece.piece_type),
-                    Color::Black => black_pieces.push(piece.piece_type),
-                }
-            }
-        }
-
-        // Helper to count specific piece types
-        let count_type = |pieces: &[Type], piece_type: Type| -> usize {
-            pieces.iter().filter(|&&p| p == piece_type).count()
-        };
-
-        // King vs King (only two kings)
-        if white_pieces.len() == 1 && black_pieces.len() == 1 {
-            return true;
+    /// `true` if the claim was valid and the game ended
+    pub fn claim_fifty_move_draw(&mut self) -> bool {
+        if !self.game_active || !self.board.borrow().position().can_claim_fifty_move_draw() {
+            return false;
         }
-
-        // King and Bishop vs King
-        if white_pieces.len() == 2 && black_pieces.len() == 1
-            && count_type(&white_pieces, Type::Bishop) == 1 {
-                return true;
-            }
-        if white_pieces.len() == 1 && black_pieces.len() == 2
-            && count_type(&black_pieces, Type::Bishop) == 1 {
-                return true;
-            }
-
-        // King and Knight vs King
-        if white_pieces.len() == 2 && black_pieces.len() == 1
-            && count_type(&white_pieces, Type::Knight) == 1 {
-                return true;
-            }
-        if white_pieces.len() == 1 && black_pieces.len() == 2
-            && count_type(&black_pieces, Type::Knight) == 1 {
-                return true;
-            }
-
-        // King and Bishop vs King and Bishop (same colored bishops)
-        if white_pieces.len() == 2 && black_pieces.len() == 2 {
-            let white_has_bishop = count_type(&white_pieces, Type::Bishop) == 1;
-            let black_has_bishop = count_type(&black_pieces, Type::Bishop) == 1;
-
-            if white_has_bishop && black_has_bishop {
-                // Find bishop squares to check if they're on same color
-                let mut white_bishop_square = None;
-                let mut black_bishop_square = None;
-
-                for idx in 0..64 {
-                    let piece = board.piece_at(idx);
-                    if piece.piece_type == Type::Bishop {
-                        if piece.color == Color::White {
-                            white_bishop_square = Some(idx);
-                        } else {
-                            black_bishop_square = Some(idx);
-                        }
-                    }
-                }
-
-                // Same color squares = insufficient material
-                // Square color: (rank + file) % 2
-                // idx / 8 = rank, idx % 8 = file
-                if let (Some(w_sq), Some(b_sq)) = (white_bishop_square, black_bishop_square) {
-                    let w_color = (w_sq / 8 + w_sq % 8) % 2;
-                    let b_color = (b_sq / 8 + b_sq % 8) % 2;
-                    if w_color == b_color {
-                        return true;
-                    }
-                }
-            }
-        }
-
-        false
+        self.handle_game_end(GameResult::FiftyMoveRule);
+        true
     }
 
     /// Return to the mode selection menu.
@@ -992,13 +998,14 @@ impl Orchestrator {
     ///
     /// - Sets `game_mode` to `Menu`
     /// - Sets `game_active` to false
-    /// - Clears `players` (dropping player instances)
+    /// - Clears `players` (dropping player instances, which stops their searches)
     /// - Clears `game_result`
     /// - Requests window redraw to show menu UI
     pub fn return_to_menu(&mut self) {
         self.game_mode = GameMode::Menu;
         self.game_active = false;
         self.players = None;
+        self.cancel_aivai_searches();
         self.game_result = None;
         self.pending_promotion = None;
         self.menu_state = MenuState::ModeSelection;
@@ -1013,8 +1020,12 @@ impl Orchestrator {
     /// Start a game from a MenuGameConfig returned by the new Menu system.
     ///
     /// This method translates the menu configuration into the orchestrator's
-    /// internal state and starts the game.
+    /// internal state and starts the game. Chess960 games get a fresh random
+    /// setup; other menu games start from the starting FEN (the standard
+    /// position unless one was set).
     fn start_game_from_config(&mut self, config: GameConfig) {
+        self.chess960_index = config.chess960.then(|| rand::thread_rng().gen_range(0..CHESS960_POSITIONS));
+
         // Set the game mode based on the menu config
         match config.mode {
             MenuGameMode::PvP => {
@@ -1138,6 +1149,8 @@ impl Orchestrator {
                 // In AIvAI mode, navigate backward in history
                 // In other modes, only allow undo in PvP or when it's human's turn
                 if self.game_mode == GameMode::AIvAI {
+                    // The search was for the position being taken back
+                    self.cancel_aivai_searches();
                     if self.board.borrow_mut().undo_move().is_some() {
                         self.current_turn = self.current_turn.opposite();
                         // Clear game result if we undo past it
@@ -1166,8 +1179,9 @@ impl Orchestrator {
                         self.check_game_end();
                         self.window.request_redraw();
                     } else if self.game_active {
-                        // No redo available, generate a new AI move
-                        self.generate_aivai_move();
+                        // No redo available, start searching for a new AI move
+                        self.poll_aivai_move();
+                        self.window.request_redraw();
                     }
                 } else if self.game_mode == GameMode::PvP {
                     // In PvP, allow free redo
@@ -1187,18 +1201,19 @@ impl Orchestrator {
 
     /// Generate a single AI move for AIvAI mode.
     ///
-    /// This is called when the user presses the forward button in AIvAI mode
-    /// and there's no redo history available. It generates one move for the
-    /// current AI player, allowing the user to step through the game at their pace.
-    fn generate_aivai_move(&mut self) {
+    /// The forward button starts a background search for the current AI
+    /// player when there's no redo history available, letting the user step
+    /// through the game at their pace. This is then called every frame until
+    /// the search finishes and the move is played.
+    fn poll_aivai_move(&mut self) {
         if !self.game_active || self.game_mode != GameMode::AIvAI {
             return;
         }
 
         // Get the appropriate AI config for the current turn
         let ai_config = match self.current_turn {
-            Color::White => self.white_ai_config,
-            Color::Black => self.black_ai_config,
+            Color::White => self.white_ai_config.as_mut(),
+            Color::Black => self.black_ai_config.as_mut(),
         };
 
         let ai_config = match ai_config {
@@ -1206,9 +1221,10 @@ impl Orchestrator {
             None => return,
         };
 
-        // Generate a move using the AI config
-        let position = self.board.borrow().position().clone();
-        if let Some(mv) = ai_config.generate_move(&position, self.current_turn) {
+        let board = self.board.borrow();
+        let polled = ai_config.poll_move(board.position(), board.history_keys());
+        drop(board);
+        if let Some(mv) = polled {
             // Execute the move with history tracking
             {
                 let mut board = self.board.borrow_mut();
@@ -1224,9 +1240,11 @@ impl Orchestrator {
             }
 
             self.window.request_redraw();
-        } else {
-            // No legal moves available - game should be over
-            self.check_game_end();
+        } else if !ai_config.is_thinking() {
+            // The game ends as soon as a side has no legal moves, so a
+            // finished search without a move failed: the AI loses
+            self.handle_game_end(GameResult::from_winner(self.current_turn.opposite()));
+            self.window.request_redraw();
         }
     }
 }
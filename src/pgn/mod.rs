@@ -0,0 +1,128 @@
+//! PGN (Portable Game Notation) import and export.
+//!
+//! A [`PgnGame`] is a start position, the main line of moves played from it,
+//! the game result and the tag pairs describing the game. [`PgnGame::to_pgn`]
+//! writes it in PGN export format with SAN moves; [`parse_pgn`] reads every
+//! game in a PGN file back, skipping comments, NAGs and variations.
+//!
+//! Games that do not start from the standard position get `[SetUp "1"]` and
+//! `[FEN "..."]` tags, and Chess960 games a `[Variant "Chess960"]` tag.
+
+mod reader;
+mod writer;
+
+#[cfg(test)]
+mod tests;
+
+pub use reader::{parse_pgn, PgnError, PgnErrorKind};
+
+use crate::agent::player::GameResult;
+use crate::game_repr::{Move, Position};
+
+/// Tags every PGN game must have, in the order they are written
+pub const SEVEN_TAG_ROSTER: [&str; 7] = ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+/// Game termination marker of a PGN game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgnResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    /// Game still in progress, abandoned or result unknown
+    Unknown,
+}
+
+impl PgnResult {
+    /// The marker as written in PGN: `1-0`, `0-1`, `1/2-1/2` or `*`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PgnResult::WhiteWins => "1-0",
+            PgnResult::BlackWins => "0-1",
+            PgnResult::Draw => "1/2-1/2",
+            PgnResult::Unknown => "*",
+        }
+    }
+
+    /// Parses a game termination marker
+    pub fn from_marker(marker: &str) -> Option<Self> {
+        match marker {
+            "1-0" => Some(PgnResult::WhiteWins),
+            "0-1" => Some(PgnResult::BlackWins),
+            "1/2-1/2" => Some(PgnResult::Draw),
+            "*" => Some(PgnResult::Unknown),
+            _ => None,
+        }
+    }
+}
+
+impl From<GameResult> for PgnResult {
+    fn from(result: GameResult) -> Self {
+        match result {
+            GameResult::WhiteWins => PgnResult::WhiteWins,
+            GameResult::BlackWins => PgnResult::BlackWins,
+            GameResult::Draw
+            | GameResult::Stalemate
+            | GameResult::ThreefoldRepetition
+            | GameResult::FiftyMoveRule
+            | GameResult::SeventyFiveMoveRule => PgnResult::Draw,
+        }
+    }
+}
+
+/// A single game: headers, start position, main line and result
+#[derive(Clone)]
+pub struct PgnGame {
+    /// Tag pairs in the order they were read or set, except `Result`, `SetUp`
+    /// and `FEN`, which are derived from `result` and `start`
+    pub tags: Vec<(String, String)>,
+    /// Position the moves are played from
+    pub start: Position,
+    /// Main line, every move legal in the position it is played in
+    pub moves: Vec<Move>,
+    pub result: PgnResult,
+}
+
+impl PgnGame {
+    /// A game with the Seven Tag Roster filled with the PGN "unknown" values
+    /// and an unknown result
+    pub fn new(start: Position, moves: Vec<Move>) -> Self {
+        let tags = [
+            ("Event", "?"),
+            ("Site", "?"),
+            ("Date", "????.??.??"),
+            ("Round", "?"),
+            ("White", "?"),
+            ("Black", "?"),
+        ]
+        .iter()
+        .map(|&(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+        Self { tags, start, moves, result: PgnResult::Unknown }
+    }
+
+    /// Value of a tag, if present
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets a tag, replacing its value if it is already present
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, existing)) => *existing = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Position after the whole main line has been played
+    pub fn final_position(&self) -> Position {
+        let mut pos = self.start.clone();
+        for &mv in &self.moves {
+            pos.mk_move(mv);
+        }
+        pos
+    }
+}
@@ -0,0 +1,357 @@
+//! PGN reader: tokenizer and main-line parser.
+//!
+//! Comments (`{...}` and `;` to end of line), `%` escape lines, NAGs (`$n`),
+//! move suffix annotations (`!?`) and variations, however deeply nested, are
+//! skipped. Only the main line is kept. Every move is resolved with
+//! [`Position::parse_san`], so an illegal or ambiguous move is an error.
+
+use super::writer::is_derived_tag;
+use super::*;
+use crate::game_repr::{FenError, SanError};
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// What went wrong while reading PGN
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgnErrorKind {
+    /// A tag pair that is not `[Name "value"]`
+    InvalidTag,
+    /// A `{` comment that is never closed
+    UnterminatedComment,
+    /// A `)` without a matching `(`, or a variation still open at the end of
+    /// the game
+    UnbalancedVariation,
+    /// The `FEN` tag does not describe a valid position
+    InvalidFen(FenError),
+    /// A main-line move that cannot be played
+    IllegalMove { san: String, error: SanError },
+}
+
+impl fmt::Display for PgnErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PgnErrorKind::InvalidTag => write!(f, "expected a tag like [Name \"value\"]"),
+            PgnErrorKind::UnterminatedComment => write!(f, "comment is never closed"),
+            PgnErrorKind::UnbalancedVariation => write!(f, "unbalanced parentheses"),
+            PgnErrorKind::InvalidFen(err) => write!(f, "{}", err),
+            PgnErrorKind::IllegalMove { san, error } => write!(f, "move '{}': {}", san, error),
+        }
+    }
+}
+
+/// Error returned by [`parse_pgn`]
+///
+/// `line` is the 1-based line the offending token starts on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnError {
+    pub line: usize,
+    pub kind: PgnErrorKind,
+}
+
+impl PgnError {
+    fn new(line: usize, kind: PgnErrorKind) -> Self {
+        Self { line, kind }
+    }
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid PGN on line {}: {}", self.line, self.kind)
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+/// Reads every game in a PGN file
+///
+/// A game ends at its termination marker (`1-0`, `0-1`, `1/2-1/2`, `*`). A
+/// game without one ends where the next tag section starts; its result is
+/// then taken from the `Result` tag.
+pub fn parse_pgn(text: &str) -> Result<Vec<PgnGame>, PgnError> {
+    let mut lexer = Lexer::new(text);
+    let mut games = Vec::new();
+    let mut game = GameBuilder::default();
+    // Nesting depth of the variation being skipped
+    let mut depth = 0;
+
+    while let Some((line, token)) = lexer.next_token()? {
+        match token {
+            Token::Tag(..) if depth > 0 => {
+                return Err(PgnError::new(line, PgnErrorKind::UnbalancedVariation));
+            }
+            Token::Tag(name, value) => {
+                if game.in_movetext {
+                    games.push(std::mem::take(&mut game).finish(None, line)?);
+                }
+                game.tags.push((name, value));
+            }
+            Token::OpenVariation => {
+                game.in_movetext = true;
+                depth += 1;
+            }
+            Token::CloseVariation => {
+                if depth == 0 {
+                    return Err(PgnError::new(line, PgnErrorKind::UnbalancedVariation));
+                }
+                depth -= 1;
+            }
+            Token::Symbol(_) if depth > 0 => {}
+            Token::Symbol(symbol) => {
+                if let Some(result) = PgnResult::from_marker(&symbol) {
+                    games.push(std::mem::take(&mut game).finish(Some(result), line)?);
+                } else {
+                    game.play(&symbol, line)?;
+                }
+            }
+        }
+    }
+
+    if depth > 0 {
+        return Err(PgnError::new(lexer.line, PgnErrorKind::UnbalancedVariation));
+    }
+    if !game.is_empty() {
+        games.push(game.finish(None, lexer.line)?);
+    }
+
+    Ok(games)
+}
+
+// ==================== PARSER ====================
+
+/// A game whose tags and moves are still being read
+#[derive(Default)]
+struct GameBuilder {
+    tags: Vec<(String, String)>,
+    /// Start position and current position, set up on the first move
+    positions: Option<(Position, Position)>,
+    moves: Vec<Move>,
+    in_movetext: bool,
+}
+
+impl GameBuilder {
+    fn is_empty(&self) -> bool {
+        self.tags.is_empty() && !self.in_movetext
+    }
+
+    fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Start position from the `FEN` and `Variant` tags
+    fn start_position(&self, line: usize) -> Result<Position, PgnError> {
+        let mut pos = match self.tag("FEN") {
+            Some(fen) => Position::try_from_fen(fen)
+                .map_err(|err| PgnError::new(line, PgnErrorKind::InvalidFen(err)))?,
+            None => Position::default(),
+        };
+
+        if let Some(variant) = self.tag("Variant") {
+            let variant = variant.to_ascii_lowercase();
+            if variant.contains("960") || variant == "fischerandom" {
+                pos.chess960 = true;
+            }
+        }
+
+        Ok(pos)
+    }
+
+    fn current_position(&mut self, line: usize) -> Result<&mut Position, PgnError> {
+        if self.positions.is_none() {
+            let start = self.start_position(line)?;
+            self.positions = Some((start.clone(), start));
+        }
+        Ok(&mut self.positions.as_mut().unwrap().1)
+    }
+
+    /// Plays a movetext symbol, ignoring move numbers and annotation glyphs
+    fn play(&mut self, symbol: &str, line: usize) -> Result<(), PgnError> {
+        self.in_movetext = true;
+
+        let san = strip_move_number(symbol);
+        if san.chars().all(|c| c == '!' || c == '?') {
+            return Ok(());
+        }
+
+        let pos = self.current_position(line)?;
+        let mv = pos.parse_san(san).map_err(|error| {
+            PgnError::new(line, PgnErrorKind::IllegalMove { san: san.to_string(), error })
+        })?;
+        pos.mk_move(mv);
+        self.moves.push(mv);
+        Ok(())
+    }
+
+    fn finish(mut self, result: Option<PgnResult>, line: usize) -> Result<PgnGame, PgnError> {
+        self.current_position(line)?;
+        let (start, _) = self.positions.take().unwrap();
+
+        let result = result
+            .or_else(|| self.tag("Result").and_then(PgnResult::from_marker))
+            .unwrap_or(PgnResult::Unknown);
+        self.tags.retain(|(name, _)| !is_derived_tag(name));
+
+        Ok(PgnGame { tags: self.tags, start, moves: self.moves, result })
+    }
+}
+
+/// Removes a leading move number indication (`12.`, `12...`), which may be
+/// written directly in front of the move
+fn strip_move_number(symbol: &str) -> &str {
+    let rest = symbol.trim_start_matches(|c: char| c.is_ascii_digit());
+    if rest.len() < symbol.len() && rest.starts_with('.') {
+        rest.trim_start_matches('.')
+    } else {
+        symbol
+    }
+}
+
+// ==================== TOKENIZER ====================
+
+enum Token {
+    Tag(String, String),
+    /// Move, move number or game termination marker
+    Symbol(String),
+    OpenVariation,
+    CloseVariation,
+}
+
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+    /// Current 1-based line
+    line: usize,
+    at_line_start: bool,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { chars: text.chars().peekable(), line: 1, at_line_start: true }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.at_line_start = true;
+        } else {
+            self.at_line_start = false;
+        }
+        Some(c)
+    }
+
+    fn skip_line(&mut self) {
+        while self.chars.peek().is_some_and(|&c| c != '\n') {
+            self.bump();
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.chars.peek().is_some_and(|&c| c == ' ' || c == '\t') {
+            self.bump();
+        }
+    }
+
+    /// Next token with the line it starts on, skipping whitespace, comments
+    /// and NAGs
+    fn next_token(&mut self) -> Result<Option<(usize, Token)>, PgnError> {
+        loop {
+            let Some(&c) = self.chars.peek() else {
+                return Ok(None);
+            };
+            let line = self.line;
+
+            if c == '%' && self.at_line_start {
+                self.skip_line();
+                continue;
+            }
+
+            match c {
+                _ if c.is_whitespace() => {
+                    self.bump();
+                }
+                ';' => self.skip_line(),
+                '{' => loop {
+                    match self.bump() {
+                        Some('}') => break,
+                        Some(_) => {}
+                        None => return Err(PgnError::new(line, PgnErrorKind::UnterminatedComment)),
+                    }
+                },
+                '$' => {
+                    self.bump();
+                    while self.chars.peek().is_some_and(|c| c.is_ascii_digit()) {
+                        self.bump();
+                    }
+                }
+                '(' => {
+                    self.bump();
+                    return Ok(Some((line, Token::OpenVariation)));
+                }
+                ')' => {
+                    self.bump();
+                    return Ok(Some((line, Token::CloseVariation)));
+                }
+                '[' => return self.tag(line).map(Some),
+                _ => {
+                    let mut symbol = String::new();
+                    while let Some(&c) = self.chars.peek() {
+                        if c.is_whitespace() || "{}()[];$".contains(c) {
+                            break;
+                        }
+                        symbol.push(c);
+                        self.bump();
+                    }
+                    return Ok(Some((line, Token::Symbol(symbol))));
+                }
+            }
+        }
+    }
+
+    /// Reads `[Name "value"]`, unescaping `\"` and `\\` in the value
+    fn tag(&mut self, line: usize) -> Result<(usize, Token), PgnError> {
+        let invalid = || PgnError::new(line, PgnErrorKind::InvalidTag);
+
+        self.bump();
+        self.skip_spaces();
+
+        let mut name = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !(c.is_ascii_alphanumeric() || c == '_') {
+                break;
+            }
+            name.push(c);
+            self.bump();
+        }
+        if name.is_empty() {
+            return Err(invalid());
+        }
+
+        self.skip_spaces();
+        if self.bump() != Some('"') {
+            return Err(invalid());
+        }
+
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => break,
+                Some('\\') => match self.bump() {
+                    Some(c) if c != '\n' => value.push(c),
+                    _ => return Err(invalid()),
+                },
+                Some('\n') | None => return Err(invalid()),
+                Some(c) => value.push(c),
+            }
+        }
+
+        self.skip_spaces();
+        if self.bump() != Some(']') {
+            return Err(invalid());
+        }
+
+        Ok((line, Token::Tag(name, value)))
+    }
+}
@@ -0,0 +1,171 @@
+use super::*;
+use crate::game_repr::{MoveType, SanError};
+
+const OPERA_GAME: &str = include_str!("testdata/opera_game.pgn");
+const CHESS960_GAME: &str = include_str!("testdata/chess960.pgn");
+const ANNOTATED: &str = include_str!("testdata/annotated.pgn");
+
+fn parse_one(text: &str) -> PgnGame {
+    let mut games = parse_pgn(text).unwrap();
+    assert_eq!(games.len(), 1);
+    games.remove(0)
+}
+
+fn error_kind(text: &str) -> PgnErrorKind {
+    parse_pgn(text).err().unwrap().kind
+}
+
+// ==================== ROUND TRIPS ====================
+
+#[test]
+fn test_export_reproduces_file() {
+    // Files already in export format come back byte for byte
+    for text in [OPERA_GAME, CHESS960_GAME] {
+        assert_eq!(parse_one(text).to_pgn(), text);
+    }
+}
+
+#[test]
+fn test_multi_game_file_round_trip() {
+    let games = parse_pgn(ANNOTATED).unwrap();
+    assert_eq!(games.len(), 3);
+
+    let exported: Vec<String> = games.iter().map(PgnGame::to_pgn).collect();
+    let reparsed = parse_pgn(&exported.join("\n")).unwrap();
+    assert_eq!(reparsed.len(), games.len());
+
+    for (game, again) in games.iter().zip(&reparsed) {
+        assert_eq!(again.tags, game.tags);
+        assert_eq!(again.moves, game.moves);
+        assert_eq!(again.result, game.result);
+        assert_eq!(again.start.to_fen(), game.start.to_fen());
+    }
+}
+
+// ==================== READING ====================
+
+#[test]
+fn test_read_opera_game() {
+    let game = parse_one(OPERA_GAME);
+
+    assert_eq!(game.tag("White"), Some("Paul Morphy"));
+    assert_eq!(game.tag("ECO"), Some("C41"));
+    assert_eq!(game.tag("Result"), None);
+    assert_eq!(game.result, PgnResult::WhiteWins);
+    assert_eq!(game.moves.len(), 33);
+
+    let end = game.final_position();
+    assert!(end.is_checkmate(end.side_to_move));
+}
+
+#[test]
+fn test_read_skips_comments_nags_and_variations() {
+    let games = parse_pgn(ANNOTATED).unwrap();
+    let immortal = &games[0];
+
+    assert_eq!(immortal.tag("Black"), Some("Lionel Kieseritzky"));
+    assert_eq!(immortal.moves.len(), 45);
+    assert_eq!(
+        immortal.final_position().to_fen(),
+        "r1bk3r/p2pBpNp/n4n2/1p1NP2P/6P1/3P4/P1P1K3/q5b1 b - - 1 23"
+    );
+
+    assert_eq!(games[1].tag("Black"), Some("Jean Dufresne"));
+    assert_eq!(games[1].result, PgnResult::WhiteWins);
+    assert_eq!(games[2].result, PgnResult::Unknown);
+    assert_eq!(games[2].moves.len(), 6);
+}
+
+#[test]
+fn test_read_chess960_game() {
+    let game = parse_one(CHESS960_GAME);
+
+    assert!(game.start.chess960);
+    assert_eq!(game.start.castling_squares, [7, 2, 6, 63, 58, 62]);
+    let castles = game.moves.iter().filter(|mv| mv.move_type() == MoveType::Castling).count();
+    assert_eq!(castles, 2);
+    assert_eq!(
+        game.final_position().to_fen(),
+        "bbrq1rk1/pp2p2p/4nnp1/3p1p2/3P1P2/4NNP1/PP2P2P/BBRQ1RK1 w - - 0 9"
+    );
+}
+
+#[test]
+fn test_read_lenient_movetext() {
+    // No tags, move numbers glued to moves, black move numbers, castling
+    // with zeros, a result taken from the tag and no termination marker
+    let game = parse_one("1.e4 e5 2.Nf3 2...Nc6 3.Bc4 Bc5 4.0-0 Nf6");
+    assert_eq!(game.moves.len(), 8);
+    assert_eq!(game.moves[6].move_type(), MoveType::Castling);
+    assert_eq!(game.result, PgnResult::Unknown);
+
+    let game = parse_one("[Result \"1/2-1/2\"]\n\n1. e4 e5");
+    assert_eq!(game.result, PgnResult::Draw);
+
+    // A game without a termination marker ends where the next tags start
+    let games = parse_pgn("[Event \"A\"]\n1. e4\n\n[Event \"B\"]\n1. d4 *").unwrap();
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[1].tag("Event"), Some("B"));
+}
+
+#[test]
+fn test_read_errors() {
+    assert_eq!(
+        parse_pgn("[Event \"?\"]\n\n1. e4 e5 2. Ke3").err(),
+        Some(PgnError {
+            line: 3,
+            kind: PgnErrorKind::IllegalMove { san: "Ke3".to_string(), error: SanError::IllegalMove },
+        })
+    );
+    assert_eq!(error_kind("1. e4 (1. d4 e5"), PgnErrorKind::UnbalancedVariation);
+    assert_eq!(error_kind("1. e4 e5) 2. Nf3"), PgnErrorKind::UnbalancedVariation);
+    assert_eq!(error_kind("1. e4 {unclosed"), PgnErrorKind::UnterminatedComment);
+    assert_eq!(error_kind("[Event ?]"), PgnErrorKind::InvalidTag);
+    assert_eq!(error_kind("[Event \"unclosed]\n1. e4"), PgnErrorKind::InvalidTag);
+    assert!(matches!(
+        error_kind("[FEN \"8/8/8/8/8/8/8/8 w - - 0 1\"]\n1. e4"),
+        PgnErrorKind::InvalidFen(_)
+    ));
+}
+
+// ==================== WRITING ====================
+
+#[test]
+fn test_write_game_from_custom_position() {
+    let start = Position::try_from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 40").unwrap();
+    let mut pos = start.clone();
+    let moves: Vec<Move> = ["Kd7", "e4", "Kc6"]
+        .iter()
+        .map(|san| {
+            let mv = pos.parse_san(san).unwrap();
+            pos.mk_move(mv);
+            mv
+        })
+        .collect();
+
+    let mut game = PgnGame::new(start, moves);
+    game.set_tag("White", "Fischer \"Bobby\"");
+    game.set_tag("Annotator", "C:\\games");
+
+    let text = game.to_pgn();
+    assert!(text.contains("[White \"Fischer \\\"Bobby\\\"\"]\n"));
+    assert!(text.contains("[Result \"*\"]\n[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 40\"]\n"));
+    assert!(text.ends_with("\n40... Kd7 41. e4 Kc6 *\n"));
+
+    let again = parse_one(&text);
+    assert_eq!(again.tag("White"), Some("Fischer \"Bobby\""));
+    assert_eq!(again.tag("Annotator"), Some("C:\\games"));
+    assert_eq!(again.moves, game.moves);
+}
+
+#[test]
+fn test_write_empty_game_has_seven_tag_roster() {
+    let mut game = PgnGame::new(Position::default(), Vec::new());
+    game.result = GameResult::Stalemate.into();
+
+    assert_eq!(
+        game.to_pgn(),
+        "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n\
+         [White \"?\"]\n[Black \"?\"]\n[Result \"1/2-1/2\"]\n\n1/2-1/2\n"
+    );
+}
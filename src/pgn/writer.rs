@@ -0,0 +1,91 @@
+//! PGN export format writer.
+
+use super::*;
+use crate::game_repr::Color;
+
+/// Movetext lines are wrapped at this many characters
+const LINE_WIDTH: usize = 80;
+
+impl PgnGame {
+    /// Writes the game in PGN export format
+    ///
+    /// Tags come first, starting with the Seven Tag Roster (missing roster
+    /// tags are written as unknown), followed by the main line in SAN and the
+    /// result. The text ends with a newline.
+    pub fn to_pgn(&self) -> String {
+        let mut out = String::new();
+
+        for name in SEVEN_TAG_ROSTER {
+            let value = match name {
+                "Result" => self.result.as_str(),
+                "Date" => self.tag(name).unwrap_or("????.??.??"),
+                _ => self.tag(name).unwrap_or("?"),
+            };
+            write_tag(&mut out, name, value);
+        }
+
+        let standard_fen = Position::default().to_fen();
+        let start_fen = self.start.to_fen();
+        if start_fen != standard_fen || self.start.chess960 {
+            write_tag(&mut out, "SetUp", "1");
+            write_tag(&mut out, "FEN", &start_fen);
+        }
+        if self.start.chess960 && self.tag("Variant").is_none() {
+            write_tag(&mut out, "Variant", "Chess960");
+        }
+
+        for (name, value) in &self.tags {
+            if !SEVEN_TAG_ROSTER.contains(&name.as_str()) && !is_derived_tag(name) {
+                write_tag(&mut out, name, value);
+            }
+        }
+
+        out.push('\n');
+        out.push_str(&self.movetext());
+        out.push('\n');
+        out
+    }
+
+    /// Main line and result, wrapped at [`LINE_WIDTH`]
+    fn movetext(&self) -> String {
+        let mut tokens = Vec::with_capacity(self.moves.len() + 1);
+        let mut pos = self.start.clone();
+
+        // A move number is kept on the same line as the move it belongs to
+        for (ply, &mv) in self.moves.iter().enumerate() {
+            let san = pos.move_to_san(mv);
+            tokens.push(match pos.side_to_move {
+                Color::White => format!("{}. {}", pos.fullmove_number, san),
+                Color::Black if ply == 0 => format!("{}... {}", pos.fullmove_number, san),
+                Color::Black => san,
+            });
+            pos.mk_move(mv);
+        }
+        tokens.push(self.result.as_str().to_string());
+
+        let mut text = String::new();
+        let mut line_len = 0;
+        for token in tokens {
+            if line_len > 0 && line_len + 1 + token.len() > LINE_WIDTH {
+                text.push('\n');
+                line_len = 0;
+            } else if line_len > 0 {
+                text.push(' ');
+                line_len += 1;
+            }
+            line_len += token.len();
+            text.push_str(&token);
+        }
+        text
+    }
+}
+
+/// Tags written from the game's result and start position rather than `tags`
+pub(super) fn is_derived_tag(name: &str) -> bool {
+    matches!(name, "Result" | "SetUp" | "FEN")
+}
+
+fn write_tag(out: &mut String, name: &str, value: &str) {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    out.push_str(&format!("[{} \"{}\"]\n", name, escaped));
+}
@@ -57,7 +57,8 @@ pub trait Renderer {
     ///
     /// # Arguments
     /// * `state` - The current menu state to render
-    fn draw_menu_state(&mut self, state: &MenuState);
+    /// * `chess960` - Whether the Chess960 toggle is on
+    fn draw_menu_state(&mut self, state: &MenuState, chess960: bool);
 
     /// Get the current window size (for Menu coordinate conversion)
     fn window_size(&self) -> (u32, u32);
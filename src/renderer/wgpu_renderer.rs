@@ -1,7 +1,7 @@
 use crate::agent::ai::Difficulty;
 use crate::agent::player::GameResult;
 use crate::assets;
-use crate::game_repr::{Color, Piece, Position, Type};
+use crate::game_repr::{Color, MoveType, Piece, Position, Type};
 use crate::menu::{layout, MenuState};
 use crate::orchestrator::AISetupButton;
 use crate::renderer::Renderer;
@@ -770,7 +770,7 @@ impl WgpuRenderer {
     }
 
     /// Prepare text areas for ModeSelection state
-    fn prepare_mode_selection_text(&mut self, viewport_width: f32, viewport_height: f32) -> Vec<OwnedTextArea> {
+    fn prepare_mode_selection_text(&mut self, viewport_width: f32, viewport_height: f32, chess960: bool) -> Vec<OwnedTextArea> {
         let mut result = Vec::new();
 
         // PvP button text
@@ -812,6 +812,20 @@ impl WgpuRenderer {
             color: glyphon::Color::rgb(0, 0, 0),
         });
 
+        // Chess960 toggle text
+        let toggle_label = if chess960 { "Chess960: On" } else { "Chess960: Off" };
+        let mut toggle_buffer = Buffer::new(&mut self.font_system, Metrics::new(24.0, 30.0));
+        toggle_buffer.set_size(&mut self.font_system, Some(viewport_width), Some(viewport_height));
+        toggle_buffer.set_text(&mut self.font_system, toggle_label, Attrs::new().family(Family::SansSerif), glyphon::Shaping::Advanced);
+        let toggle_width = self.get_text_width(&toggle_buffer);
+        let toggle_center_y = self.ndc_to_screen_y((layout::main_menu::CHESS960.top + layout::main_menu::CHESS960.bottom()) / 2.0, viewport_height);
+        result.push(OwnedTextArea {
+            buffer: toggle_buffer,
+            left: self.center_text_x(toggle_width, viewport_width),
+            top: toggle_center_y - 12.0,
+            color: glyphon::Color::rgb(255, 255, 255),
+        });
+
         result
     }
 
@@ -1146,10 +1160,19 @@ impl Renderer for WgpuRenderer {
                 render_pass.set_pipeline(&self.dot_pipeline);
                 render_pass.set_bind_group(0, dot_bind_group, &[]);
 
-                for _move in legal_moves {
+                // Castling shows a dot on the rook it takes and on the king's landing square
+                let targets = legal_moves.iter().flat_map(|_move| {
+                    let landing = match _move.move_type() {
+                        MoveType::Castling => Some(_move.castling_destinations().0),
+                        _ => None,
+                    };
+                    std::iter::once(_move._to()).chain(landing)
+                });
+
+                for target in targets {
                     let idx = match pov {
-                        Color::White => _move._to(),
-                        Color::Black => 63 - _move._to(),
+                        Color::White => target,
+                        Color::Black => 63 - target,
                     };
 
                     let vertices = self.create_dot_quad(idx);
@@ -1766,7 +1789,11 @@ impl Renderer for WgpuRenderer {
                 // Dark background for black wins
                 ([0.15, 0.15, 0.15, 0.95], "Black Wins!", glyphon::Color::rgb(240, 240, 240))
             }
-            GameResult::Draw | GameResult::Stalemate => {
+            GameResult::Draw
+            | GameResult::Stalemate
+            | GameResult::ThreefoldRepetition
+            | GameResult::FiftyMoveRule
+            | GameResult::SeventyFiveMoveRule => {
                 // Neutral gray background for draw/stalemate
                 ([0.55, 0.55, 0.55, 0.95], "Draw!", glyphon::Color::rgb(240, 240, 240))
             }
@@ -3027,7 +3054,7 @@ impl Renderer for WgpuRenderer {
         self.window_size
     }
 
-    fn draw_menu_state(&mut self, state: &MenuState) {
+    fn draw_menu_state(&mut self, state: &MenuState, chess960: bool) {
         let output = self.surface.get_current_texture().unwrap();
         let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
 
@@ -3065,6 +3092,12 @@ impl Renderer for WgpuRenderer {
                     self.draw_button_rect(&mut render_pass, &layout::main_menu::PVP, layout::colors::PVP);
                     self.draw_button_rect(&mut render_pass, &layout::main_menu::PVAI, layout::colors::PVAI);
                     self.draw_button_rect(&mut render_pass, &layout::main_menu::AIVAI, layout::colors::AIVAI);
+                    let toggle_color = if chess960 {
+                        layout::colors::CHESS960_ON
+                    } else {
+                        layout::colors::CHESS960_OFF
+                    };
+                    self.draw_button_rect(&mut render_pass, &layout::main_menu::CHESS960, toggle_color);
                 }
                 MenuState::SideSelection => {
                     self.draw_button_rect(&mut render_pass, &layout::side_selection::WHITE, layout::colors::SIDE_WHITE);
@@ -3123,7 +3156,7 @@ impl Renderer for WgpuRenderer {
         // Create text buffers based on state
         let text_areas = match state {
             MenuState::ModeSelection => {
-                self.prepare_mode_selection_text(viewport_width, viewport_height)
+                self.prepare_mode_selection_text(viewport_width, viewport_height, chess960)
             }
             MenuState::SideSelection => {
                 self.prepare_side_selection_text(viewport_width, viewport_height)